@@ -1,5 +1,322 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
+/// Arithmetic on amounts. The contract uses the `U256` balances of
+/// pallet-revive by default; with the `u128-balance` feature it keeps amounts
+/// in the classic `u128` `Balance` instead, converting at the environment
+/// boundary. Products are taken in 256 bits either way.
+pub mod math {
+    use ink::U256;
+
+    /// The type of every amount the contract stores or is passed.
+    #[cfg(not(feature = "u128-balance"))]
+    pub type Amount = U256;
+    /// The type of every amount the contract stores or is passed.
+    #[cfg(feature = "u128-balance")]
+    pub type Amount = u128;
+
+    #[cfg(not(feature = "u128-balance"))]
+    pub const ZERO: Amount = U256::zero();
+    #[cfg(feature = "u128-balance")]
+    pub const ZERO: Amount = 0;
+
+    #[cfg(not(feature = "u128-balance"))]
+    pub const ONE: Amount = U256::one();
+    #[cfg(feature = "u128-balance")]
+    pub const ONE: Amount = 1;
+
+    /// `U256::is_zero` for the classic balance type.
+    #[cfg(feature = "u128-balance")]
+    pub trait IsZero {
+        fn is_zero(&self) -> bool;
+    }
+
+    #[cfg(feature = "u128-balance")]
+    impl IsZero for u128 {
+        fn is_zero(&self) -> bool {
+            *self == 0
+        }
+    }
+
+    /// An amount received from the environment, or `None` if it does not
+    /// fit.
+    #[cfg(not(feature = "u128-balance"))]
+    pub fn from_native(value: U256) -> Option<Amount> {
+        Some(value)
+    }
+    /// An amount received from the environment, or `None` if it does not
+    /// fit.
+    #[cfg(feature = "u128-balance")]
+    pub fn from_native(value: U256) -> Option<Amount> {
+        u128::try_from(value).ok()
+    }
+
+    /// `value` as an amount. Traps if it does not fit, as overflowing
+    /// arithmetic on amounts does.
+    #[cfg(not(feature = "u128-balance"))]
+    fn narrow(value: U256) -> Amount {
+        value
+    }
+    /// `value` as an amount. Traps if it does not fit, as overflowing
+    /// arithmetic on amounts does.
+    #[cfg(feature = "u128-balance")]
+    fn narrow(value: U256) -> Amount {
+        value.as_u128()
+    }
+
+    /// An amount to hand to the environment.
+    #[cfg_attr(not(feature = "u128-balance"), allow(clippy::useless_conversion))]
+    pub fn to_native(amount: Amount) -> U256 {
+        U256::from(amount)
+    }
+
+    /// `amount * numerator / denominator`, rounding down. Traps if the
+    /// result does not fit.
+    pub fn mul_div(amount: Amount, numerator: Amount, denominator: Amount) -> Amount {
+        narrow(to_native(amount) * to_native(numerator) / to_native(denominator))
+    }
+
+    /// `bps` basis points of `amount`, rounding down.
+    pub fn bps(amount: Amount, bps: u16) -> Amount {
+        mul_div(amount, Amount::from(bps), Amount::from(10_000u16))
+    }
+
+    /// `part` as basis points of `whole`, rounding down.
+    pub fn to_bps(part: Amount, whole: Amount) -> Amount {
+        mul_div(part, Amount::from(10_000u16), whole)
+    }
+}
+
+/// Interface for contracts that vouch for accounts, such as KYC providers,
+/// proof-of-personhood registries or charity registries.
+#[ink::trait_definition]
+pub trait AttestationVerifier {
+    /// Whether `account` holds the attestation this verifier issues.
+    #[ink(message)]
+    fn has_attestation(&self, account: ink::H160) -> bool;
+}
+
+/// Interface for contracts that want to follow a wish's activity. Calls are
+/// best-effort: a failing or out-of-gas observer does not affect the wishlist.
+#[ink::trait_definition]
+pub trait WishObserver {
+    /// Called after `amount` was contributed to wish `id`.
+    #[ink(message)]
+    fn on_contribution(&mut self, id: u32, contributor: ink::H160, amount: crate::math::Amount);
+
+    /// Called once wish `id` is settled, with the pot it held.
+    #[ink(message)]
+    fn on_settlement(&mut self, id: u32, pot: crate::math::Amount);
+}
+
+/// Custom behaviour for a wish type, provided by an admin-approved extension
+/// contract and consulted at fixed points in a wish's life.
+#[ink::trait_definition]
+pub trait WishExtension {
+    /// Whether a wish of this type may be created.
+    #[ink(message)]
+    fn validate_wish(&self, owner: ink::H160, target: crate::math::Amount, end_date: u64) -> bool;
+
+    /// Whether `contributor` may add `amount` to wish `id`.
+    #[ink(message)]
+    fn validate_contribution(
+        &self,
+        id: u32,
+        contributor: ink::H160,
+        amount: crate::math::Amount,
+    ) -> bool;
+
+    /// Whether the owner may claim wish `id`, replacing the target check.
+    #[ink(message)]
+    fn claimable(&self, id: u32, raised: crate::math::Amount, target: crate::math::Amount) -> bool;
+}
+
+/// Errors of a PSP22 token, as far as the wishlist needs to tell them apart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub enum PSP22Error {
+    Custom(ink::prelude::string::String),
+    InsufficientBalance,
+    InsufficientAllowance,
+    ZeroRecipientAddress,
+    ZeroSenderAddress,
+    SafeTransferCheckFailed(ink::prelude::string::String),
+}
+
+/// The part of the PSP22 token standard the wishlist calls.
+#[ink::trait_definition]
+pub trait PSP22 {
+    /// Move `value` of the caller's tokens to `to`.
+    #[ink(message)]
+    fn transfer(
+        &mut self,
+        to: ink::H160,
+        value: crate::math::Amount,
+        data: ink::prelude::vec::Vec<u8>,
+    ) -> Result<(), PSP22Error>;
+
+    /// Move `value` of `from`'s tokens to `to` out of the caller's
+    /// allowance.
+    #[ink(message)]
+    fn transfer_from(
+        &mut self,
+        from: ink::H160,
+        to: ink::H160,
+        value: crate::math::Amount,
+        data: ink::prelude::vec::Vec<u8>,
+    ) -> Result<(), PSP22Error>;
+}
+
+/// Why a receiver refused a PSP22 transfer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub enum PSP22ReceiverError {
+    TransferRejected(ink::prelude::string::String),
+}
+
+/// Hook a PSP22 token calls on a receiving contract while transferring to
+/// it, so the transfer and its purpose arrive in one transaction.
+#[ink::trait_definition]
+pub trait PSP22Receiver {
+    /// Called by the token as `value` is sent from `from` by `operator`.
+    /// Returning an error reverts the transfer.
+    #[ink(message)]
+    fn before_received(
+        &mut self,
+        operator: ink::H160,
+        from: ink::H160,
+        value: crate::math::Amount,
+        data: ink::prelude::vec::Vec<u8>,
+    ) -> Result<(), PSP22ReceiverError>;
+}
+
+/// A PSP34 token id.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub enum Id {
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    Bytes(ink::prelude::vec::Vec<u8>),
+}
+
+/// Errors of a PSP34 collection, as far as the wishlist needs to tell them
+/// apart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub enum PSP34Error {
+    Custom(ink::prelude::string::String),
+    SelfApprove,
+    NotApproved,
+    TokenExists,
+    TokenNotExists,
+    SafeTransferCheckFailed(ink::prelude::string::String),
+}
+
+/// The part of the PSP34 NFT standard the wishlist calls.
+#[ink::trait_definition]
+pub trait PSP34 {
+    /// Move token `id`, owned by the caller, to `to`.
+    #[ink(message)]
+    fn transfer(
+        &mut self,
+        to: ink::H160,
+        id: Id,
+        data: ink::prelude::vec::Vec<u8>,
+    ) -> Result<(), PSP34Error>;
+}
+
+/// Why a receiver refused a PSP34 transfer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub enum PSP34ReceiverError {
+    TransferRejected(ink::prelude::string::String),
+}
+
+/// Hook a PSP34 collection calls on a receiving contract while transferring
+/// a token to it.
+#[ink::trait_definition]
+pub trait PSP34Receiver {
+    /// Called by the collection as token `id` is sent from `from` by
+    /// `operator`. Returning an error reverts the transfer.
+    #[ink(message)]
+    fn before_received(
+        &mut self,
+        operator: ink::H160,
+        from: ink::H160,
+        id: Id,
+        data: ink::prelude::vec::Vec<u8>,
+    ) -> Result<(), PSP34ReceiverError>;
+}
+
+/// Why a marketplace refused a purchase.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub enum MarketplaceError {
+    ListingNotFound,
+    PriceMismatch,
+    Custom(ink::prelude::string::String),
+}
+
+/// A marketplace a wish can buy its item from when it is claimed.
+#[ink::trait_definition]
+pub trait Marketplace {
+    /// Current price of `listing`, if it is still for sale.
+    #[ink(message)]
+    fn price(&self, listing: u128) -> Option<crate::math::Amount>;
+
+    /// Buy `listing` for the transferred value and deliver the asset to
+    /// `recipient`.
+    #[ink(message, payable)]
+    fn buy(&mut self, listing: u128, recipient: ink::H160) -> Result<(), MarketplaceError>;
+}
+
+/// The core wish lifecycle, for other contracts (a DAO treasury, say) to
+/// call through `ink::contract_ref!(WishlistCore)` or the generated
+/// `WishlistRef`.
+/// Selectors are pinned to the message names alone, as they were before the
+/// trait existed, so existing callers and the call metrics are unaffected.
+#[ink::trait_definition]
+pub trait WishlistCore {
+    /// Create a wish for the caller, seeded with the transferred value.
+    #[ink(message, payable, selector = 0x89FF1CE6)]
+    fn add_wishlist_item(
+        &mut self,
+        description: ink::prelude::string::String,
+        end_date: u64,
+        target: crate::math::Amount,
+    ) -> Result<(), wishlist::Error>;
+
+    /// Contribute the transferred value to wish `id`.
+    #[ink(message, payable, selector = 0x88F0DE17)]
+    fn fund_wish(&mut self, id: u32) -> Result<(), wishlist::Error>;
+
+    /// Pay the pot of wish `id` out to its owner once it has met its target
+    /// and ended.
+    #[ink(message, selector = 0xA800B6A1)]
+    fn claim_wish(&mut self, id: u32) -> Result<(), wishlist::Error>;
+
+    /// Withdraw the caller's share of failed wish `id`.
+    #[ink(message, selector = 0x1F5206A6)]
+    fn claim_refund(&mut self, id: u32) -> Result<(), wishlist::Error>;
+
+    /// Wish `id` while it is active, or its settlement record once settled.
+    #[ink(message, selector = 0xC3F88462)]
+    fn get_wishlist_item(&self, id: u32) -> Result<wishlist::WishEntry, wishlist::Error>;
+
+    /// Where wish `id` is in its lifecycle, `None` if it never existed.
+    #[ink(message, selector = 0xB8D0032A)]
+    fn get_wish_status(&self, id: u32) -> Option<wishlist::WishStatus>;
+
+    /// What `account` has contributed to wish `id`.
+    #[ink(message, selector = 0xE79557BC)]
+    fn get_contribution(&self, id: u32, account: ink::H160) -> crate::math::Amount;
+}
+
+pub use crate::wishlist::{Error, SettlementRecord, WishEntry, WishListItem, WishStatus};
+
 /**
  * This is a simple ink! contract that tracks user contributions towards a wishlist.
  * Users can create a wishlist of how much tokens they want to contribute.
@@ -13,466 +330,11848 @@
  *
  * @Storage
  * - next_item_id: u32
- * - items_by_id: Mapping<Account, Vec<WishListItem>>
- * - items: StorageVec<WishlistItem>
+ * - items_by_owner: Mapping<Account, Vec<u32>>
+ * - items: Mapping<u32, WishlistItem>
  *
  * @Messages
  * - add_wishlist_item(description: String, goal: Balance, end_date: Timestamp);
  * - claim_wish(id: u32);
  * - split_rewards(id: u32);
  * - get_wishlist_item(id: u32);
+ * - wish_exists(id: u32);
+ * - get_ids(from: u32, to: u32);
+ * - metrics();
  * - get_user_wishes(account: AccountId);
+ * - get_wish_status(id: u32);
+ * - get_wishes_paginated(offset: u32, limit: u32);
+ * - fund_wish_psp22(id: u32, token: AccountId, amount: Balance);
  *
  * - fund_wish(id: u32, owner: AccountId);
+ *
+ * - add_wishlist_item_with_options(description: String, end_date: Timestamp, target: Balance, options: WishOptions);
+ * - spawn_next_occurrence(id: u32);
+ * - stop_recurrence(id: u32);
+ * - pause_wish(id: u32);
+ * - resume_wish(id: u32, extend_deadline: bool);
+ * - close_funding(id: u32);
+ * - reject_contribution(id: u32, contributor: AccountId);
+ * - set_banned(id: u32, account: AccountId, banned: bool);
+ * - set_terms(id: u32, terms: Option<[u8; 32]>);
+ * - fund_wish_with_terms(id: u32, terms: [u8; 32]);
+ * - consent_rollover(id: u32, consent: bool);
+ * - set_rollover_preapproval(approved: bool);
+ * - rollover(id: u32, new_end_date: u64);
+ * - poke(id: u32);
+ * - before_received(operator: AccountId, from: AccountId, value: Balance, data: Vec<u8>);
+ * - set_accepted_token(token: AccountId, accepted: bool);
+ * - withdraw_token_credits(token: AccountId);
+ * - accept_nft(deposit: u32);
+ * - reject_nft(deposit: u32);
+ * - withdraw_nft(deposit: u32);
+ * - set_purchase(id: u32, purchase: Option<Purchase>);
+ * - set_unlisted(id: u32, unlisted: bool);
+ * - pledge(id: u32, amount: Balance);
+ * - convert_pledges(id: u32);
+ * - set_guardians(accounts: Vec<AccountId>, threshold: u32);
+ * - propose_recovery(owner: AccountId, new_owner: AccountId);
+ * - cancel_recovery();
+ * - execute_recovery(owner: AccountId);
+ * - set_manager(id: u32, manager: Option<AccountId>);
+ * - approve_operator(operator: AccountId, approved: bool);
+ * - add_wishlist_item_for(owner: AccountId, description: String, end_date: u64, target: Balance, options: WishOptions);
+ * - gift_wish(recipient: AccountId, description: String, end_date: u64, target: Balance, options: WishOptions);
+ * - accept_gift(id: u32);
+ * - post_update(id: u32, hash: [u8; 32], uri: String);
+ * - extend_deadline(id: u32, by: u64);
+ * - set_rounds(id: u32, rounds: Vec<Round>);
+ * - get_current_round(id: u32);
+ * - commit_contribution(id: u32, hash: [u8; 32]);
+ * - reveal_contribution(id: u32, amount: Balance, salt: [u8; 32]);
+ * - reclaim_commitment(id: u32);
+ * - create_pool(id: u32, target_sum: Balance, deadline: Timestamp);
+ * - join_pool(pool_id: u32);
+ * - refund_pool(pool_id: u32);
+ * - deposit();
+ * - withdraw_deposit(amount: Balance);
+ * - approve_standing_order(id: u32, amount: Balance, period: u64, cap: Balance);
+ * - approve_standing_order_by_permit(contributor: AccountId, id: u32, amount: Balance, period: u64, cap: Balance, deadline: Timestamp, signature: [u8; 65]);
+ * - domain_separator();
+ * - cancel_standing_order(id: u32);
+ * - execute_standing_order(contributor: AccountId, id: u32);
+ * - boost_wish(id: u32);
+ * - get_wishes_sorted(key: SortKey, offset: u32, limit: u32);
+ * - get_expiring_soon(window_ms: u64, offset: u32, limit: u32);
+ * - raise_history(id: u32);
+ * - get_contributions(id: u32, offset: u32, limit: u32);
+ * - get_contribution_count(id: u32);
+ * - get_contributor_activity(id: u32, account: AccountId);
+ * - exit_contribution(id: u32);
+ * - withdraw_contribution(id: u32, amount: Balance);
+ * - withdraw_progress(id: u32, amount: Balance);
+ * - request_early_claim(id: u32);
+ * - vote_early_claim(id: u32, approve: bool);
+ * - revoke_early_claim_vote(id: u32);
+ * - tally_early_claim(id: u32);
+ * - delegate(to: Option<AccountId>);
+ * - get_wish_summary(id: u32);
+ * - get_progress(id: u32);
+ * - storage_stats();
+ * - audit();
+ * - sweep_dust();
+ * - simulate_settlement(id: u32);
+ * - get_refund_amount(id: u32, account: AccountId);
+ * - get_claimable(id: u32);
+ * - claim_all(cursor: u32);
+ * - refund_all(cursor: u32);
+ * - get_settlement(id: u32);
+ * - settlement_history(account: AccountId, offset: u32, limit: u32);
+ * - submit_receipt(id: u32, hash: [u8; 32], uri: String);
+ * - get_reputation(owner: AccountId);
+ * - file_spend_report(id: u32, amount: Balance, category: SpendCategory, memo_hash: [u8; 32]);
+ * - set_moderator(account: AccountId, enabled: bool);
+ * - set_deposit_policy(policy: DepositPolicy);
+ * - set_verified(id: u32, verified: bool);
+ * - set_max_active_wishes(max: u32);
+ * - set_minimums(min_target: Balance, min_deposit: Balance);
+ * - set_clock(time: Timestamp);
+ * - set_entry_fee(bps: u16, burn: bool);
+ * - set_fee_exempt(account: AccountId, exempt: bool);
+ * - set_claim_fee(bps: u16);
+ * - withdraw_fees(to: AccountId);
+ * - create_campaign(slug: String, ids: Vec<u32>);
+ * - add_to_campaign(campaign_id: u32, id: u32);
+ * - set_verifier(purpose: AttestationPurpose, verifier: Option<AccountId>);
+ * - set_observer(id: u32, observer: Option<AccountId>);
+ * - register_extension(wish_type: u32, extension: Option<AccountId>);
+ * - set_payout_split(id: u32, shares: Vec<PayoutShare>);
+ * - set_verified_charity(account: AccountId, verified: bool);
+ * - confirm_fulfillment(id: u32);
+ * - refund_escrow(id: u32);
+ * - withdraw_payout(id: u32);
+ * - claim_refund(id: u32);
+ * - cancel_wish(id: u32);
+ * - repay(id: u32);
+ * - get_payout(id: u32, account: AccountId);
+ * - force_cancel(id: u32, reason: String);
+ * - appeal_moderation(id: u32);
+ * - resolve_appeal(id: u32, uphold: bool);
+ * - set_appeal_bond(bond: Balance);
+ * - set_arbiter(arbiter: Option<AccountId>);
+ * - propose_admin(new_admin: AccountId);
+ * - accept_admin();
+ * - pause();
+ * - unpause();
+ * - withdraw_credits();
+ * - set_payout_address(payout: Option<AccountId>);
+ * - withdraw_treasury(to: AccountId, amount: Balance);
  */
 
 #[ink::contract]
 mod wishlist {
+    use super::{
+        AttestationVerifier, Id, Marketplace, PSP22Receiver, PSP22ReceiverError, PSP34Receiver,
+        PSP34ReceiverError, WishExtension, WishObserver, WishlistCore, PSP22, PSP34,
+    };
     use ink::{
-        storage::{StorageVec},
+        codegen::TraitCallBuilder,
+        env::hash::Keccak256,
+        storage::{Lazy, Mapping},
         H160, U256,
     };
 
+    #[cfg(feature = "u128-balance")]
+    use super::math::IsZero;
+    use super::math::{self, Amount};
+
     use ink::prelude::{string::String, vec::Vec};
 
     #[ink(event)]
     pub struct WishlistAdded {
         #[ink(topic)]
         id: u32,
+        #[ink(topic)]
         owner: H160,
+        target: Amount,
+        end_date: u64,
+        funding_model: FundingModel,
+        /// Keccak-256 hash of the description.
+        description_hash: [u8; 32],
     }
 
-    /// Errors that can occur upon calling this contract.
-    #[derive(Debug, PartialEq, Eq)]
-    #[ink::scale_derive(Encode, Decode, TypeInfo)]
-    pub enum Error {
-        /// Returned if the name already exists upon registration.
-        InvalidContribution,
-        /// Returned if wish does not exist.
-        WishNotFound,
-        /// Invalid Target amount
-        InvalidTarget,
+    #[ink(event)]
+    pub struct WishRecurred {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        owner: H160,
+        next_id: u32,
     }
 
-    /// Type alias for the contract's result type.
-    pub type Result<T> = core::result::Result<T, Error>;
+    #[ink(event)]
+    pub struct InstallmentExecuted {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        contributor: H160,
+        amount: Amount,
+    }
 
-    #[cfg_attr(
-        feature = "std",
-        derive(Debug, PartialEq, Eq, ink::storage::traits::StorageLayout)
-    )]
-    #[ink::scale_derive(Encode, Decode, TypeInfo)]
-    pub struct WishListItem {
+    #[ink(event)]
+    pub struct StandingOrderStopped {
+        #[ink(topic)]
         id: u32,
-        description: String,
-        owner: H160,
-        target: U256,
-        end_date: u64,
-        raised: U256,
-        contributors: Vec<(H160, U256)>,
+        #[ink(topic)]
+        contributor: H160,
     }
 
-    /// Defines the storage of your contract.
-    /// Add new fields to the below struct in order
-    /// to add new static storage fields to your contract.
-    #[ink(storage)]
-    pub struct Wishlist {
-        /// Stores the id of the next wishlist item.
-        /// The current length will be (next_item_id - 1)
-        next_item_id: u32,
-        // items_by_id: Mapping<H160, Vec<WishListItem>>,
-        items: StorageVec<Option<WishListItem>>,
+    #[ink(event)]
+    pub struct TokenContributed {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        token: H160,
+        #[ink(topic)]
+        contributor: H160,
+        amount: Amount,
     }
 
-    impl Wishlist {
-        /// Constructor that initializes the contract.
-        #[ink(constructor)]
-        pub fn new() -> Self {
-            Self {
-                next_item_id: 1,
-                items: StorageVec::new(),
-            }
-        }
+    #[ink(event)]
+    pub struct NftDeposited {
+        #[ink(topic)]
+        id: u32,
+        deposit: u32,
+        #[ink(topic)]
+        contributor: H160,
+        #[ink(topic)]
+        collection: H160,
+        token_id: Id,
+        /// What the contributor declared the NFT to be worth.
+        valuation: Amount,
+    }
 
-        /// Constructor that initializes the `bool` value to `false`.
-        ///
-        /// Constructors can delegate to other constructors.
-        #[ink(constructor)]
-        pub fn default() -> Self {
-            Self::new()
-        }
+    /// Emitted when the owner accepts or rejects a deposited NFT.
+    #[ink(event)]
+    pub struct NftReviewed {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        contributor: H160,
+        deposit: u32,
+        accepted: bool,
+    }
 
-        /// add a wishlist item to the accountId
-        #[ink(message, payable)]
-        pub fn add_wishlist_item(
-            &mut self,
-            description: String,
-            end_date: u64,
-            target: U256,
-        ) -> Result<()> {
-            let caller = self.env().caller();
-            let value = self.env().transferred_value();
+    /// Emitted when a claimed wish bought its item from a marketplace.
+    #[ink(event)]
+    pub struct WishPurchased {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        marketplace: H160,
+        listing: u128,
+        price: Amount,
+        /// What was left of the pot, refunded pro rata.
+        leftover: Amount,
+    }
 
-            // Ensure target is not 0
-            if target <= U256::zero() {
-                return Err(Error::InvalidTarget);
-            }
+    #[ink(event)]
+    pub struct LoanRepaid {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        payer: H160,
+        amount: Amount,
+        /// What is still owed after this repayment.
+        outstanding: Amount,
+    }
 
-            // The trasferred_value > 10% of target
-            let ten_percent = (target * U256::from(10)) / U256::from(100);
-            if value < ten_percent {
-                return Err(Error::InvalidContribution);
-            }
-            let item_count = self.next_item_id;
+    /// A wish was claimed by its owner.
+    #[ink(event)]
+    pub struct WishClaimed {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        owner: H160,
+        /// The pot the claim settled, including any part held in escrow.
+        amount: Amount,
+        timestamp: u64,
+    }
 
-            let wishlist = WishListItem {
-                id: item_count,
-                description,
-                owner: caller,
-                target,
-                end_date,
-                raised: value,
-                contributors: Vec::new(),
-            };
+    #[ink(event)]
+    pub struct AutoClaimed {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        owner: H160,
+        pot: Amount,
+    }
 
-            self.next_item_id = self
-                .next_item_id
-                .checked_add(1)
-                .ok_or(Error::InvalidContribution)?;
-            self.items.push(&Some(wishlist));
-            self.env().emit_event(WishlistAdded {
-                id: item_count,
-                owner: caller,
-            });
+    #[ink(event)]
+    pub struct WishRolledOver {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        successor: u32,
+        #[ink(topic)]
+        owner: H160,
+        /// Contributions carried into the successor.
+        carried: Amount,
+    }
 
-            Ok(())
-        }
+    #[ink(event)]
+    pub struct TermsAccepted {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        contributor: H160,
+        terms: TermsHash,
+    }
 
-        #[ink(message, payable)]
-        pub fn fund_wish(&mut self, id: u32) -> Result<()> {
-            let caller = self.get_caller();
-            let value = self.env().transferred_value();
-            if value <= U256::zero() {
-                return Err(Error::InvalidContribution);
-            }
+    #[ink(event)]
+    pub struct ContributorBanned {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        account: H160,
+        banned: bool,
+    }
 
-            let wishlist = self.items.get(id);
-            match wishlist {
-                None => Err(Error::WishNotFound),
-                Some(item) => {
-                    let mut item = item.unwrap();
-                    if caller == item.owner {
-                        // If owner is funding, update the raised amount
-                        item.raised = item.raised + value;
-                        self.items.set(id, &Some(item));
-                    } else {
-                        // If contributor exists, update contribution
-                        let contributor_exists = item.contributors.iter().any(|c| c.0 == caller);
-                        if contributor_exists {
-                            let contributors: Vec<(H160, U256)> = item
-                                .contributors
-                                .iter_mut()
-                                .map(|c| {
-                                    if c.0 == caller {
-                                        c.1 = c.1 + value;
-                                    }
-                                    return *c;
-                                })
-                                .collect::<Vec<(H160, U256)>>();
-
-                            item.contributors = contributors.clone();
-                        } else {
-                            // If contributor does not exist, add to contributors
-                            item.contributors.push((caller, value));
-                        }
+    #[ink(event)]
+    pub struct ContributionRejected {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        contributor: H160,
+        refunded: Amount,
+    }
 
-                        self.items.set(id, &Some(item));
-                    }
-                    Ok(())
-                }
-            }
-        }
+    /// Emitted when a contributor takes some or all of their contribution
+    /// back out before the end date.
+    #[ink(event)]
+    pub struct ContributionExited {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        contributor: H160,
+        refunded: Amount,
+        penalty: Amount,
+    }
 
-        #[ink(message)]
-        pub fn split_raised_wish(&mut self, id: u32) -> Result<()> {
-            let caller = self.get_caller();
-            let wishlist = self.items.get(id);
+    #[ink(event)]
+    pub struct ProgressWithdrawn {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        owner: H160,
+        amount: Amount,
+    }
 
-            match wishlist {
-                None => Err(Error::WishNotFound),
-                Some(item) => {
-                    let item = item.unwrap();
-                    // owner must be a contributor
-                    assert!(
-                        item.contributors.iter().find(|c| c.0 == caller).is_some(),
-                        "Caller is not a contributor"
-                    );
-                    let contributors_raise = self.get_contributors_raised(id);
-                    let total_worth = match contributors_raise {
-                        None => item.raised,
-                        Some(raised) => raised + item.raised,
-                    };
+    #[ink(event)]
+    pub struct EarlyClaimRequested {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        owner: H160,
+    }
 
-                    let contributors = item.contributors;
-                    self.items.set(
-                        id,
-                        &None::<WishListItem>,
-                    );
+    #[ink(event)]
+    pub struct EarlyClaimVoted {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        contributor: H160,
+        approve: bool,
+        weight: Amount,
+    }
 
-                    for (address, bal) in contributors {
-                        let percentage = (bal * U256::from(100)) / total_worth;
-                        let _ = self.env().transfer(address, percentage);
+    #[ink(event)]
+    pub struct EarlyClaimVoteRevoked {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        voter: H160,
+    }
+
+    #[ink(event)]
+    pub struct VotesDelegated {
+        #[ink(topic)]
+        delegator: H160,
+        delegate: Option<H160>,
+    }
+
+    #[ink(event)]
+    pub struct PayoutSent {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        recipient: H160,
+        amount: Amount,
+    }
+
+    #[ink(event)]
+    pub struct FulfillmentConfirmed {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        contributor: H160,
+        weight: Amount,
+    }
+
+    #[ink(event)]
+    pub struct EscrowReleased {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        beneficiary: H160,
+        amount: Amount,
+    }
+
+    #[ink(event)]
+    pub struct EscrowRefunded {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        beneficiary: H160,
+        amount: Amount,
+    }
+
+    #[ink(event)]
+    pub struct ReceiptSubmitted {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        owner: H160,
+        hash: [u8; 32],
+        uri: String,
+    }
+
+    #[ink(event)]
+    pub struct SpendReported {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        owner: H160,
+        amount: Amount,
+        category: SpendCategory,
+        memo_hash: [u8; 32],
+    }
+
+    #[ink(event)]
+    pub struct ContributionMatched {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        contributor: H160,
+        amount: Amount,
+    }
+
+    #[ink(event)]
+    pub struct PoolCreated {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        creator: H160,
+        target_sum: Amount,
+        deadline: u64,
+    }
+
+    #[ink(event)]
+    pub struct CampaignCreated {
+        #[ink(topic)]
+        campaign_id: u32,
+        #[ink(topic)]
+        owner: H160,
+        slug: String,
+    }
+
+    #[ink(event)]
+    pub struct PoolJoined {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        member: H160,
+        amount: Amount,
+    }
+
+    #[ink(event)]
+    pub struct PoolApplied {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        id: u32,
+        total: Amount,
+    }
+
+    #[ink(event)]
+    pub struct PoolRefunded {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        id: u32,
+        total: Amount,
+    }
+
+    #[ink(event)]
+    pub struct ContributionCommitted {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        contributor: H160,
+        deposit: Amount,
+    }
+
+    #[ink(event)]
+    pub struct ContributionRevealed {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        contributor: H160,
+        amount: Amount,
+    }
+
+    #[ink(event)]
+    pub struct RoundStarted {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        owner: H160,
+        round: u32,
+        cap: Amount,
+        ends_at: u64,
+    }
+
+    /// A contributor's share of a failed wish was set aside for them to
+    /// withdraw.
+    #[ink(event)]
+    pub struct RefundIssued {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        contributor: H160,
+        amount: Amount,
+    }
+
+    /// A failed wish was shared out among its contributors.
+    #[ink(event)]
+    pub struct WishSplit {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        owner: H160,
+        /// The sum of the `RefundIssued` amounts.
+        total_refunded: Amount,
+    }
+
+    #[ink(event)]
+    pub struct PayoutWithdrawn {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        account: H160,
+        amount: Amount,
+    }
+
+    #[ink(event)]
+    pub struct MilestoneReached {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        owner: H160,
+        /// Share of the target now raised: 25, 50, 75 or 100.
+        percent: u8,
+    }
+
+    #[ink(event)]
+    pub struct UpdatePosted {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        author: H160,
+        hash: [u8; 32],
+        uri: String,
+    }
+
+    #[ink(event)]
+    pub struct DeadlineExtended {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        owner: H160,
+        end_date: u64,
+    }
+
+    #[ink(event)]
+    pub struct OperatorApproval {
+        #[ink(topic)]
+        owner: H160,
+        #[ink(topic)]
+        operator: H160,
+        approved: bool,
+    }
+
+    #[ink(event)]
+    pub struct RecoveryProposed {
+        #[ink(topic)]
+        owner: H160,
+        #[ink(topic)]
+        new_owner: H160,
+        #[ink(topic)]
+        guardian: H160,
+    }
+
+    #[ink(event)]
+    pub struct RecoveryCancelled {
+        #[ink(topic)]
+        owner: H160,
+    }
+
+    #[ink(event)]
+    pub struct EntryFeeCharged {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        contributor: H160,
+        fee: Amount,
+        /// Whether the fee was burned rather than paid to the treasury.
+        burned: bool,
+    }
+
+    #[ink(event)]
+    pub struct EntryFeeUpdated {
+        bps: u16,
+        burn: bool,
+    }
+
+    #[ink(event)]
+    pub struct ClaimFeeCharged {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        owner: H160,
+        fee: Amount,
+    }
+
+    #[ink(event)]
+    pub struct ClaimFeeUpdated {
+        bps: u16,
+    }
+
+    #[ink(event)]
+    pub struct FeesWithdrawn {
+        #[ink(topic)]
+        to: H160,
+        amount: Amount,
+    }
+
+    #[ink(event)]
+    pub struct FeeExemptionSet {
+        #[ink(topic)]
+        account: H160,
+        exempt: bool,
+    }
+
+    #[ink(event)]
+    pub struct PayoutAddressSet {
+        #[ink(topic)]
+        account: H160,
+        payout: Option<H160>,
+    }
+
+    #[ink(event)]
+    pub struct WishGifted {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        from: H160,
+        #[ink(topic)]
+        recipient: H160,
+    }
+
+    #[ink(event)]
+    pub struct GiftAccepted {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        recipient: H160,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipRecovered {
+        #[ink(topic)]
+        owner: H160,
+        #[ink(topic)]
+        new_owner: H160,
+    }
+
+    #[ink(event)]
+    pub struct Pledged {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        account: H160,
+        /// Zero when the pledge is withdrawn.
+        amount: Amount,
+    }
+
+    #[ink(event)]
+    pub struct PledgeConverted {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        account: H160,
+        amount: Amount,
+    }
+
+    #[ink(event)]
+    pub struct WishPaused {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        owner: H160,
+    }
+
+    #[ink(event)]
+    pub struct FundingClosed {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        owner: H160,
+        /// When the wish can be settled.
+        end_date: u64,
+    }
+
+    #[ink(event)]
+    pub struct WishResumed {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        owner: H160,
+        /// End date after resuming, extended by the paused time if asked to.
+        end_date: u64,
+    }
+
+    #[ink(event)]
+    pub struct DustSwept {
+        amount: Amount,
+    }
+
+    #[ink(event)]
+    pub struct WishVerified {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        moderator: H160,
+        verified: bool,
+    }
+
+    /// The owner called a wish off before its end date.
+    #[ink(event)]
+    pub struct WishCancelled {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        owner: H160,
+        /// The owner's side of the pot, credited back to them.
+        refunded: Amount,
+    }
+
+    #[ink(event)]
+    pub struct WishModerated {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        moderator: H160,
+        #[ink(topic)]
+        owner: H160,
+        reason: String,
+    }
+
+    #[ink(event)]
+    pub struct AppealLodged {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        owner: H160,
+        bond: Amount,
+    }
+
+    #[ink(event)]
+    pub struct AppealResolved {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        arbiter: H160,
+        #[ink(topic)]
+        owner: H160,
+        upheld: bool,
+    }
+
+    #[ink(event)]
+    pub struct CharityUpdated {
+        #[ink(topic)]
+        account: H160,
+        verified: bool,
+    }
+
+    #[ink(event)]
+    pub struct AdminProposed {
+        #[ink(topic)]
+        admin: H160,
+        #[ink(topic)]
+        proposed: H160,
+    }
+
+    #[ink(event)]
+    pub struct AdminChanged {
+        #[ink(topic)]
+        previous: H160,
+        #[ink(topic)]
+        admin: H160,
+    }
+
+    #[ink(event)]
+    pub struct ContractPauseSet {
+        #[ink(topic)]
+        admin: H160,
+        paused: bool,
+    }
+
+    #[ink(event)]
+    pub struct ModeratorUpdated {
+        #[ink(topic)]
+        account: H160,
+        enabled: bool,
+    }
+
+    /// Errors that can occur upon calling this contract.
+    ///
+    /// Each variant has a fixed numeric code, which is also its SCALE index
+    /// and so the first byte of the revert data. Codes are never reused or
+    /// renumbered: new variants take the next free number.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        /// Returned if the name already exists upon registration.
+        InvalidContribution = 0,
+        /// Returned if wish does not exist.
+        WishNotFound = 1,
+        /// Invalid Target amount
+        InvalidTarget = 2,
+        /// Returned if the caller is not the owner of the wish.
+        NotOwner = 3,
+        /// Returned if there is no pending occurrence to spawn for the wish.
+        NoRecurrence = 4,
+        /// Returned if the caller's deposit cannot cover the requested amount.
+        InsufficientDeposit = 5,
+        /// Returned if a transfer out of the contract failed.
+        TransferFailed = 6,
+        /// Returned if there is no standing order for the contributor and wish.
+        StandingOrderNotFound = 7,
+        /// Returned if the next installment of a standing order is not due yet.
+        InstallmentNotDue = 8,
+        /// Returned if a basis point value exceeds 10_000.
+        InvalidBasisPoints = 9,
+        /// Returned if the caller has not contributed to the wish.
+        NotContributor = 10,
+        /// Returned if the action is only allowed before the wish's end date.
+        WishEnded = 11,
+        /// Returned if the wish does not allow early withdrawals by the owner.
+        PartialWithdrawalDisabled = 12,
+        /// Returned if a withdrawal exceeds what the wish's progress allows.
+        WithdrawalLimitExceeded = 13,
+        /// Returned when splitting a wish whose pot always goes to the owner.
+        NotRefundable = 14,
+        /// Returned if the wish has not raised its target.
+        TargetNotReached = 15,
+        /// Returned if the owner has not requested an early claim for the wish.
+        EarlyClaimNotRequested = 16,
+        /// Returned if the caller already voted on the request.
+        AlreadyVoted = 17,
+        /// Returned if the caller is not the contract admin.
+        OnlyAdmin = 18,
+        /// Returned if the caller is not a moderator.
+        NotModerator = 19,
+        /// Returned if the caller has no credits to withdraw.
+        NothingToWithdraw = 20,
+        /// Returned if the treasury cannot cover the requested amount.
+        InsufficientTreasury = 21,
+        /// Returned if the wish was not taken down by a moderator.
+        NotModerated = 22,
+        /// Returned if the appeal window for the wish has closed.
+        AppealWindowClosed = 23,
+        /// Returned if the wish already has an appeal.
+        AppealExists = 24,
+        /// Returned if there is no pending appeal for the wish.
+        NoPendingAppeal = 25,
+        /// Returned if the bond sent with an appeal is below the minimum.
+        InsufficientBond = 26,
+        /// Returned if the caller may not resolve appeals.
+        NotArbiter = 27,
+        /// Returned if the owner already has the maximum number of open wishes.
+        TooManyActiveWishes = 28,
+        /// Returned if contributions are locked for the final stretch.
+        ContributionsLocked = 29,
+        /// Returned if the verifier registered for a purpose did not vouch
+        /// for the caller.
+        AttestationRequired = 30,
+        /// Returned if no extension is registered for the wish type.
+        UnknownWishType = 31,
+        /// Returned if the wish type's extension rejected the action.
+        ExtensionRejected = 32,
+        /// Returned if payout shares are empty, too many, or do not add up to 100%.
+        InvalidPayoutSplit = 33,
+        /// Returned if a charity wish names a beneficiary that is not verified.
+        UnverifiedCharity = 34,
+        /// Returned if the action would route a charity wish's funds elsewhere.
+        CharityPayoutFixed = 35,
+        /// Returned if the wish has no funds in escrow.
+        NoEscrow = 36,
+        /// Returned if the caller already confirmed fulfillment.
+        AlreadyConfirmed = 37,
+        /// Returned if the escrow can still be confirmed.
+        EscrowOpen = 38,
+        /// Returned if the wish was not claimed.
+        NotClaimed = 39,
+        /// Returned if a receipt was already submitted for the wish.
+        ReceiptExists = 40,
+        /// Returned if the receipt URI is longer than `MAX_RECEIPT_URI_LEN`.
+        UriTooLong = 41,
+        /// Returned if spend reports would account for more than the pot, or
+        /// the wish has `MAX_SPEND_REPORTS` already.
+        InvalidSpendReport = 42,
+        PoolNotFound = 43,
+        /// Returned if the pool is no longer open, past its deadline or full.
+        PoolClosed = 44,
+        /// Returned if the pool can still be filled.
+        PoolOpen = 45,
+        /// Returned if a sealed wish is funded in the open, or vice versa.
+        SealedWish = 46,
+        /// Returned if the caller already committed to the wish.
+        AlreadyCommitted = 47,
+        /// Returned if the caller has no commitment for the wish.
+        NoCommitment = 48,
+        /// Returned outside the window in which commitments are revealed.
+        NotRevealPhase = 49,
+        /// Returned if the revealed amount and salt do not match the commitment.
+        InvalidReveal = 50,
+        /// Returned if rounds are out of order, past the end date, too many,
+        /// or set after contributions started.
+        InvalidRounds = 51,
+        /// Returned if the wish's rounds are over.
+        NoActiveRound = 52,
+        /// Returned if a contribution is below the round's minimum or over its cap.
+        RoundLimitExceeded = 53,
+        /// Returned if a permit signature does not recover to the expected signer.
+        InvalidSignature = 54,
+        /// Returned if a permit is used after its deadline.
+        PermitExpired = 55,
+        /// Returned if the wish is paused by its owner.
+        WishPaused = 56,
+        /// Returned if resuming a wish that is not paused, or pausing one that is.
+        NotPaused = 57,
+        /// Returned if a wish's start date is not before its end date.
+        InvalidStartDate = 58,
+        /// Returned if contributing to a wish before its start date.
+        WishUpcoming = 59,
+        /// Returned if pledging to a wish that has no start date or has started.
+        NotUpcoming = 60,
+        /// Returned if a wish already has `MAX_PLEDGES` pledges.
+        TooManyPledges = 61,
+        /// Returned if the caller is not a guardian of the owner.
+        NotGuardian = 62,
+        /// Returned if a guardian set is empty, too large or its threshold
+        /// cannot be met.
+        InvalidGuardians = 63,
+        /// Returned if there is no recovery in progress for the owner.
+        NoRecovery = 64,
+        /// Returned if executing a recovery before its delay has passed.
+        RecoveryPending = 65,
+        /// Returned if the caller is neither the owner nor the manager of the
+        /// wish.
+        NotManager = 66,
+        /// Returned if an extension would push the end date more than
+        /// `MAX_DEADLINE_EXTENSION` past the original one.
+        ExtensionLimitExceeded = 67,
+        /// Returned if the caller is not an approved operator of the owner.
+        NotOperator = 68,
+        /// Returned if an operator tries to choose where a payout goes.
+        OperatorNotAllowed = 69,
+        /// Returned if a wish's target is below the configured minimum.
+        TargetTooSmall = 70,
+        /// Returned if no campaign has the given id.
+        CampaignNotFound = 71,
+        /// Returned if another campaign already uses the slug.
+        SlugTaken = 72,
+        /// Returned if a campaign's slug is empty or too long, or it would
+        /// hold no wishes, too many, or the same wish twice.
+        InvalidCampaign = 73,
+        /// Returned if the owner created a wish with the same description
+        /// within `DUPLICATE_WINDOW`.
+        DuplicateWish = 74,
+        /// Returned if the owner has closed the wish to contributions.
+        FundingClosed = 75,
+        /// Returned if the owner has banned the caller from contributing.
+        BannedContributor = 76,
+        /// Returned if a contribution does not accept the wish's current
+        /// terms.
+        TermsMismatch = 77,
+        /// Returned if rolling over or refunding a wish that has not failed.
+        NotFailed = 78,
+        /// Returned if poking a wish that did not opt into automatic claims.
+        NotAutoClaim = 79,
+        /// Returned if a wish cannot be claimed yet.
+        NotClaimable = 80,
+        /// Returned if a token has not been approved for contributions.
+        TokenNotAccepted = 81,
+        /// Returned if a wish already holds `MAX_WISH_TOKENS` other tokens.
+        TooManyTokens = 82,
+        /// Returned if a contract contributes to a wish that only takes
+        /// contributions from externally owned accounts.
+        ContractContributor = 83,
+        /// Returned if an NFT deposit does not exist or was withdrawn.
+        NftNotFound = 84,
+        /// Returned if reviewing an NFT deposit the owner already reviewed.
+        NftReviewed = 85,
+        /// Returned if withdrawing an NFT that is still held for its wish, or
+        /// that is owed to someone else.
+        NftHeld = 86,
+        /// Returned if a wish already holds `MAX_WISH_NFTS` NFTs.
+        TooManyNfts = 87,
+        /// Returned if a purchase has no max price.
+        InvalidPurchase = 88,
+        /// Returned if the listing is gone, costs more than the max price or
+        /// the pot, or the marketplace refused the purchase.
+        PurchaseFailed = 89,
+        /// Returned if a loan has no installments or no interval between them.
+        InvalidLoanTerms = 90,
+        /// Returned if repaying a wish with no loan outstanding.
+        NoLoan = 91,
+        /// Returned if a soft deadline is not before the end date.
+        InvalidSoftDeadline = 92,
+        /// Returned if contributing to a wish that fell short at its soft
+        /// deadline.
+        SoftDeadlineMissed = 93,
+        /// Returned if accepting a wish that is not a pending gift to the
+        /// caller.
+        NotGiftRecipient = 94,
+        /// Returned if revoking a vote the caller has not cast.
+        NotVoted = 95,
+        /// Returned if claiming a wish before its end date without an
+        /// approved early claim.
+        EndDateNotReached = 96,
+        /// Returned if accepting the admin role without having been proposed.
+        NotPendingAdmin = 97,
+        /// Returned if creating or funding a wish while the admin has paused
+        /// the contract.
+        ContractPaused = 98,
+        /// Returned if requesting an early claim while one is already
+        /// pending for the wish.
+        EarlyClaimPending = 99,
+        /// Returned if the value sent with a call is more than an `Amount`
+        /// can hold.
+        AmountOverflow = 100,
+        /// Returned if moving the clock of a contract that reads the block
+        /// timestamp.
+        ClockNotFixed = 101,
+    }
+
+    impl Error {
+        /// The stable numeric code of this error, for frontends to branch on.
+        pub fn error_code(&self) -> u32 {
+            *self as u32
+        }
+    }
+
+    /// EIP-712 domain name of signed permits.
+    pub const PERMIT_DOMAIN_NAME: &str = "Wishlist";
+
+    /// EIP-712 domain version of signed permits. Bump it when a permit type changes.
+    pub const PERMIT_DOMAIN_VERSION: &str = "1";
+
+    const DOMAIN_TYPE: &str =
+        "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+
+    const STANDING_ORDER_PERMIT_TYPE: &str = "StandingOrderPermit(address contributor,uint32 wishId,uint256 amount,uint64 period,uint256 cap,uint64 nonce,uint64 deadline)";
+
+    /// A value ABI-encoded as a 32-byte big-endian word, as EIP-712 hashes
+    /// struct members.
+    fn word(value: U256) -> [u8; 32] {
+        value.to_big_endian()
+    }
+
+    /// `description` trimmed, lowercased and with runs of whitespace
+    /// collapsed, so trivially edited copies compare equal.
+    fn normalize(description: &str) -> String {
+        description
+            .split_whitespace()
+            .map(|word| word.to_lowercase())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn address_word(account: H160) -> [u8; 32] {
+        let mut word = [0u8; 32];
+        word[12..].copy_from_slice(account.as_bytes());
+        word
+    }
+
+    /// Type alias for the contract's result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// Hash of the terms a wish's contributors accept.
+    pub type TermsHash = [u8; 32];
+
+    /// One day in milliseconds, the unit of block timestamps.
+    pub const DAY: u64 = 24 * 60 * 60 * 1000;
+
+    /// Where the contract reads the current time, in milliseconds, from.
+    /// Messages ask `Wishlist::now` rather than the environment so the
+    /// source can be swapped per instance, e.g. for an oracle-fed time.
+    pub(crate) trait TimeSource {
+        fn now(&self) -> u64;
+    }
+
+    /// The block timestamp, the source used on chain.
+    #[derive(Default)]
+    pub(crate) struct BlockTime;
+
+    impl TimeSource for BlockTime {
+        fn now(&self) -> u64 {
+            ink::env::block_timestamp::<ink::env::DefaultEnvironment>()
+        }
+    }
+
+    /// The time source of a contract instance, chosen when it is
+    /// instantiated.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Clock {
+        /// The block timestamp.
+        #[default]
+        Block,
+        /// A time the admin moves with `set_clock`, for sandbox and
+        /// end-to-end deployments that need to get past a deadline.
+        Fixed(u64),
+    }
+
+    impl TimeSource for Clock {
+        fn now(&self) -> u64 {
+            match self {
+                Clock::Block => BlockTime.now(),
+                Clock::Fixed(time) => *time,
+            }
+        }
+    }
+
+    /// How often a recurring wish comes back after settlement.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Interval {
+        Weekly,
+        Monthly,
+        Yearly,
+    }
+
+    impl Interval {
+        /// Length of the interval in milliseconds (months are 30 days, years 365).
+        pub fn duration(&self) -> u64 {
+            match self {
+                Interval::Weekly => 7 * DAY,
+                Interval::Monthly => 30 * DAY,
+                Interval::Yearly => 365 * DAY,
+            }
+        }
+    }
+
+    /// Orderings supported by `get_wishes_sorted`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum SortKey {
+        /// Most recently created first.
+        Newest,
+        /// Closest deadline first, skipping wishes whose deadline has passed.
+        EndingSoonest,
+        /// Highest share of the target raised first.
+        PercentFunded,
+        /// Highest boost score first.
+        BoostScore,
+    }
+
+    /// How long contributors have to object to an early claim before it
+    /// goes through without a majority approval.
+    pub const EARLY_CLAIM_WINDOW: u64 = 2 * DAY;
+
+    /// Symbol of the chain's native currency, which wishes are denominated in.
+    pub const NATIVE_SYMBOL: &str = "UNIT";
+
+    /// Decimals of the native currency as seen by contracts.
+    pub const NATIVE_DECIMALS: u8 = 18;
+
+    /// How far `extend_deadline` can move a wish's end date in total.
+    pub const MAX_DEADLINE_EXTENSION: u64 = 30 * DAY;
+
+    /// Maximum number of guardians an owner can nominate.
+    pub const MAX_GUARDIANS: usize = 10;
+
+    /// How long an approved recovery waits before it can be executed,
+    /// giving the owner time to cancel it.
+    pub const RECOVERY_DELAY: u64 = 3 * DAY;
+
+    /// How long an owner has to appeal a force-cancel.
+    pub const APPEAL_WINDOW: u64 = 7 * DAY;
+
+    /// Initial cap on the number of wishes an owner can have open at once.
+    pub const DEFAULT_MAX_ACTIVE_WISHES: u32 = 20;
+
+    /// `ref_time` each observer callback may use.
+    pub const OBSERVER_REF_TIME_LIMIT: u64 = 2_000_000_000;
+
+    /// How long contributors have to confirm fulfillment after a claim.
+    pub const FULFILLMENT_WINDOW: u64 = 30 * DAY;
+
+    /// Share of contributions, in basis points, that must confirm
+    /// fulfillment before escrow releases.
+    pub const FULFILLMENT_QUORUM_BPS: u64 = 5_000;
+
+    /// How long after claiming a spend report counts as timely.
+    pub const REPORT_WINDOW: u64 = 30 * DAY;
+
+    /// Maximum number of different PSP22 tokens a wish can hold.
+    pub const MAX_WISH_TOKENS: usize = 5;
+
+    /// Reputation lost for each loan installment repaid after its due date.
+    pub const LATE_INSTALLMENT_PENALTY_BPS: u16 = 1_000;
+
+    /// Maximum number of NFTs deposited towards a wish.
+    pub const MAX_WISH_NFTS: usize = 10;
+
+    /// Highest fee, in basis points, the admin may charge on contributions.
+    pub const MAX_ENTRY_FEE_BPS: u16 = 500;
+
+    /// Highest fee, in basis points, the platform may take off a claimed pot.
+    pub const MAX_CLAIM_FEE_BPS: u16 = 1_000;
+
+    /// Maximum number of wishes in a campaign.
+    pub const MAX_CAMPAIGN_WISHES: usize = 20;
+
+    /// Maximum length of a campaign's share slug, in bytes.
+    pub const MAX_SLUG_LEN: usize = 64;
+
+    /// Maximum number of spend reports per wish.
+    pub const MAX_SPEND_REPORTS: usize = 50;
+
+    /// Maximum length, in bytes, of a receipt URI.
+    pub const MAX_RECEIPT_URI_LEN: usize = 256;
+
+    /// How long an owner must wait before creating a wish with the same
+    /// description again.
+    pub const DUPLICATE_WINDOW: u64 = DAY;
+
+    /// How long after the end date contributors to a sealed wish have to
+    /// reveal their commitments.
+    pub const REVEAL_WINDOW: u64 = 2 * DAY;
+
+    /// Scale of the per-share payout accumulator.
+    pub const PAYOUT_PRECISION: u128 = 1_000_000_000_000_000_000;
+
+    /// Maximum number of rounds a campaign can be split into.
+    pub const MAX_ROUNDS: usize = 10;
+
+    /// Maximum number of members in a contribution pool.
+    pub const MAX_POOL_MEMBERS: usize = 50;
+
+    /// Maximum number of recipients a payout can be split between.
+    pub const MAX_PAYOUT_RECIPIENTS: usize = 10;
+
+    /// Shares of the target, in percent, announced with `MilestoneReached`.
+    pub const MILESTONES: [u8; 4] = [25, 50, 75, 100];
+
+    /// Maximum number of pledges an upcoming wish can collect.
+    pub const MAX_PLEDGES: usize = 50;
+
+    /// Upper bound on the number of wishes a batched settlement call visits.
+    pub const MAX_BATCH_SIZE: usize = 20;
+
+    /// Upper bound on the number of wishes returned by a listing call.
+    pub const MAX_PAGE_SIZE: u32 = 100;
+
+    /// Most entries kept in one page of a listing index. A full page is
+    /// split in two, so an update reads and writes a single page.
+    pub const INDEX_PAGE_SIZE: usize = 64;
+
+    /// The listing indexes kept over active wishes. Each holds `(key, id)`
+    /// entries in ascending order, split into pages of at most
+    /// `INDEX_PAGE_SIZE` entries.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum ListingIndex {
+        /// Keyed by nothing, so in creation order.
+        Newest,
+        /// Keyed by `end_date`.
+        Deadline,
+        /// Keyed by funded basis points.
+        Funded,
+        /// Keyed by boost score.
+        Boost,
+    }
+
+    /// A `(key, wish id)` entry of a listing index.
+    type IndexEntry = (Amount, u32);
+
+    /// Directory entry for one page of a listing index.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct IndexPage {
+        page: u32,
+        /// Lowest entry on the page.
+        first: IndexEntry,
+        len: u32,
+    }
+
+    /// What happens to the pot when a wish misses its target.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum FundingModel {
+        /// The owner only claims if the target is reached, otherwise the
+        /// contributors split the pot.
+        #[default]
+        AllOrNothing,
+        /// The owner claims whatever was raised once the wish has ended.
+        KeepWhatYouRaise,
+    }
+
+    /// How the contributions to a loan wish are paid back once it is claimed.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct LoanTerms {
+        /// Equal parts the contributions are repaid in.
+        pub installments: u32,
+        /// Time between the claim and the first installment, and between
+        /// installments.
+        pub interval: u64,
+    }
+
+    /// The repayment schedule of a claimed loan wish.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct Loan {
+        /// The contributions, owed back to the contributors.
+        principal: Amount,
+        repaid: Amount,
+        terms: LoanTerms,
+        claimed_at: u64,
+    }
+
+    impl Loan {
+        /// Installment `n`, counting from 1, falls due at this time.
+        fn due_date(&self, n: u32) -> u64 {
+            self.claimed_at
+                .saturating_add(self.terms.interval.saturating_mul(u64::from(n)))
+        }
+
+        /// Installments fully covered by `repaid`.
+        fn installments_paid(&self, repaid: Amount) -> u32 {
+            let paid = math::mul_div(
+                repaid,
+                Amount::from(self.terms.installments),
+                self.principal,
+            );
+            math::to_native(paid).try_into().unwrap_or(u32::MAX)
+        }
+    }
+
+    /// Optional settings for a wish, passed to `add_wishlist_item_with_options`.
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct WishOptions {
+        /// Spawn a new occurrence of the wish every interval after settlement.
+        pub recurrence: Option<Interval>,
+        /// Share of a contribution, in basis points, kept in the pot when a
+        /// contributor exits before `end_date`.
+        pub exit_penalty_bps: u16,
+        /// Let the owner withdraw part of the pot before `end_date` as the
+        /// wish makes progress (see `withdraw_progress`).
+        pub allow_partial_withdrawal: bool,
+        pub funding_model: FundingModel,
+        /// Length, in milliseconds, of the final stretch before `end_date`
+        /// during which contributions can no longer be withdrawn, e.g.
+        /// `2 * DAY`. Zero allows withdrawals right up to the end date.
+        pub withdrawal_lock: u64,
+        /// Custom wish type, handled by the extension registered for it.
+        pub wish_type: Option<u32>,
+        /// Verified charity the pot is paid to on claim, making this a
+        /// charity wish.
+        pub charity: Option<H160>,
+        /// Share of the pot, in basis points, held in escrow on claim until
+        /// contributors confirm the wish was fulfilled.
+        pub escrow_bps: u16,
+        /// Minimum viable amount the wish is judged successful against; the
+        /// target is then the ideal. Defaults to the target.
+        pub minimum_target: Option<Amount>,
+        /// Part of the value sent at creation set aside to match external
+        /// contributions; whatever is left is returned at settlement.
+        pub match_budget: Amount,
+        /// Match paid per contribution, in basis points of it, e.g. 10 000
+        /// for one-to-one.
+        pub match_ratio_bps: u16,
+        /// Take contributions as hash commitments revealed after the end
+        /// date (see `commit_contribution`), so amounts stay hidden while the
+        /// wish is running.
+        pub sealed: bool,
+        /// When the wish opens for contributions. Until then it is upcoming:
+        /// visible and shareable, but not fundable.
+        pub start_date: Option<u64>,
+        /// Settle the wish to the owner as soon as a contribution meets the
+        /// target, or through `poke` once it can be claimed, crediting the
+        /// payout to the pull ledger.
+        pub auto_claim: bool,
+        /// Only take contributions from externally owned accounts, not from
+        /// contracts, which might be unable to receive a refund.
+        pub eoa_only: bool,
+        /// Treat contributions as an interest-free loan the owner repays on
+        /// this schedule after claiming.
+        pub loan: Option<LoanTerms>,
+        /// When the target should be met by; the end date is then the hard
+        /// cutoff for settlement.
+        pub soft_deadline: Option<u64>,
+        /// Share of the target, in basis points, the wish must have reached
+        /// by its soft deadline to keep taking contributions. Short of it,
+        /// the wish fails there and then.
+        pub soft_threshold_bps: u16,
+    }
+
+    /// Display information for the asset a wish is denominated in.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct AssetMetadata {
+        symbol: String,
+        decimals: u8,
+    }
+
+    impl AssetMetadata {
+        pub fn native() -> Self {
+            Self {
+                symbol: String::from(NATIVE_SYMBOL),
+                decimals: NATIVE_DECIMALS,
+            }
+        }
+    }
+
+    #[cfg_attr(
+        feature = "std",
+        derive(Debug, PartialEq, Eq, ink::storage::traits::StorageLayout)
+    )]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct WishListItem {
+        id: u32,
+        description: String,
+        owner: H160,
+        target: Amount,
+        end_date: u64,
+        raised: Amount,
+        /// Number of accounts with a contribution in the wish; the amounts
+        /// are kept in `stakes`.
+        contributor_count: u32,
+        /// Entries in `contributor_index` for the wish, including accounts
+        /// that have since pulled out.
+        contributor_slots: u32,
+        /// Sum of all contributions.
+        contributed: Amount,
+        recurrence: Option<Interval>,
+        boost: Amount,
+        exit_penalty_bps: u16,
+        /// Exit penalties left behind by contributors; part of the pot.
+        forfeited: Amount,
+        allow_partial_withdrawal: bool,
+        /// Paid out to the owner ahead of settlement; no longer in the pot.
+        withdrawn: Amount,
+        funding_model: FundingModel,
+        asset: AssetMetadata,
+        /// Set by a moderator once the wish has been checked off-chain.
+        verified: bool,
+        withdrawal_lock: u64,
+        wish_type: Option<u32>,
+        charity: Option<H160>,
+        escrow_bps: u16,
+        minimum_target: Option<Amount>,
+        match_ratio_bps: u16,
+        /// Match budget not yet paid into the wish; not part of the pot.
+        match_remaining: Amount,
+        sealed: bool,
+        /// When the owner paused contributions, if they are paused.
+        paused_at: Option<u64>,
+        /// Kept out of public listings; still fundable by id.
+        unlisted: bool,
+        start_date: Option<u64>,
+        /// Highest of `MILESTONES` the wish has reached.
+        milestone: u8,
+        /// Time added to the end date through `extend_deadline`.
+        extended: u64,
+        /// Added by the owner after creation. Counts towards the target like
+        /// `raised`, but goes back to the owner if the wish is split.
+        owner_topups: Amount,
+        /// Set once the owner stops taking contributions ahead of the end date.
+        closed: bool,
+        /// Hash of the terms contributors must accept, if any.
+        terms: Option<TermsHash>,
+        auto_claim: bool,
+        eoa_only: bool,
+        loan: Option<LoanTerms>,
+        soft_deadline: Option<u64>,
+        soft_threshold_bps: u16,
+        /// Valuation of the NFTs the owner accepted. Counts towards the
+        /// target, but is not part of the pot.
+        nft_value: Amount,
+        /// `Active` or `Funded`; settled wishes are removed and their final
+        /// status is taken from the archive.
+        status: WishStatus,
+    }
+
+    /// A compact view of a wish for listings, with amounts ready to format
+    /// using the asset's symbol and decimals.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct WishSummary {
+        id: u32,
+        owner: H160,
+        description: String,
+        target: Amount,
+        /// Everything currently held for the wish.
+        pot: Amount,
+        end_date: u64,
+        funding_model: FundingModel,
+        symbol: String,
+        decimals: u8,
+        verified: bool,
+        /// Where the pot goes, if this is a charity wish.
+        charity: Option<H160>,
+        /// What the wish must raise to succeed; `target` is the ideal.
+        minimum_target: Amount,
+        /// Contributions are rejected before this time, if set.
+        start_date: Option<u64>,
+        /// Added by the owner after creation.
+        owner_topups: Amount,
+        status: WishStatus,
+    }
+
+    impl WishListItem {
+        /// The options the wish was created with.
+        fn options(&self) -> WishOptions {
+            WishOptions {
+                recurrence: self.recurrence,
+                exit_penalty_bps: self.exit_penalty_bps,
+                allow_partial_withdrawal: self.allow_partial_withdrawal,
+                funding_model: self.funding_model,
+                withdrawal_lock: self.withdrawal_lock,
+                wish_type: self.wish_type,
+                charity: self.charity,
+                escrow_bps: self.escrow_bps,
+                minimum_target: self.minimum_target,
+                // Match budgets are funded at creation and not carried over.
+                match_budget: math::ZERO,
+                match_ratio_bps: self.match_ratio_bps,
+                sealed: self.sealed,
+                // Later occurrences open as soon as they are spawned.
+                start_date: None,
+                auto_claim: self.auto_claim,
+                eoa_only: self.eoa_only,
+                loan: self.loan,
+                // Later occurrences have their own deadlines.
+                soft_deadline: None,
+                soft_threshold_bps: self.soft_threshold_bps,
+            }
+        }
+
+        /// The amount the wish must raise to succeed.
+        fn minimum(&self) -> Amount {
+            self.minimum_target.unwrap_or(self.target)
+        }
+
+        /// Record the wish's progress at `funded` basis points of its target:
+        /// it keeps the highest milestone it has reached, and is `Funded`
+        /// while the target is met and `Active` again once it no longer is.
+        fn reach(&mut self, funded: Amount) {
+            self.milestone = self.milestone.max(Wishlist::milestone_of(funded));
+            self.status = if funded >= Amount::from(10_000u16) {
+                WishStatus::Funded
+            } else {
+                WishStatus::Active
+            };
+        }
+    }
+
+    /// Where a wish is in its lifecycle. A wish starts `Active`, becomes
+    /// `Funded` once its target is met and ends in one of the other states.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum WishStatus {
+        Active,
+        Funded,
+        /// The owner, or a marketplace purchase on their behalf, took the pot.
+        Claimed,
+        /// The wish failed and its pot went back to the contributors, or into
+        /// a successor.
+        Refunded,
+        /// The owner or a moderator called the wish off.
+        Cancelled,
+    }
+
+    /// Configuration carried over from a settled recurring wish to its next occurrence.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct RecurringWish {
+        owner: H160,
+        description: String,
+        target: Amount,
+        end_date: u64,
+        interval: Interval,
+        options: WishOptions,
+    }
+
+    /// A contributor's recurring payment towards a wish, drawn from their deposit.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct StandingOrder {
+        amount: Amount,
+        period: u64,
+        cap: Amount,
+        paid: Amount,
+        next_due: u64,
+    }
+
+    /// A marketplace listing a wish buys on claim instead of paying out.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct Purchase {
+        pub marketplace: H160,
+        pub listing: u128,
+        /// Most the wish will pay for the listing.
+        pub max_price: Amount,
+    }
+
+    /// One recipient of a wish's payout and their share of it.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct PayoutShare {
+        pub recipient: H160,
+        /// Share of the pot in basis points.
+        pub bps: u16,
+    }
+
+    /// A non-binding promise to contribute to an upcoming wish.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct Pledge {
+        pub account: H160,
+        pub amount: Amount,
+    }
+
+    /// Outcome of one call to a batched settlement message.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct BatchResult {
+        /// Wishes settled by this call.
+        settled: Vec<u32>,
+        /// Cursor to pass to the next call, or `None` once every wish was visited.
+        next: Option<u32>,
+    }
+
+    /// Total value raised by a wish during one day.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct RaiseBucket {
+        /// Days since the unix epoch.
+        day: u64,
+        raised: Amount,
+    }
+
+    /// A single funding of a wish, as recorded in the contribution log.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct ContributionRecord {
+        /// Position of the contribution within the wish, starting at 0.
+        ordinal: u32,
+        contributor: H160,
+        amount: Amount,
+        timestamp: u64,
+    }
+
+    /// When an account first and last funded a wish.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct ContributorActivity {
+        first_ordinal: u32,
+        first_at: u64,
+        last_ordinal: u32,
+        last_at: u64,
+    }
+
+    /// An owner's request to claim a funded wish before its end date, with
+    /// the contribution-weighted votes cast on it so far.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct EarlyClaim {
+        requested_at: u64,
+        approvals: Amount,
+        objections: Amount,
+        /// Contributions when the claim was requested, less what was taken
+        /// out since; a majority of it approves the claim.
+        total: Amount,
+    }
+
+    /// The voting weight a contributor holds on a wish, and who casts it.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct VoteWeight {
+        /// The contributor, or whoever they had delegated to when they last
+        /// contributed.
+        voter: H160,
+        weight: Amount,
+    }
+
+    /// Part of a claimed pot held back until contributors confirm the wish
+    /// was fulfilled.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct Escrow {
+        beneficiary: H160,
+        amount: Amount,
+        /// Contributions at claim time; confirmation weight and refunds
+        /// follow each contributor's stake in it.
+        contributed: Amount,
+        confirmed: Amount,
+        /// After this, unconfirmed escrow can be refunded to contributors.
+        deadline: u64,
+    }
+
+    /// A contributor's vote and the contribution weight it was cast with.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct Vote {
+        approve: bool,
+        weight: Amount,
+    }
+
+    /// Why a wish was removed.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum SettlementOutcome {
+        /// The owner claimed the pot.
+        Claimed,
+        /// The contributors split the pot.
+        Split,
+        /// The owner cancelled the wish.
+        Cancelled,
+        /// A moderator took the wish down.
+        Moderated,
+        /// A moderator took the wish down, but the owner won the appeal.
+        ModerationReversed,
+        /// The wish failed and was carried into a successor.
+        RolledOver,
+        /// The wish failed after its end date; contributors withdraw their
+        /// share with `claim_refund`.
+        Refunding,
+    }
+
+    impl SettlementOutcome {
+        /// The final status of a wish settled this way.
+        fn status(self) -> WishStatus {
+            match self {
+                SettlementOutcome::Claimed => WishStatus::Claimed,
+                SettlementOutcome::Split
+                | SettlementOutcome::RolledOver
+                | SettlementOutcome::Refunding => WishStatus::Refunded,
+                SettlementOutcome::Cancelled
+                | SettlementOutcome::Moderated
+                | SettlementOutcome::ModerationReversed => WishStatus::Cancelled,
+            }
+        }
+    }
+
+    /// What the owner of a wish would receive by claiming it now.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct Claimable {
+        /// Whether `claim_wish` would succeed now.
+        can_claim: bool,
+        /// The whole pot.
+        gross: Amount,
+        fee: Amount,
+        /// Held in escrow until fulfillment is confirmed.
+        escrowed: Amount,
+        /// Paid out on claiming.
+        net: Amount,
+    }
+
+    /// What settling a wish would pay out if it happened now.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct SettlementPreview {
+        /// `Claimed` if the owner can take the pot now, `Split` otherwise.
+        outcome: SettlementOutcome,
+        pot: Amount,
+        /// Sent to the owner or their payout split, rounding remainder included.
+        payouts: Vec<(H160, Amount)>,
+        /// Held back from a claim until fulfillment is confirmed.
+        escrowed: Amount,
+        /// Withdrawable by each contributor after a split.
+        refunds: Vec<(H160, Amount)>,
+        fees: Amount,
+        /// Left in the contract by rounding.
+        dust: Amount,
+    }
+
+    /// The final state of a removed wish, kept as a tombstone after the wish
+    /// itself is gone.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct SettlementRecord {
+        id: u32,
+        owner: H160,
+        target: Amount,
+        /// Raised by the owner.
+        raised: Amount,
+        /// Raised by everyone else.
+        contributed: Amount,
+        /// Paid out at settlement.
+        pot: Amount,
+        outcome: SettlementOutcome,
+        /// The account whose call removed the wish.
+        actor: H160,
+        settled_at: u64,
+        /// Proof of purchase submitted by the owner after claiming.
+        receipt: Option<Receipt>,
+    }
+
+    /// Commitment to a receipt for what a claimed wish was spent on.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct Receipt {
+        /// Hash of the receipt document.
+        hash: [u8; 32],
+        /// Where the receipt can be fetched, e.g. an IPFS URI.
+        uri: String,
+        submitted_at: u64,
+    }
+
+    /// How far a wish is towards its minimum and its ideal target.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct Progress {
+        /// Share of the minimum target raised, in basis points.
+        minimum_bps: Amount,
+        /// Share of the ideal target raised, in basis points.
+        target_bps: Amount,
+    }
+
+    /// One stage of a campaign, running until `ends_at`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct Round {
+        pub ends_at: u64,
+        /// Most that contributors can put in during the round.
+        pub cap: Amount,
+        /// Smallest contribution accepted during the round.
+        pub min_contribution: Amount,
+    }
+
+    /// The round a wish is in and what contributors have put in during it.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct RoundProgress {
+        index: u32,
+        raised: Amount,
+    }
+
+    /// A hidden contribution to a sealed wish, with the funds backing it.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct Commitment {
+        hash: [u8; 32],
+        /// Held in escrow; may exceed the committed amount to hide it.
+        deposit: Amount,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum PoolStatus {
+        Open,
+        /// The pool reached its target and was contributed to the wish.
+        Applied,
+        /// The pool missed its deadline and members were refunded.
+        Refunded,
+    }
+
+    /// Commitments from several contributors that only reach the wish
+    /// together, once they add up to `target_sum`.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct Pool {
+        wish_id: u32,
+        creator: H160,
+        target_sum: Amount,
+        deadline: u64,
+        total: Amount,
+        members: Vec<(H160, Amount)>,
+        status: PoolStatus,
+    }
+
+    /// Contributions to a wish in one PSP22 token, kept apart from its
+    /// native pot.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct TokenPot {
+        token: H160,
+        contributions: Vec<(H160, Amount)>,
+    }
+
+    /// A PSP34 NFT deposited towards a wish.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct NftDeposit {
+        id: u32,
+        collection: H160,
+        token_id: Id,
+        contributor: H160,
+        /// Declared by the contributor; counts towards the target once the
+        /// owner accepts the NFT.
+        valuation: Amount,
+        accepted: bool,
+        /// Who may withdraw the NFT, once it is no longer held for the wish.
+        owed_to: Option<H160>,
+    }
+
+    /// Several wishes of one owner shown together under a share slug, e.g.
+    /// the itemized gifts of a wedding registry.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct Campaign {
+        owner: H160,
+        slug: String,
+        wishes: Vec<u32>,
+    }
+
+    /// What a campaign's wishes add up to, settled ones included.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct CampaignTotals {
+        target: Amount,
+        /// Pots of the open wishes and final pots of the settled ones.
+        raised: Amount,
+    }
+
+    /// Snapshot of how much the contract is storing, for monitoring growth.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct StorageStats {
+        /// Wishes ever created, including settled ones.
+        wishes_created: u32,
+        active_wishes: u32,
+        archived_wishes: u32,
+        /// Contributor entries across all active wishes.
+        contributor_entries: u32,
+        newest_index_len: u32,
+        deadline_index_len: u32,
+        funded_index_len: u32,
+        boost_index_len: u32,
+        /// Encoded size, in bytes, of the listing indexes together.
+        index_bytes: u32,
+        /// Encoded size, in bytes, of all active wishes.
+        wish_bytes: u32,
+    }
+
+    /// Value the contract holds on behalf of others outside of wish pots,
+    /// kept as running totals so it can be reconciled with the balance.
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct Holdings {
+        /// Standing-order deposits.
+        deposits: Amount,
+        /// Owed on the pull ledger.
+        credits: Amount,
+        /// Distributed to contributors and not yet withdrawn.
+        payouts: Amount,
+        /// Held back from claims pending fulfillment confirmation.
+        escrowed: Amount,
+        /// Paid into pools that have not been applied or refunded.
+        pooled: Amount,
+        /// Backing unrevealed commitments.
+        committed: Amount,
+        /// Posted with pending appeals.
+        bonds: Amount,
+        /// Burned entry fees, locked in the contract for good.
+        burned: Amount,
+    }
+
+    /// Result of reconciling what the contract owes with its balance.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct AuditReport {
+        /// Pots and unused match budgets of active wishes.
+        pots: Amount,
+        held: Holdings,
+        treasury: Amount,
+        accumulated_fees: Amount,
+        /// Everything above added up.
+        liabilities: Amount,
+        balance: Amount,
+        /// Balance in excess of liabilities, e.g. rounding dust.
+        surplus: Amount,
+        /// Liabilities the balance cannot cover; should always be zero.
+        shortfall: Amount,
+    }
+
+    /// The fee taken off each contribution, independent of any fee on
+    /// claims.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct EntryFee {
+        bps: u16,
+        /// Burn the fee instead of paying it to the treasury.
+        burn: bool,
+    }
+
+    /// Entry fees charged so far, by where they went.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct EntryFeeTotals {
+        to_treasury: Amount,
+        burned: Amount,
+    }
+
+    /// How often a message was called successfully, and the value it moved.
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct CallMetrics {
+        calls: u64,
+        /// Value sent with the calls, or paid out by them.
+        value: Amount,
+    }
+
+    /// An owner's track record, built up from their settled wishes.
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct Reputation {
+        /// Wishes the owner claimed.
+        claimed: u32,
+        /// Claimed wishes the owner submitted a receipt for.
+        receipts: u32,
+        /// Claimed wishes the owner filed a spend report for within
+        /// `REPORT_WINDOW` of claiming.
+        timely_reports: u32,
+        /// Loan installments repaid by their due date.
+        installments_on_time: u32,
+        /// Loan installments repaid after their due date.
+        installments_late: u32,
+    }
+
+    /// What part of a claimed pot was spent on.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum SpendCategory {
+        Goods,
+        Services,
+        Fees,
+        Donation,
+        Other,
+    }
+
+    /// An owner's account of how part of a claimed pot was spent.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct SpendReport {
+        amount: Amount,
+        category: SpendCategory,
+        /// Hash of an off-chain memo with the details.
+        memo_hash: [u8; 32],
+        filed_at: u64,
+    }
+
+    /// Result of looking up a wish by id.
+    // Only ever returned from a message, so the size of the variants is moot.
+    #[allow(clippy::large_enum_variant)]
+    #[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum WishEntry {
+        /// The wish is still open or awaiting settlement.
+        Active(WishListItem),
+        /// The wish has been settled and only its record remains.
+        Settled(SettlementRecord),
+    }
+
+    impl WishEntry {
+        /// The wish, if it has not been settled yet.
+        pub fn active(self) -> Option<WishListItem> {
+            match self {
+                WishEntry::Active(item) => Some(item),
+                WishEntry::Settled(_) => None,
+            }
+        }
+    }
+
+    /// What happens to the owner's side of the pot when a moderator cancels a wish.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum DepositPolicy {
+        /// Credit it back to the owner.
+        #[default]
+        Refund,
+        /// Move it to the treasury.
+        Forfeit,
+    }
+
+    /// Where an appeal against a moderation action stands.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum AppealStatus {
+        Pending,
+        /// The moderation stands and the bond went to the treasury.
+        Upheld,
+        /// The moderation was overturned and the bond returned.
+        Reversed,
+    }
+
+    /// Accounts an owner trusts to move their wishes to a new key.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct Guardians {
+        pub accounts: Vec<H160>,
+        /// Approvals needed to start a recovery.
+        pub threshold: u32,
+    }
+
+    /// A proposal by guardians to move an owner's wishes to `new_owner`.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct Recovery {
+        new_owner: H160,
+        approvals: Vec<H160>,
+        /// When the recovery can be executed, once the threshold is met.
+        ready_at: Option<u64>,
+    }
+
+    /// An owner's bonded appeal against the force-cancel of their wish.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct Appeal {
+        owner: H160,
+        bond: Amount,
+        lodged_at: u64,
+        status: AppealStatus,
+    }
+
+    /// What an attestation verifier vouches for. Each purpose gates its own flow.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum AttestationPurpose {
+        /// Required of owners creating a wish.
+        Kyc,
+        /// Required of contributors funding a wish.
+        Personhood,
+        /// Required of the beneficiary of a charity wish.
+        CharityStatus,
+    }
+
+    /// Defines the storage of your contract.
+    /// Add new fields to the below struct in order
+    /// to add new static storage fields to your contract.
+    #[ink(storage)]
+    pub struct Wishlist {
+        /// Id the next wish will get. Ids start at 1, so this is one more
+        /// than the number of wishes created so far.
+        next_item_id: u32,
+        /// Ids of every wish each account owns, settled or not, in creation
+        /// order.
+        items_by_owner: Mapping<H160, Vec<u32>>,
+        /// Open wishes by id. Settled wishes are removed and archived.
+        items: Mapping<u32, WishListItem>,
+        /// Settled recurring wishes waiting for their next occurrence to be spawned.
+        recurring: Mapping<u32, RecurringWish>,
+        /// Native value deposited by each account for pull-style contributions.
+        deposits: Mapping<H160, Amount>,
+        /// Standing orders keyed by (contributor, wish id).
+        standing_orders: Mapping<(H160, u32), StandingOrder>,
+        /// Pages of the listing indexes, keyed by page id.
+        index_pages: Mapping<u32, Vec<IndexEntry>>,
+        /// Pages of each listing index in the order of their entries.
+        index_directory: Mapping<ListingIndex, Vec<IndexPage>>,
+        /// Key each active wish is filed under in each listing index.
+        index_keys: Mapping<(ListingIndex, u32), Amount>,
+        next_index_page: u32,
+        /// Daily raised totals per wish, oldest first.
+        raise_history: Mapping<u32, Vec<RaiseBucket>>,
+        /// Every funding of a wish keyed by (wish id, position in the log).
+        contribution_log: Mapping<(u32, u32), ContributionRecord>,
+        /// Number of entries in the contribution log of each wish.
+        contribution_counts: Mapping<u32, u32>,
+        /// First and last contribution of each account, keyed by (wish id, account).
+        contributor_activity: Mapping<(u32, H160), ContributorActivity>,
+        /// Pending early claim requests by wish id.
+        early_claims: Mapping<u32, EarlyClaim>,
+        /// Early claim votes keyed by (wish id, contributor).
+        early_claim_votes: Mapping<(u32, H160), Vote>,
+        /// Voting weight each contributor holds on the early claim of a wish,
+        /// keyed by (wish id, contributor). Contributions made once a claim
+        /// is requested carry no weight.
+        early_claim_stakes: Mapping<(u32, H160), VoteWeight>,
+        /// Weight each voter casts on the early claim of a wish, their own and
+        /// what was delegated to them, keyed by (wish id, voter).
+        early_claim_weights: Mapping<(u32, H160), Amount>,
+        /// Who each account has delegated its voting weight to.
+        delegates: Mapping<H160, H160>,
+        /// Usage of the messages that create, fund and pay out wishes, by
+        /// selector.
+        call_metrics: Mapping<u32, CallMetrics>,
+        /// Selectors with an entry in `call_metrics`, in the order first called.
+        metered_selectors: Lazy<Vec<u32>>,
+        entry_fee: EntryFee,
+        entry_fee_totals: EntryFeeTotals,
+        /// Accounts whose contributions are not charged the entry fee.
+        fee_exempt: Mapping<H160, ()>,
+        /// Basis points of the pot the platform keeps when a wish is claimed.
+        claim_fee_bps: u16,
+        /// Claim fees taken and not yet withdrawn with `withdraw_fees`.
+        accumulated_fees: Amount,
+        /// Settlement records of retired wishes.
+        archive: Mapping<u32, SettlementRecord>,
+        /// Wish ids each account took part in, as owner or contributor, keyed
+        /// by `(account, n)` for the account's `n`th wish.
+        account_history: Mapping<(H160, u32), u32>,
+        /// How many entries each account has in `account_history`.
+        account_history_len: Mapping<H160, u32>,
+        /// Account allowed to configure the contract and appoint moderators.
+        admin: H160,
+        /// Account proposed as the next admin, until it accepts.
+        pending_admin: Option<H160>,
+        /// Whether the admin has stopped new wishes and contributions.
+        paused: bool,
+        /// Where this instance reads the current time from.
+        clock: Clock,
+        /// Accounts allowed to take wishes down.
+        moderators: Mapping<H160, ()>,
+        /// Pull-payment ledger: value owed to each account, withdrawn with
+        /// `withdraw_credits`.
+        credits: Mapping<H160, Amount>,
+        /// Value owned by the platform, withdrawn by the admin.
+        treasury: Amount,
+        /// How the owner's deposit is handled on `force_cancel`.
+        deposit_policy: DepositPolicy,
+        /// Deposits forfeited to the treasury by `force_cancel`, per wish.
+        moderation_forfeits: Mapping<u32, Amount>,
+        /// Appeals against force-cancels, per wish.
+        appeals: Mapping<u32, Appeal>,
+        /// Minimum bond for lodging an appeal.
+        appeal_bond: Amount,
+        /// Account besides the admin allowed to resolve appeals.
+        arbiter: Option<H160>,
+        /// Number of unsettled wishes per owner.
+        active_counts: Mapping<H160, u32>,
+        /// Ids of each owner's unsettled wishes in creation order.
+        active_by_owner: Mapping<H160, Vec<u32>>,
+        /// Non-binding pledges to upcoming wishes, converted at launch.
+        pledges: Mapping<u32, Vec<Pledge>>,
+        guardians: Mapping<H160, Guardians>,
+        /// Recoveries in progress, keyed by the owner being recovered.
+        recoveries: Mapping<H160, Recovery>,
+        max_active_wishes: u32,
+        /// Smallest target a new wish may have.
+        min_target: Amount,
+        /// Smallest initial contribution a new wish may have, whatever its
+        /// target, so the 10% deposit cannot round down to nothing.
+        min_deposit: Amount,
+        /// Attestation verifier contracts registered per purpose.
+        verifiers: Mapping<AttestationPurpose, H160>,
+        /// `WishObserver` contract registered by the owner, per wish.
+        observers: Mapping<u32, H160>,
+        /// Secondary keys allowed to post updates and extend deadlines.
+        managers: Mapping<u32, H160>,
+        /// Accounts approved to manage all wishes of an owner, keyed by
+        /// `(owner, operator)`.
+        operators: Mapping<(H160, H160), ()>,
+        /// Who each gifted wish goes to once they accept it.
+        gifts: Mapping<u32, H160>,
+        /// Where accounts have their refunds and withdrawals sent, if not to
+        /// themselves.
+        payout_addresses: Mapping<H160, H160>,
+        /// Accounts an owner has barred from contributing to a wish.
+        bans: Mapping<(u32, H160), ()>,
+        /// The terms each contributor last accepted for a wish.
+        terms_accepted: Mapping<(u32, H160), TermsHash>,
+        /// Contributors who agreed to carry their contribution to a wish into
+        /// its successor.
+        rollover_consents: Mapping<(u32, H160), ()>,
+        /// Contributors who agreed to this for every wish they fund.
+        rollover_preapproved: Mapping<H160, ()>,
+        /// PSP22 tokens wishes may be funded with.
+        accepted_tokens: Mapping<H160, ()>,
+        token_pots: Mapping<u32, Vec<TokenPot>>,
+        /// Tokens owed to accounts, keyed by `(account, token)`.
+        token_credits: Mapping<(H160, H160), Amount>,
+        next_nft_deposit: u32,
+        nft_deposits: Mapping<u32, NftDeposit>,
+        /// NFT deposits held for each wish.
+        wish_nfts: Mapping<u32, Vec<u32>>,
+        /// Admin-approved `WishExtension` contracts, per wish type.
+        extensions: Mapping<u32, H160>,
+        /// How the pot is divided on claim, per wish; the owner gets it all if unset.
+        payout_splits: Mapping<u32, Vec<PayoutShare>>,
+        /// What each wish buys when claimed, if anything.
+        purchases: Mapping<u32, Purchase>,
+        /// Repayment schedules of claimed loan wishes.
+        loans: Mapping<u32, Loan>,
+        /// Charities admins have verified as beneficiaries of charity wishes.
+        verified_charities: Mapping<H160, ()>,
+        /// Claimed funds awaiting fulfillment confirmation, per wish.
+        escrows: Mapping<u32, Escrow>,
+        fulfillment_confirmations: Mapping<(u32, H160), ()>,
+        reputations: Mapping<H160, Reputation>,
+        spend_reports: Mapping<u32, Vec<SpendReport>>,
+        next_pool_id: u32,
+        pools: Mapping<u32, Pool>,
+        next_campaign_id: u32,
+        campaigns: Mapping<u32, Campaign>,
+        campaign_slugs: Mapping<String, u32>,
+        /// When an owner last created a wish, keyed by the hash of the owner
+        /// and its normalized description.
+        recent_descriptions: Mapping<[u8; 32], u64>,
+        /// Unrevealed contributions to sealed wishes.
+        commitments: Mapping<(u32, H160), Commitment>,
+        rounds: Mapping<u32, Vec<Round>>,
+        round_progress: Mapping<u32, RoundProgress>,
+        /// Each account's contribution to each wish. Left at zero rather
+        /// than removed once an account pulls out, so it keeps its single
+        /// entry in `contributor_index`.
+        stakes: Mapping<(u32, H160), Amount>,
+        /// Accounts that contributed to each wish, keyed by (wish id, order of
+        /// their first contribution), for settlements that visit them all.
+        contributor_index: Mapping<(u32, u32), H160>,
+        /// Value paid out per unit of stake in a wish, scaled by
+        /// `PAYOUT_PRECISION`.
+        payout_per_share: Mapping<u32, Amount>,
+        /// Payout each contributor already withdrew from a wish.
+        payout_withdrawn: Mapping<(u32, H160), Amount>,
+        archived_count: u32,
+        held: Holdings,
+        /// Chain id signed into the permit domain separator.
+        chain_id: u64,
+        /// Next nonce each signer's permit must carry.
+        permit_nonces: Mapping<H160, u64>,
+    }
+
+    impl Wishlist {
+        /// Constructor that initializes the contract.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                next_item_id: 1,
+                items_by_owner: Mapping::new(),
+                items: Mapping::new(),
+                recurring: Mapping::new(),
+                deposits: Mapping::new(),
+                standing_orders: Mapping::new(),
+                index_pages: Mapping::new(),
+                index_directory: Mapping::new(),
+                index_keys: Mapping::new(),
+                next_index_page: 0,
+                raise_history: Mapping::new(),
+                contribution_log: Mapping::new(),
+                contribution_counts: Mapping::new(),
+                contributor_activity: Mapping::new(),
+                early_claims: Mapping::new(),
+                early_claim_votes: Mapping::new(),
+                early_claim_stakes: Mapping::new(),
+                early_claim_weights: Mapping::new(),
+                delegates: Mapping::new(),
+                call_metrics: Mapping::new(),
+                metered_selectors: Lazy::new(),
+                entry_fee: EntryFee::default(),
+                entry_fee_totals: EntryFeeTotals::default(),
+                fee_exempt: Mapping::new(),
+                claim_fee_bps: 0,
+                accumulated_fees: math::ZERO,
+                archive: Mapping::new(),
+                account_history: Mapping::new(),
+                account_history_len: Mapping::new(),
+                admin: Self::env().caller(),
+                moderators: Mapping::new(),
+                credits: Mapping::new(),
+                treasury: math::ZERO,
+                deposit_policy: DepositPolicy::default(),
+                moderation_forfeits: Mapping::new(),
+                appeals: Mapping::new(),
+                appeal_bond: math::ZERO,
+                arbiter: None,
+                pending_admin: None,
+                paused: false,
+                clock: Clock::default(),
+                active_counts: Mapping::new(),
+                active_by_owner: Mapping::new(),
+                pledges: Mapping::new(),
+                guardians: Mapping::new(),
+                recoveries: Mapping::new(),
+                max_active_wishes: DEFAULT_MAX_ACTIVE_WISHES,
+                min_target: math::ONE,
+                min_deposit: math::ONE,
+                verifiers: Mapping::new(),
+                observers: Mapping::new(),
+                managers: Mapping::new(),
+                operators: Mapping::new(),
+                gifts: Mapping::new(),
+                payout_addresses: Mapping::new(),
+                bans: Mapping::new(),
+                terms_accepted: Mapping::new(),
+                rollover_consents: Mapping::new(),
+                rollover_preapproved: Mapping::new(),
+                accepted_tokens: Mapping::new(),
+                token_pots: Mapping::new(),
+                token_credits: Mapping::new(),
+                next_nft_deposit: 0,
+                nft_deposits: Mapping::new(),
+                wish_nfts: Mapping::new(),
+                extensions: Mapping::new(),
+                payout_splits: Mapping::new(),
+                purchases: Mapping::new(),
+                loans: Mapping::new(),
+                verified_charities: Mapping::new(),
+                escrows: Mapping::new(),
+                fulfillment_confirmations: Mapping::new(),
+                reputations: Mapping::new(),
+                spend_reports: Mapping::new(),
+                next_pool_id: 0,
+                pools: Mapping::new(),
+                next_campaign_id: 0,
+                campaigns: Mapping::new(),
+                campaign_slugs: Mapping::new(),
+                recent_descriptions: Mapping::new(),
+                commitments: Mapping::new(),
+                rounds: Mapping::new(),
+                round_progress: Mapping::new(),
+                stakes: Mapping::new(),
+                contributor_index: Mapping::new(),
+                payout_per_share: Mapping::new(),
+                payout_withdrawn: Mapping::new(),
+                archived_count: 0,
+                held: Holdings::default(),
+                chain_id: 0,
+                permit_nonces: Mapping::new(),
+            }
+        }
+
+        /// Constructor that initializes the `bool` value to `false`.
+        ///
+        /// Constructors can delegate to other constructors.
+        #[ink(constructor)]
+        pub fn default() -> Self {
+            Self::new()
+        }
+
+        /// Constructor for running the contract as a service, keeping
+        /// `fee_bps` basis points, at most `MAX_CLAIM_FEE_BPS`, of every
+        /// claimed pot.
+        #[ink(constructor)]
+        pub fn with_claim_fee(fee_bps: u16) -> Result<Self> {
+            if fee_bps > MAX_CLAIM_FEE_BPS {
+                return Err(Error::InvalidBasisPoints);
+            }
+            let mut contract = Self::new();
+            contract.claim_fee_bps = fee_bps;
+            Ok(contract)
+        }
+
+        /// A contract reading the time from `clock`. Only an instance created
+        /// with `Clock::Fixed` can have its time moved with `set_clock`.
+        #[ink(constructor)]
+        pub fn with_clock(clock: Clock) -> Self {
+            let mut contract = Self::new();
+            contract.clock = clock;
+            contract
+        }
+
+        /// add a wishlist item with the optional settings in `options`
+        #[ink(message, payable)]
+        pub fn add_wishlist_item_with_options(
+            &mut self,
+            description: String,
+            end_date: u64,
+            target: Amount,
+            options: WishOptions,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            self.meter(
+                ink::selector_id!("add_wishlist_item_with_options"),
+                self.transferred()?,
+            );
+            self.add_item(caller, description, end_date, target, options)
+                .map(|_| ())
+        }
+
+        /// Add a wish owned by `owner`, as one of their operators. The
+        /// operator pays the initial contribution but cannot make the wish
+        /// pay out to a charity.
+        #[ink(message, payable)]
+        pub fn add_wishlist_item_for(
+            &mut self,
+            owner: H160,
+            description: String,
+            end_date: u64,
+            target: Amount,
+            options: WishOptions,
+        ) -> Result<()> {
+            if !self.operators.contains((owner, self.get_caller())) {
+                return Err(Error::NotOperator);
+            }
+            if options.charity.is_some() {
+                return Err(Error::OperatorNotAllowed);
+            }
+            self.meter(
+                ink::selector_id!("add_wishlist_item_for"),
+                self.transferred()?,
+            );
+            self.add_item(owner, description, end_date, target, options)
+                .map(|_| ())
+        }
+
+        /// Set up a wish as a gift to `recipient`, paying its deposit. The
+        /// caller owns and manages the wish until the recipient takes it over
+        /// with `accept_gift`, so it can be prepared as a surprise. Returns
+        /// the id of the wish.
+        #[ink(message, payable)]
+        pub fn gift_wish(
+            &mut self,
+            recipient: H160,
+            description: String,
+            end_date: u64,
+            target: Amount,
+            options: WishOptions,
+        ) -> Result<u32> {
+            let caller = self.get_caller();
+            self.meter(ink::selector_id!("gift_wish"), self.transferred()?);
+            let id = self.add_item(caller, description, end_date, target, options)?;
+            self.gifts.insert(id, &recipient);
+            self.env().emit_event(WishGifted {
+                id,
+                from: caller,
+                recipient,
+            });
+            Ok(id)
+        }
+
+        /// Take over gifted wish `id` as its owner and beneficiary.
+        #[ink(message)]
+        pub fn accept_gift(&mut self, id: u32) -> Result<()> {
+            let caller = self.get_caller();
+            if self.gifts.get(id) != Some(caller) {
+                return Err(Error::NotGiftRecipient);
+            }
+            let mut item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            let active = self.active_counts.get(caller).unwrap_or(0);
+            if active >= self.max_active_wishes {
+                return Err(Error::TooManyActiveWishes);
+            }
+            self.gifts.remove(id);
+
+            let giver = item.owner;
+            let mut owned = self.active_by_owner.get(giver).unwrap_or_default();
+            owned.retain(|entry| *entry != id);
+            self.active_by_owner.insert(giver, &owned);
+            let given = self.active_counts.get(giver).unwrap_or(0);
+            self.active_counts.insert(giver, &given.saturating_sub(1));
+            let mut owned = self.active_by_owner.get(caller).unwrap_or_default();
+            let position = owned.partition_point(|entry| *entry < id);
+            owned.insert(position, id);
+            self.active_by_owner.insert(caller, &owned);
+            self.active_counts.insert(caller, &active.saturating_add(1));
+            self.unindex_owner(giver, id);
+            self.index_owner(caller, id);
+            self.record_history(caller, id);
+
+            item.owner = caller;
+            self.items.insert(id, &item);
+            self.env().emit_event(GiftAccepted {
+                id,
+                recipient: caller,
+            });
+            Ok(())
+        }
+
+        /// Who gifted wish `id` goes to, while they have not accepted it.
+        #[ink(message)]
+        pub fn get_gift_recipient(&self, id: u32) -> Option<H160> {
+            self.gifts.get(id)
+        }
+
+        /// Approve `operator` to manage all of the caller's wishes: creating
+        /// them, posting updates, extending deadlines and triggering claims.
+        /// Payouts still go to the caller.
+        #[ink(message)]
+        pub fn approve_operator(&mut self, operator: H160, approved: bool) -> Result<()> {
+            let caller = self.get_caller();
+            if approved {
+                self.operators.insert((caller, operator), &());
+            } else {
+                self.operators.remove((caller, operator));
+            }
+            self.env().emit_event(OperatorApproval {
+                owner: caller,
+                operator,
+                approved,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_operator(&self, owner: H160, operator: H160) -> bool {
+            self.operators.contains((owner, operator))
+        }
+
+        /// Spawn the next occurrence of a settled recurring wish.
+        ///
+        /// Only the owner may spawn it, funding it like a new wish: the
+        /// transferred value is its deposit and it goes through the same
+        /// checks as `add_wishlist_item_with_options`. The new wish ends one
+        /// interval after the previous one (skipping intervals that already
+        /// lie in the past). Returns the id of the new wish.
+        #[ink(message, payable)]
+        pub fn spawn_next_occurrence(&mut self, id: u32) -> Result<u32> {
+            let template = self.recurring.get(id).ok_or(Error::NoRecurrence)?;
+            let owner = template.owner;
+            if self.get_caller() != owner {
+                return Err(Error::NotOwner);
+            }
+
+            let now = self.now();
+            let step = template.interval.duration();
+            let mut end_date = template.end_date.saturating_add(step);
+            if end_date <= now {
+                let skipped = (now - end_date) / step + 1;
+                end_date = end_date.saturating_add(skipped.saturating_mul(step));
+            }
+
+            self.meter(
+                ink::selector_id!("spawn_next_occurrence"),
+                self.transferred()?,
+            );
+            let next_id = self.add_item(
+                owner,
+                template.description,
+                end_date,
+                template.target,
+                template.options,
+            )?;
+            self.recurring.remove(id);
+            self.env().emit_event(WishRecurred { id, owner, next_id });
+
+            Ok(next_id)
+        }
+
+        /// Stop a recurring wish from coming back, whether it is still active
+        /// or already waiting for its next occurrence.
+        #[ink(message)]
+        pub fn stop_recurrence(&mut self, id: u32) -> Result<()> {
+            let caller = self.get_caller();
+
+            if let Some(mut item) = self.items.get(id) {
+                if item.owner != caller {
+                    return Err(Error::NotOwner);
+                }
+                item.recurrence = None;
+                self.items.insert(id, &item);
+                return Ok(());
+            }
+
+            let template = self.recurring.get(id).ok_or(Error::NoRecurrence)?;
+            if template.owner != caller {
+                return Err(Error::NotOwner);
+            }
+            self.recurring.remove(id);
+            Ok(())
+        }
+
+        /// Stop wish `id` from taking contributions until `resume_wish`, e.g.
+        /// while its details are being updated. Only the owner may pause.
+        #[ink(message)]
+        pub fn pause_wish(&mut self, id: u32) -> Result<()> {
+            let caller = self.get_caller();
+            let mut item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if item.owner != caller {
+                return Err(Error::NotOwner);
+            }
+            if item.paused_at.is_some() {
+                return Err(Error::WishPaused);
+            }
+            item.paused_at = Some(self.now());
+            self.items.insert(id, &item);
+            self.env().emit_event(WishPaused { id, owner: caller });
+            Ok(())
+        }
+
+        /// Let paused wish `id` take contributions again. With
+        /// `extend_deadline` the end date moves back by the time it was paused.
+        #[ink(message)]
+        pub fn resume_wish(&mut self, id: u32, extend_deadline: bool) -> Result<()> {
+            let caller = self.get_caller();
+            let mut item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if item.owner != caller {
+                return Err(Error::NotOwner);
+            }
+            let paused_at = item.paused_at.take().ok_or(Error::NotPaused)?;
+            if extend_deadline {
+                let paused_for = self.now().saturating_sub(paused_at);
+                item.end_date = item.end_date.saturating_add(paused_for);
+                self.index_upsert(ListingIndex::Deadline, id, Amount::from(item.end_date));
+            }
+            let end_date = item.end_date;
+            self.items.insert(id, &item);
+            self.env().emit_event(WishResumed {
+                id,
+                owner: caller,
+                end_date,
+            });
+            Ok(())
+        }
+
+        /// Stop wish `id` from taking further contributions. It can be
+        /// settled at its end date as usual, or right away if the target is
+        /// already met. Only the owner may close funding.
+        #[ink(message)]
+        pub fn close_funding(&mut self, id: u32) -> Result<()> {
+            let caller = self.get_caller();
+            let mut item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if item.owner != caller {
+                return Err(Error::NotOwner);
+            }
+            if item.closed {
+                return Err(Error::FundingClosed);
+            }
+            let now = self.now();
+            if now >= item.end_date {
+                return Err(Error::WishEnded);
+            }
+            item.closed = true;
+            if Self::counted(&item) >= item.target {
+                item.end_date = now;
+                self.index_upsert(ListingIndex::Deadline, id, Amount::from(now));
+            }
+            let end_date = item.end_date;
+            self.items.insert(id, &item);
+            self.env().emit_event(FundingClosed {
+                id,
+                owner: caller,
+                end_date,
+            });
+            Ok(())
+        }
+
+        /// Pledge to contribute `amount` to upcoming wish `id` once it opens.
+        /// Nothing is transferred: at launch `convert_pledges` pays the
+        /// pledge from the caller's deposit if it covers it. Pledging again
+        /// replaces the pledge, and an `amount` of zero withdraws it.
+        #[ink(message)]
+        pub fn pledge(&mut self, id: u32, amount: Amount) -> Result<()> {
+            let caller = self.get_caller();
+            let item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if item.start_date.is_none_or(|start| self.now() >= start) {
+                return Err(Error::NotUpcoming);
+            }
+
+            let mut pledges = self.pledges.get(id).unwrap_or_default();
+            pledges.retain(|pledge| pledge.account != caller);
+            if !amount.is_zero() {
+                if pledges.len() >= MAX_PLEDGES {
+                    return Err(Error::TooManyPledges);
+                }
+                pledges.push(Pledge {
+                    account: caller,
+                    amount,
+                });
+            }
+            self.pledges.insert(id, &pledges);
+            self.env().emit_event(Pledged {
+                id,
+                account: caller,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Turn the pledges to wish `id` into contributions once it has
+        /// started. Anyone may call this. Pledges the pledger's deposit
+        /// cannot cover, or the wish refuses, are dropped and leave the
+        /// deposit untouched.
+        #[ink(message)]
+        pub fn convert_pledges(&mut self, id: u32) -> Result<()> {
+            let item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if item.start_date.is_some_and(|start| self.now() < start) {
+                return Err(Error::WishUpcoming);
+            }
+            for Pledge { account, amount } in self.pledges.take(id).unwrap_or_default() {
+                let balance = self.deposits.get(account).unwrap_or_default();
+                if balance < amount || self.record_contribution(id, account, amount).is_err() {
+                    continue;
+                }
+                self.deposits.insert(account, &(balance - amount));
+                self.held.deposits -= amount;
+                self.env().emit_event(PledgeConverted {
+                    id,
+                    account,
+                    amount,
+                });
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_pledges(&self, id: u32) -> Vec<Pledge> {
+            self.pledges.get(id).unwrap_or_default()
+        }
+
+        /// Nominate the guardians who can recover the caller's wishes should
+        /// the caller lose their key. `threshold` of them must approve a
+        /// recovery. An empty list removes the guardians.
+        #[ink(message)]
+        pub fn set_guardians(&mut self, accounts: Vec<H160>, threshold: u32) -> Result<()> {
+            let caller = self.get_caller();
+            if accounts.is_empty() {
+                self.guardians.remove(caller);
+                return Ok(());
+            }
+            let distinct = accounts
+                .iter()
+                .enumerate()
+                .all(|(i, account)| !accounts[..i].contains(account) && *account != caller);
+            if accounts.len() > MAX_GUARDIANS
+                || !distinct
+                || threshold == 0
+                || threshold as usize > accounts.len()
+            {
+                return Err(Error::InvalidGuardians);
+            }
+            self.guardians.insert(
+                caller,
+                &Guardians {
+                    accounts,
+                    threshold,
+                },
+            );
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_guardians(&self, owner: H160) -> Option<Guardians> {
+            self.guardians.get(owner)
+        }
+
+        /// Approve, as a guardian of `owner`, moving their wishes to
+        /// `new_owner`. Approving a different new owner restarts the
+        /// proposal. Once the threshold is met the recovery can be executed
+        /// after `RECOVERY_DELAY`, unless the owner cancels it.
+        #[ink(message)]
+        pub fn propose_recovery(&mut self, owner: H160, new_owner: H160) -> Result<()> {
+            let caller = self.get_caller();
+            let guardians = self.guardians.get(owner).ok_or(Error::NotGuardian)?;
+            if !guardians.accounts.contains(&caller) {
+                return Err(Error::NotGuardian);
+            }
+
+            let mut recovery = self
+                .recoveries
+                .get(owner)
+                .filter(|recovery| recovery.new_owner == new_owner)
+                .unwrap_or(Recovery {
+                    new_owner,
+                    approvals: Vec::new(),
+                    ready_at: None,
+                });
+            if recovery.approvals.contains(&caller) {
+                return Err(Error::AlreadyVoted);
+            }
+            recovery.approvals.push(caller);
+            if recovery.ready_at.is_none()
+                && recovery.approvals.len() >= guardians.threshold as usize
+            {
+                recovery.ready_at = Some(self.now().saturating_add(RECOVERY_DELAY));
+            }
+            self.recoveries.insert(owner, &recovery);
+            self.env().emit_event(RecoveryProposed {
+                owner,
+                new_owner,
+                guardian: caller,
+            });
+            Ok(())
+        }
+
+        /// Stop the recovery of the caller's wishes.
+        #[ink(message)]
+        pub fn cancel_recovery(&mut self) -> Result<()> {
+            let caller = self.get_caller();
+            self.recoveries.take(caller).ok_or(Error::NoRecovery)?;
+            self.env().emit_event(RecoveryCancelled { owner: caller });
+            Ok(())
+        }
+
+        /// Move every active wish of `owner`, and their guardians, to the new
+        /// owner of an approved recovery whose delay has passed. Anyone may
+        /// call this.
+        #[ink(message)]
+        pub fn execute_recovery(&mut self, owner: H160) -> Result<()> {
+            let recovery = self.recoveries.get(owner).ok_or(Error::NoRecovery)?;
+            match recovery.ready_at {
+                Some(ready_at) if self.now() >= ready_at => {}
+                _ => return Err(Error::RecoveryPending),
+            }
+            self.recoveries.remove(owner);
+            let new_owner = recovery.new_owner;
+
+            let moved = self.active_by_owner.take(owner).unwrap_or_default();
+            for id in moved.iter() {
+                if let Some(mut item) = self.items.get(*id) {
+                    item.owner = new_owner;
+                    self.items.insert(*id, &item);
+                }
+                self.unindex_owner(owner, *id);
+                self.index_owner(new_owner, *id);
+                self.record_history(new_owner, *id);
+            }
+            let mut owned = self.active_by_owner.get(new_owner).unwrap_or_default();
+            owned.extend(moved.iter().copied());
+            owned.sort_unstable();
+            self.active_by_owner.insert(new_owner, &owned);
+            let active = self.active_counts.take(owner).unwrap_or(0);
+            let existing = self.active_counts.get(new_owner).unwrap_or(0);
+            self.active_counts
+                .insert(new_owner, &existing.saturating_add(active));
+            if let Some(guardians) = self.guardians.take(owner) {
+                self.guardians.insert(new_owner, &guardians);
+            }
+
+            self.env()
+                .emit_event(OwnershipRecovered { owner, new_owner });
+            Ok(())
+        }
+
+        /// Hide wish `id` from public listings, or list it again. An unlisted
+        /// wish can still be looked up and funded by its id.
+        #[ink(message)]
+        pub fn set_unlisted(&mut self, id: u32, unlisted: bool) -> Result<()> {
+            let caller = self.get_caller();
+            let mut item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if item.owner != caller {
+                return Err(Error::NotOwner);
+            }
+            item.unlisted = unlisted;
+            self.items.insert(id, &item);
+            Ok(())
+        }
+
+        /// Contribute the transferred value to wish `id`, accepting the terms
+        /// the owner attached to it. `terms` must match the wish's current
+        /// terms hash; the acceptance is recorded for the caller.
+        #[ink(message, payable)]
+        pub fn fund_wish_with_terms(&mut self, id: u32, terms: TermsHash) -> Result<()> {
+            let caller = self.get_caller();
+            let value = self.transferred()?;
+            if value.is_zero() {
+                return Err(Error::InvalidContribution);
+            }
+            self.meter(ink::selector_id!("fund_wish_with_terms"), value);
+            let item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if item.sealed {
+                return Err(Error::SealedWish);
+            }
+            if item.terms != Some(terms) {
+                return Err(Error::TermsMismatch);
+            }
+            self.ensure_attested(AttestationPurpose::Personhood, caller)?;
+
+            self.record_contribution(id, caller, value)?;
+            self.auto_claim_at_target(id)?;
+            self.terms_accepted.insert((id, caller), &terms);
+            self.env().emit_event(TermsAccepted {
+                id,
+                contributor: caller,
+                terms,
+            });
+            Ok(())
+        }
+
+        /// Attach the hash of the terms contributors to wish `id` must
+        /// accept, or `None` to drop them. Earlier acceptances are kept.
+        /// Only the owner may set terms.
+        #[ink(message)]
+        pub fn set_terms(&mut self, id: u32, terms: Option<TermsHash>) -> Result<()> {
+            let mut item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if item.owner != self.get_caller() {
+                return Err(Error::NotOwner);
+            }
+            item.terms = terms;
+            self.items.insert(id, &item);
+            Ok(())
+        }
+
+        /// The terms hash `account` last accepted when contributing to wish
+        /// `id`.
+        #[ink(message)]
+        pub fn get_terms_accepted(&self, id: u32, account: H160) -> Option<TermsHash> {
+            self.terms_accepted.get((id, account))
+        }
+
+        /// Commit to a hidden contribution to sealed wish `id`. `hash` is the
+        /// Keccak-256 of the SCALE-encoded `(id, contributor, amount, salt)`,
+        /// and the transferred value, at least `amount`, is held until the
+        /// commitment is revealed with `reveal_contribution`.
+        #[ink(message, payable)]
+        pub fn commit_contribution(&mut self, id: u32, hash: [u8; 32]) -> Result<()> {
+            let caller = self.get_caller();
+            let deposit = self.transferred()?;
+            if deposit.is_zero() {
+                return Err(Error::InvalidContribution);
+            }
+            self.meter(ink::selector_id!("commit_contribution"), deposit);
+            let item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if !item.sealed {
+                return Err(Error::SealedWish);
+            }
+            if self.now() >= item.end_date {
+                return Err(Error::WishEnded);
+            }
+            if self.commitments.contains((id, caller)) {
+                return Err(Error::AlreadyCommitted);
+            }
+            self.ensure_attested(AttestationPurpose::Personhood, caller)?;
+
+            self.commitments
+                .insert((id, caller), &Commitment { hash, deposit });
+            self.held.committed += deposit;
+            self.env().emit_event(ContributionCommitted {
+                id,
+                contributor: caller,
+                deposit,
+            });
+            Ok(())
+        }
+
+        /// Reveal the caller's commitment to sealed wish `id` during the
+        /// `REVEAL_WINDOW` after its end date. `amount` is recorded as their
+        /// contribution and the rest of the deposit is returned.
+        #[ink(message)]
+        pub fn reveal_contribution(
+            &mut self,
+            id: u32,
+            amount: Amount,
+            salt: [u8; 32],
+        ) -> Result<()> {
+            let caller = self.get_caller();
+            let item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            let now = self.now();
+            if now < item.end_date || now >= item.end_date.saturating_add(REVEAL_WINDOW) {
+                return Err(Error::NotRevealPhase);
+            }
+            let commitment = self
+                .commitments
+                .get((id, caller))
+                .ok_or(Error::NoCommitment)?;
+            let hash = self
+                .env()
+                .hash_encoded::<Keccak256, _>(&(id, caller, amount, salt));
+            if hash != commitment.hash || amount.is_zero() || amount > commitment.deposit {
+                return Err(Error::InvalidReveal);
+            }
+
+            self.commitments.remove((id, caller));
+            self.held.committed -= commitment.deposit;
+            self.record_contribution(id, caller, amount)?;
+            let change = commitment.deposit - amount;
+            if !change.is_zero() {
+                self.env()
+                    .transfer(caller, math::to_native(change))
+                    .map_err(|_| Error::TransferFailed)?;
+            }
+            self.env().emit_event(ContributionRevealed {
+                id,
+                contributor: caller,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Take back the deposit of a commitment to wish `id` that was not
+        /// revealed in time.
+        #[ink(message)]
+        pub fn reclaim_commitment(&mut self, id: u32) -> Result<()> {
+            let caller = self.get_caller();
+            let commitment = self
+                .commitments
+                .get((id, caller))
+                .ok_or(Error::NoCommitment)?;
+            if let Some(item) = self.items.get(id) {
+                if self.now() < item.end_date.saturating_add(REVEAL_WINDOW) {
+                    return Err(Error::NotRevealPhase);
+                }
+            }
+            self.commitments.remove((id, caller));
+            self.held.committed -= commitment.deposit;
+            self.env()
+                .transfer(
+                    self.payout_address(caller),
+                    math::to_native(commitment.deposit),
+                )
+                .map_err(|_| Error::TransferFailed)
+        }
+
+        /// Split the campaign for wish `id` into sequential `rounds`, each with
+        /// its own cap and minimum contribution. Only possible before anyone
+        /// but the owner has contributed; the last round must end by the
+        /// wish's end date.
+        #[ink(message)]
+        pub fn set_rounds(&mut self, id: u32, rounds: Vec<Round>) -> Result<()> {
+            let item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if item.owner != self.get_caller() {
+                return Err(Error::NotOwner);
+            }
+            if item.sealed {
+                return Err(Error::SealedWish);
+            }
+            let ordered = rounds.windows(2).all(|w| w[0].ends_at < w[1].ends_at);
+            let within = rounds.last().is_none_or(|r| r.ends_at <= item.end_date);
+            if item.contributor_count > 0 || rounds.len() > MAX_ROUNDS || !ordered || !within {
+                return Err(Error::InvalidRounds);
+            }
+            if rounds.is_empty() {
+                self.rounds.remove(id);
+            } else {
+                self.rounds.insert(id, &rounds);
+            }
+            Ok(())
+        }
+
+        /// The round wish `id` is in, with its index, if it has rounds.
+        #[ink(message)]
+        pub fn get_current_round(&self, id: u32) -> Option<(u32, Round)> {
+            let now = self.now();
+            self.rounds
+                .get(id)?
+                .into_iter()
+                .enumerate()
+                .find(|(_, r)| now < r.ends_at)
+                .map(|(i, r)| (i as u32, r))
+        }
+
+        /// Open a pool towards wish `id` that friends can pay into with
+        /// `join_pool`. Nothing reaches the wish until the pool holds
+        /// `target_sum`; if that does not happen by `deadline` the members
+        /// are refunded. Returns the pool id.
+        #[ink(message)]
+        pub fn create_pool(&mut self, id: u32, target_sum: Amount, deadline: u64) -> Result<u32> {
+            let caller = self.get_caller();
+            let item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if target_sum.is_zero() {
+                return Err(Error::InvalidTarget);
+            }
+            if deadline <= self.now() || deadline > item.end_date {
+                return Err(Error::WishEnded);
+            }
+
+            let pool_id = self.next_pool_id;
+            self.next_pool_id = pool_id.checked_add(1).ok_or(Error::InvalidContribution)?;
+            self.pools.insert(
+                pool_id,
+                &Pool {
+                    wish_id: id,
+                    creator: caller,
+                    target_sum,
+                    deadline,
+                    total: math::ZERO,
+                    members: Vec::new(),
+                    status: PoolStatus::Open,
+                },
+            );
+            self.env().emit_event(PoolCreated {
+                pool_id,
+                id,
+                creator: caller,
+                target_sum,
+                deadline,
+            });
+            Ok(pool_id)
+        }
+
+        /// Commit the transferred value to pool `pool_id`. The payment that
+        /// fills the pool applies every member's share to the wish at once.
+        #[ink(message, payable)]
+        pub fn join_pool(&mut self, pool_id: u32) -> Result<()> {
+            let caller = self.get_caller();
+            let value = self.transferred()?;
+            if value.is_zero() {
+                return Err(Error::InvalidContribution);
+            }
+            self.meter(ink::selector_id!("join_pool"), value);
+            let mut pool = self.pools.get(pool_id).ok_or(Error::PoolNotFound)?;
+            if pool.status != PoolStatus::Open || self.now() > pool.deadline {
+                return Err(Error::PoolClosed);
+            }
+            self.ensure_attested(AttestationPurpose::Personhood, caller)?;
+            match pool.members.iter().position(|m| m.0 == caller) {
+                Some(position) => pool.members[position].1 += value,
+                None if pool.members.len() < MAX_POOL_MEMBERS => pool.members.push((caller, value)),
+                None => return Err(Error::PoolClosed),
+            }
+            pool.total += value;
+            self.held.pooled += value;
+            self.env().emit_event(PoolJoined {
+                pool_id,
+                id: pool.wish_id,
+                member: caller,
+                amount: value,
+            });
+
+            if pool.total >= pool.target_sum {
+                self.held.pooled -= pool.total;
+                for (member, amount) in pool.members.iter() {
+                    self.record_contribution(pool.wish_id, *member, *amount)?;
+                }
+                pool.status = PoolStatus::Applied;
+                self.env().emit_event(PoolApplied {
+                    pool_id,
+                    id: pool.wish_id,
+                    total: pool.total,
+                });
+            }
+            self.pools.insert(pool_id, &pool);
+            Ok(())
+        }
+
+        /// Refund the members of pool `pool_id` through the pull ledger once
+        /// it has missed its deadline, or its wish is gone. Anyone may call this.
+        #[ink(message)]
+        pub fn refund_pool(&mut self, pool_id: u32) -> Result<()> {
+            let mut pool = self.pools.get(pool_id).ok_or(Error::PoolNotFound)?;
+            if pool.status != PoolStatus::Open {
+                return Err(Error::PoolClosed);
+            }
+            let wish_active = self.items.get(pool.wish_id).is_some();
+            if wish_active && self.now() <= pool.deadline {
+                return Err(Error::PoolOpen);
+            }
+
+            self.held.pooled -= pool.total;
+            for (member, amount) in pool.members.iter() {
+                self.credit(*member, *amount);
+            }
+            pool.status = PoolStatus::Refunded;
+            self.pools.insert(pool_id, &pool);
+            self.env().emit_event(PoolRefunded {
+                pool_id,
+                id: pool.wish_id,
+                total: pool.total,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_pool(&self, pool_id: u32) -> Option<Pool> {
+            self.pools.get(pool_id)
+        }
+
+        /// Group open wishes `ids` of the caller into a campaign shared as
+        /// `slug`. Returns the id of the new campaign.
+        #[ink(message)]
+        pub fn create_campaign(&mut self, slug: String, ids: Vec<u32>) -> Result<u32> {
+            let caller = self.get_caller();
+            if slug.is_empty() || slug.len() > MAX_SLUG_LEN {
+                return Err(Error::InvalidCampaign);
+            }
+            if self.campaign_slugs.contains(&slug) {
+                return Err(Error::SlugTaken);
+            }
+            if ids.is_empty() || ids.len() > MAX_CAMPAIGN_WISHES {
+                return Err(Error::InvalidCampaign);
+            }
+            for (i, id) in ids.iter().enumerate() {
+                if ids[..i].contains(id) {
+                    return Err(Error::InvalidCampaign);
+                }
+                self.ensure_campaign_wish(*id, caller)?;
+            }
+
+            let campaign_id = self.next_campaign_id;
+            self.next_campaign_id = campaign_id.checked_add(1).ok_or(Error::InvalidCampaign)?;
+            self.campaign_slugs.insert(&slug, &campaign_id);
+            self.campaigns.insert(
+                campaign_id,
+                &Campaign {
+                    owner: caller,
+                    slug: slug.clone(),
+                    wishes: ids,
+                },
+            );
+            self.env().emit_event(CampaignCreated {
+                campaign_id,
+                owner: caller,
+                slug,
+            });
+            Ok(campaign_id)
+        }
+
+        /// Add open wish `id` of the caller to their campaign `campaign_id`.
+        #[ink(message)]
+        pub fn add_to_campaign(&mut self, campaign_id: u32, id: u32) -> Result<()> {
+            let caller = self.get_caller();
+            let mut campaign = self
+                .campaigns
+                .get(campaign_id)
+                .ok_or(Error::CampaignNotFound)?;
+            if campaign.owner != caller {
+                return Err(Error::NotOwner);
+            }
+            if campaign.wishes.len() >= MAX_CAMPAIGN_WISHES || campaign.wishes.contains(&id) {
+                return Err(Error::InvalidCampaign);
+            }
+            self.ensure_campaign_wish(id, caller)?;
+            campaign.wishes.push(id);
+            self.campaigns.insert(campaign_id, &campaign);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_campaign(&self, campaign_id: u32) -> Option<Campaign> {
+            self.campaigns.get(campaign_id)
+        }
+
+        /// The id of the campaign shared as `slug`, if any.
+        #[ink(message)]
+        pub fn get_campaign_by_slug(&self, slug: String) -> Option<u32> {
+            self.campaign_slugs.get(&slug)
+        }
+
+        /// Target and raise of all wishes in campaign `campaign_id`.
+        #[ink(message)]
+        pub fn get_campaign_totals(&self, campaign_id: u32) -> Result<CampaignTotals> {
+            let campaign = self
+                .campaigns
+                .get(campaign_id)
+                .ok_or(Error::CampaignNotFound)?;
+            let mut totals = CampaignTotals {
+                target: math::ZERO,
+                raised: math::ZERO,
+            };
+            for id in campaign.wishes {
+                if let Some(item) = self.items.get(id) {
+                    totals.target += item.target;
+                    totals.raised += Self::pot(&item);
+                } else if let Some(record) = self.archive.get(id) {
+                    totals.target += record.target;
+                    totals.raised += record.pot;
+                }
+            }
+            Ok(totals)
+        }
+
+        /// Deposit native value to the caller's on-contract balance, which
+        /// standing orders draw their installments from.
+        #[ink(message, payable)]
+        pub fn deposit(&mut self) -> Result<()> {
+            let caller = self.get_caller();
+            let value = self.transferred()?;
+            if value.is_zero() {
+                return Err(Error::InvalidContribution);
+            }
+            self.meter(ink::selector_id!("deposit"), value);
+
+            let balance = self.deposits.get(caller).unwrap_or_default();
+            self.deposits.insert(caller, &(balance + value));
+            self.held.deposits += value;
+            Ok(())
+        }
+
+        /// Withdraw `amount` from the caller's on-contract deposit.
+        #[ink(message)]
+        pub fn withdraw_deposit(&mut self, amount: Amount) -> Result<()> {
+            let caller = self.get_caller();
+            let balance = self.deposits.get(caller).unwrap_or_default();
+            if amount > balance {
+                return Err(Error::InsufficientDeposit);
+            }
+
+            self.deposits.insert(caller, &(balance - amount));
+            self.held.deposits -= amount;
+            self.meter(ink::selector_id!("withdraw_deposit"), amount);
+            self.env()
+                .transfer(self.payout_address(caller), math::to_native(amount))
+                .map_err(|_| Error::TransferFailed)
+        }
+
+        #[ink(message)]
+        pub fn get_deposit(&self, account: H160) -> Amount {
+            self.deposits.get(account).unwrap_or_default()
+        }
+
+        /// Approve a standing order paying `amount` towards wish `id` every
+        /// `period` milliseconds from the caller's deposit, until `cap` has
+        /// been paid in total. Replaces any existing order for the same wish.
+        #[ink(message)]
+        pub fn approve_standing_order(
+            &mut self,
+            id: u32,
+            amount: Amount,
+            period: u64,
+            cap: Amount,
+        ) -> Result<()> {
+            let caller = self.get_caller();
+            self.set_standing_order(caller, id, amount, period, cap)
+        }
+
+        /// Approve a standing order on behalf of `contributor`, who signed it
+        /// off-chain as an EIP-712 `StandingOrderPermit` over this contract's
+        /// `domain_separator`. Anyone may submit the permit; it carries the
+        /// contributor's next nonce and is void after `deadline`.
+        #[ink(message)]
+        #[allow(clippy::too_many_arguments)]
+        pub fn approve_standing_order_by_permit(
+            &mut self,
+            contributor: H160,
+            id: u32,
+            amount: Amount,
+            period: u64,
+            cap: Amount,
+            deadline: u64,
+            signature: [u8; 65],
+        ) -> Result<()> {
+            if self.now() > deadline {
+                return Err(Error::PermitExpired);
+            }
+            let nonce = self.permit_nonces.get(contributor).unwrap_or_default();
+            let mut encoded = Vec::new();
+            encoded.extend_from_slice(
+                &self
+                    .env()
+                    .hash_bytes::<Keccak256>(STANDING_ORDER_PERMIT_TYPE.as_bytes()),
+            );
+            encoded.extend_from_slice(&address_word(contributor));
+            encoded.extend_from_slice(&word(U256::from(id)));
+            encoded.extend_from_slice(&word(math::to_native(amount)));
+            encoded.extend_from_slice(&word(U256::from(period)));
+            encoded.extend_from_slice(&word(math::to_native(cap)));
+            encoded.extend_from_slice(&word(U256::from(nonce)));
+            encoded.extend_from_slice(&word(U256::from(deadline)));
+            let struct_hash = self.env().hash_bytes::<Keccak256>(&encoded);
+
+            if self.recover_signer(struct_hash, &signature) != Some(contributor) {
+                return Err(Error::InvalidSignature);
+            }
+            self.permit_nonces.insert(contributor, &(nonce + 1));
+            self.set_standing_order(contributor, id, amount, period, cap)
+        }
+
+        /// The EIP-712 domain separator permits are signed against. It binds
+        /// signatures to this contract and chain, so they cannot be replayed
+        /// on another deployment.
+        #[ink(message)]
+        pub fn domain_separator(&self) -> [u8; 32] {
+            let mut encoded = Vec::new();
+            encoded.extend_from_slice(&self.env().hash_bytes::<Keccak256>(DOMAIN_TYPE.as_bytes()));
+            encoded.extend_from_slice(
+                &self
+                    .env()
+                    .hash_bytes::<Keccak256>(PERMIT_DOMAIN_NAME.as_bytes()),
+            );
+            encoded.extend_from_slice(
+                &self
+                    .env()
+                    .hash_bytes::<Keccak256>(PERMIT_DOMAIN_VERSION.as_bytes()),
+            );
+            encoded.extend_from_slice(&word(U256::from(self.chain_id)));
+            encoded.extend_from_slice(&address_word(self.env().address()));
+            self.env().hash_bytes::<Keccak256>(&encoded)
+        }
+
+        /// The nonce the next permit signed by `account` must carry.
+        #[ink(message)]
+        pub fn get_permit_nonce(&self, account: H160) -> u64 {
+            self.permit_nonces.get(account).unwrap_or_default()
+        }
+
+        /// Set the chain id signed into the permit domain.
+        #[ink(message)]
+        pub fn set_chain_id(&mut self, chain_id: u64) -> Result<()> {
+            self.ensure_admin()?;
+            self.chain_id = chain_id;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn cancel_standing_order(&mut self, id: u32) -> Result<()> {
+            let caller = self.get_caller();
+            self.standing_orders
+                .take((caller, id))
+                .ok_or(Error::StandingOrderNotFound)?;
+            self.env().emit_event(StandingOrderStopped {
+                id,
+                contributor: caller,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_standing_order(&self, contributor: H160, id: u32) -> Option<StandingOrder> {
+            self.standing_orders.get((contributor, id))
+        }
+
+        /// Execute the due installment of `contributor`'s standing order on
+        /// wish `id`. Callable by anyone (e.g. a keeper bot).
+        ///
+        /// The order is removed once its cap is reached, or if the deposit can
+        /// no longer cover an installment or the wish is gone.
+        #[ink(message)]
+        pub fn execute_standing_order(&mut self, contributor: H160, id: u32) -> Result<()> {
+            let mut order = self
+                .standing_orders
+                .get((contributor, id))
+                .ok_or(Error::StandingOrderNotFound)?;
+            if self.now() < order.next_due {
+                return Err(Error::InstallmentNotDue);
+            }
+
+            let remaining = order.cap - order.paid;
+            let installment = if order.amount < remaining {
+                order.amount
+            } else {
+                remaining
+            };
+            let balance = self.deposits.get(contributor).unwrap_or_default();
+            if balance < installment || !self.items.contains(id) {
+                self.standing_orders.remove((contributor, id));
+                self.env()
+                    .emit_event(StandingOrderStopped { id, contributor });
+                return Ok(());
+            }
+
+            self.deposits.insert(contributor, &(balance - installment));
+            self.held.deposits -= installment;
+            self.record_contribution(id, contributor, installment)?;
+            self.env().emit_event(InstallmentExecuted {
+                id,
+                contributor,
+                amount: installment,
+            });
+
+            order.paid += installment;
+            if order.paid >= order.cap {
+                self.standing_orders.remove((contributor, id));
+                self.env()
+                    .emit_event(StandingOrderStopped { id, contributor });
+            } else {
+                order.next_due = order.next_due.saturating_add(order.period);
+                self.standing_orders.insert((contributor, id), &order);
+            }
+            Ok(())
+        }
+
+        /// Pull the caller's whole contribution out of wish `id` before its
+        /// end date. The wish's exit penalty is withheld and stays in the pot.
+        #[ink(message)]
+        pub fn exit_contribution(&mut self, id: u32) -> Result<()> {
+            let caller = self.get_caller();
+            if self.items.get(id).is_none() {
+                return Err(Error::WishNotFound);
+            }
+            let balance = self.stake_of(id, caller);
+            if balance.is_zero() {
+                return Err(Error::NotContributor);
+            }
+            self.withdraw_contribution(id, balance)
+        }
+
+        /// Take `amount` of the caller's contribution back out of wish `id`
+        /// before its end date, less the wish's exit penalty. The amount is
+        /// also taken off the caller's weight on a pending early claim, and
+        /// off the vote cast with it. Not available within the wish's withdrawal
+        /// lock window, unless the wish failed at its soft deadline.
+        #[ink(message)]
+        pub fn withdraw_contribution(&mut self, id: u32, amount: Amount) -> Result<()> {
+            let caller = self.get_caller();
+            let mut item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            let now = self.now();
+            if now >= item.end_date {
+                return Err(Error::WishEnded);
+            }
+            if now >= item.end_date.saturating_sub(item.withdrawal_lock)
+                && !self.failed_early(&item)
+            {
+                return Err(Error::ContributionsLocked);
+            }
+
+            let balance = self.stake_of(id, caller);
+            if balance.is_zero() {
+                return Err(Error::NotContributor);
+            }
+            if amount.is_zero() || amount > balance {
+                return Err(Error::InvalidContribution);
+            }
+            self.reduce_stake(id, &mut item, caller, amount);
+            self.reduce_early_claim_weight(id, caller, amount);
+            let penalty = math::bps(amount, item.exit_penalty_bps);
+            let refunded = amount - penalty;
+            item.forfeited += penalty;
+            self.items.insert(id, &item);
+
+            self.meter(ink::selector_id!("withdraw_contribution"), refunded);
+            self.env()
+                .transfer(self.payout_address(caller), math::to_native(refunded))
+                .map_err(|_| Error::TransferFailed)?;
+            self.env().emit_event(ContributionExited {
+                id,
+                contributor: caller,
+                refunded,
+                penalty,
+            });
+            Ok(())
+        }
+
+        /// Refund `contributor`'s whole position in wish `id` to their credits,
+        /// without an exit penalty, and drop them from the contributors and
+        /// any early-claim vote. Only the owner may reject a contribution,
+        /// and only before the end date.
+        #[ink(message)]
+        pub fn reject_contribution(&mut self, id: u32, contributor: H160) -> Result<()> {
+            let caller = self.get_caller();
+            let mut item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if item.owner != caller {
+                return Err(Error::NotOwner);
+            }
+            if self.now() >= item.end_date {
+                return Err(Error::WishEnded);
+            }
+            let refunded = self.stake_of(id, contributor);
+            if refunded.is_zero() {
+                return Err(Error::NotContributor);
+            }
+            self.reduce_stake(id, &mut item, contributor, refunded);
+            self.reduce_early_claim_weight(id, contributor, refunded);
+            self.items.insert(id, &item);
+            self.credit(contributor, refunded);
+            self.env().emit_event(ContributionRejected {
+                id,
+                contributor,
+                refunded,
+            });
+            Ok(())
+        }
+
+        /// Ban `account` from contributing to wish `id`, or lift the ban.
+        /// Existing contributions stay; see `reject_contribution`. Only the
+        /// owner may ban.
+        #[ink(message)]
+        pub fn set_banned(&mut self, id: u32, account: H160, banned: bool) -> Result<()> {
+            let item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if item.owner != self.get_caller() {
+                return Err(Error::NotOwner);
+            }
+            if banned {
+                self.bans.insert((id, account), &());
+            } else {
+                self.bans.remove((id, account));
+            }
+            self.env().emit_event(ContributorBanned {
+                id,
+                account,
+                banned,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_banned(&self, id: u32, account: H160) -> bool {
+            self.bans.contains((id, account))
+        }
+
+        /// Withdraw part of the pot before `end_date`. The owner may take out
+        /// in total up to half of the funded share of the pot, e.g. 25% of it
+        /// once the wish is 50% funded. Withdrawals are deducted at settlement.
+        #[ink(message)]
+        pub fn withdraw_progress(&mut self, id: u32, amount: Amount) -> Result<()> {
+            let caller = self.get_caller();
+            let mut item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if item.owner != caller {
+                return Err(Error::NotOwner);
+            }
+            if item.charity.is_some() {
+                return Err(Error::CharityPayoutFixed);
+            }
+            if !item.allow_partial_withdrawal {
+                return Err(Error::PartialWithdrawalDisabled);
+            }
+            if self.now() >= item.end_date {
+                return Err(Error::WishEnded);
+            }
+
+            // Only the owner's own funds back the allowance, so contributors
+            // can always take their stakes back out.
+            let progress = Self::funded_bps(&item).min(Amount::from(10_000u16));
+            let allowance =
+                math::mul_div(Self::owner_funds(&item), progress, Amount::from(20_000u16));
+            if item.withdrawn + amount > allowance {
+                return Err(Error::WithdrawalLimitExceeded);
+            }
+
+            item.withdrawn += amount;
+            self.items.insert(id, &item);
+            self.meter(ink::selector_id!("withdraw_progress"), amount);
+            self.env()
+                .transfer(caller, math::to_native(amount))
+                .map_err(|_| Error::TransferFailed)?;
+            self.env().emit_event(ProgressWithdrawn {
+                id,
+                owner: caller,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Agree, or stop agreeing, to carry the caller's contribution to wish
+        /// `id` into its successor should it fail and be rolled over.
+        #[ink(message)]
+        pub fn consent_rollover(&mut self, id: u32, consent: bool) -> Result<()> {
+            let caller = self.get_caller();
+            if consent {
+                self.rollover_consents.insert((id, caller), &());
+            } else {
+                self.rollover_consents.remove((id, caller));
+            }
+            Ok(())
+        }
+
+        /// Consent to the rollover of every wish the caller contributes to.
+        #[ink(message)]
+        pub fn set_rollover_preapproval(&mut self, approved: bool) -> Result<()> {
+            let caller = self.get_caller();
+            if approved {
+                self.rollover_preapproved.insert(caller, &());
+            } else {
+                self.rollover_preapproved.remove(caller);
+            }
+            Ok(())
+        }
+
+        /// Start a new attempt at failed wish `id`, ending at `new_end_date`.
+        /// The successor keeps the wish's settings and the owner's side of
+        /// the pot. Contributors who consented, directly or in advance, are
+        /// carried over; the rest are credited their contribution. Only the
+        /// owner may roll a wish over. Returns the id of the successor.
+        #[ink(message)]
+        pub fn rollover(&mut self, id: u32, new_end_date: u64) -> Result<u32> {
+            let caller = self.get_caller();
+            let mut item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if item.owner != caller {
+                return Err(Error::NotOwner);
+            }
+            let now = self.now();
+            let ended = now >= item.end_date || self.failed_early(&item);
+            if !ended || !self.fell_short(id, &item) {
+                return Err(Error::NotFailed);
+            }
+            if new_end_date <= now {
+                return Err(Error::WishEnded);
+            }
+
+            let (carried, refunded): (Vec<_>, Vec<_>) = self
+                .contributors_of(id, &item)
+                .into_iter()
+                .partition(|(account, _)| {
+                    self.rollover_consents.contains((id, *account))
+                        || self.rollover_preapproved.contains(*account)
+                });
+            let kept = carried
+                .iter()
+                .fold(Self::owner_funds(&item) + item.forfeited, |acc, cur| {
+                    acc + cur.1
+                });
+            if item.withdrawn > kept {
+                return Err(Error::WithdrawalLimitExceeded);
+            }
+
+            let successor = self.create_wish(
+                caller,
+                item.description.clone(),
+                new_end_date,
+                item.target,
+                item.raised,
+                item.options(),
+            )?;
+            let mut next = self.items.get(successor).ok_or(Error::WishNotFound)?;
+            next.owner_topups = item.owner_topups;
+            next.forfeited = item.forfeited;
+            next.withdrawn = item.withdrawn;
+            for (account, amount) in carried.iter() {
+                self.add_stake(successor, &mut next, *account, *amount);
+            }
+            let funded = Self::funded_bps(&next);
+            next.reach(funded);
+            self.items.insert(successor, &next);
+            self.update_funded_index(successor, funded);
+
+            for (account, amount) in refunded {
+                self.credit(account, amount);
+            }
+            // The successor recurs in its place.
+            item.recurrence = None;
+            self.retire_wish(id, item, SettlementOutcome::RolledOver);
+            self.env().emit_event(WishRolledOver {
+                id,
+                successor,
+                owner: caller,
+                carried: carried.iter().fold(math::ZERO, |acc, cur| acc + cur.1),
+            });
+            Ok(successor)
+        }
+
+        #[ink(message)]
+        pub fn split_raised_wish(&mut self, id: u32) -> Result<()> {
+            let caller = self.get_caller();
+            let item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if item.funding_model == FundingModel::KeepWhatYouRaise {
+                return Err(Error::NotRefundable);
+            }
+            let now = self.now();
+            let reveal_end = item.end_date.saturating_add(REVEAL_WINDOW);
+            if item.sealed && now < reveal_end {
+                return Err(Error::NotRevealPhase);
+            }
+            if self.stake_of(id, caller).is_zero() {
+                return Err(Error::NotContributor);
+            }
+            if now < item.end_date && !self.failed_early(&item) {
+                return Err(Error::EndDateNotReached);
+            }
+            if !self.fell_short(id, &item) {
+                return Err(Error::NotFailed);
+            }
+            let total_worth = self.refund_contributors(id, item, SettlementOutcome::Split);
+            self.meter(ink::selector_id!("split_raised_wish"), total_worth);
+            Ok(())
+        }
+
+        /// Call wish `id` off before its end date. The owner's side of the
+        /// pot is credited back to them and every contributor can take their
+        /// contribution back with `claim_refund`.
+        #[ink(message)]
+        pub fn cancel_wish(&mut self, id: u32) -> Result<()> {
+            let caller = self.get_caller();
+            let item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if item.owner != caller {
+                return Err(Error::NotOwner);
+            }
+            if self.now() >= item.end_date {
+                return Err(Error::WishEnded);
+            }
+
+            let refunded = self.return_contributions(id, &item);
+            self.credit(caller, refunded);
+            self.retire_wish(id, item, SettlementOutcome::Cancelled);
+            self.env().emit_event(WishCancelled {
+                id,
+                owner: caller,
+                refunded,
+            });
+            Ok(())
+        }
+
+        /// Settle wish `id`, which opted into automatic claims, to its owner
+        /// once it has met its target or can be claimed. The payout is credited to the pull
+        /// ledger. Anyone may poke a wish.
+        #[ink(message)]
+        pub fn poke(&mut self, id: u32) -> Result<()> {
+            let item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if !item.auto_claim {
+                return Err(Error::NotAutoClaim);
+            }
+            if Self::counted(&item) < item.target && !self.can_claim_now(id, &item) {
+                return Err(Error::NotClaimable);
+            }
+            self.auto_claim(id, item)
+        }
+
+        /// Claim every wish of the caller that can be claimed now. Visits at
+        /// most `MAX_BATCH_SIZE` wishes with ids from `cursor` on; call again
+        /// with the returned cursor until it is `None`.
+        #[ink(message)]
+        pub fn claim_all(&mut self, cursor: u32) -> Result<BatchResult> {
+            let caller = self.get_caller();
+            let owned = self.active_by_owner.get(caller).unwrap_or_default();
+            let mut pending = owned.into_iter().filter(|id| *id >= cursor);
+            let mut settled = Vec::new();
+            for id in pending.by_ref().take(MAX_BATCH_SIZE) {
+                let Some(item) = self.items.get(id) else {
+                    continue;
+                };
+                if self.can_claim_now(id, &item) {
+                    self.settle_claim(id, item, true)?;
+                    settled.push(id);
+                }
+            }
+            Ok(BatchResult {
+                settled,
+                next: pending.next(),
+            })
+        }
+
+        /// Confirm, as a contributor to claimed wish `id`, that it was
+        /// fulfilled. The escrow is released to the beneficiary once
+        /// confirmations reach `FULFILLMENT_QUORUM_BPS` of contributions.
+        #[ink(message)]
+        pub fn confirm_fulfillment(&mut self, id: u32) -> Result<()> {
+            let caller = self.get_caller();
+            let mut escrow = self.escrows.get(id).ok_or(Error::NoEscrow)?;
+            if self.now() > escrow.deadline {
+                return Err(Error::WishEnded);
+            }
+            let weight = self.stakes.get((id, caller)).ok_or(Error::NotContributor)?;
+            if self.fulfillment_confirmations.contains((id, caller)) {
+                return Err(Error::AlreadyConfirmed);
+            }
+            self.fulfillment_confirmations.insert((id, caller), &());
+            escrow.confirmed += weight;
+            self.env().emit_event(FulfillmentConfirmed {
+                id,
+                contributor: caller,
+                weight,
+            });
+
+            if escrow.confirmed * Amount::from(10_000u16)
+                < escrow.contributed * Amount::from(FULFILLMENT_QUORUM_BPS)
+            {
+                self.escrows.insert(id, &escrow);
+                return Ok(());
+            }
+            self.escrows.remove(id);
+            self.held.escrowed -= escrow.amount;
+            self.pay_out(id, escrow.beneficiary, escrow.amount)?;
+            self.payout_splits.remove(id);
+            self.env().emit_event(EscrowReleased {
+                id,
+                beneficiary: escrow.beneficiary,
+                amount: escrow.amount,
+            });
+            Ok(())
+        }
+
+        /// Refund the escrow of wish `id` to its contributors, pro rata, once
+        /// the fulfillment window has passed without a quorum. Contributors
+        /// collect their refund with `withdraw_payout`.
+        #[ink(message)]
+        pub fn refund_escrow(&mut self, id: u32) -> Result<()> {
+            let escrow = self.escrows.get(id).ok_or(Error::NoEscrow)?;
+            if self.now() <= escrow.deadline {
+                return Err(Error::EscrowOpen);
+            }
+            self.escrows.remove(id);
+            self.payout_splits.remove(id);
+            self.held.escrowed -= escrow.amount;
+
+            self.distribute(id, escrow.amount, escrow.contributed);
+            self.env().emit_event(EscrowRefunded {
+                id,
+                beneficiary: escrow.beneficiary,
+                amount: escrow.amount,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_escrow(&self, id: u32) -> Option<Escrow> {
+            self.escrows.get(id)
+        }
+
+        /// Withdraw the caller's share of everything distributed to the
+        /// contributors of wish `id` on a split, force-cancel or escrow
+        /// refund.
+        #[ink(message)]
+        pub fn withdraw_payout(&mut self, id: u32) -> Result<()> {
+            let caller = self.get_caller();
+            let amount = self.take_payout(id, caller);
+            if amount.is_zero() {
+                return Err(Error::NothingToWithdraw);
+            }
+            self.meter(ink::selector_id!("withdraw_payout"), amount);
+            self.env()
+                .transfer(self.payout_address(caller), math::to_native(amount))
+                .map_err(|_| Error::TransferFailed)
+        }
+
+        /// Withdraw the caller's payouts from every settled wish they took
+        /// part in, in one transfer. Visits at most `MAX_BATCH_SIZE` wishes
+        /// of the caller's settlement history from position `cursor` on; call
+        /// again with the returned cursor until it is `None`.
+        #[ink(message)]
+        pub fn refund_all(&mut self, cursor: u32) -> Result<BatchResult> {
+            let caller = self.get_caller();
+            let len = self.account_history_len.get(caller).unwrap_or(0);
+            let start = cursor.min(len);
+            let end = start.saturating_add(MAX_BATCH_SIZE as u32).min(len);
+
+            let mut settled = Vec::new();
+            let mut total = math::ZERO;
+            for n in start..end {
+                let Some(id) = self.account_history.get((caller, n)) else {
+                    continue;
+                };
+                let amount = self.take_payout(id, caller);
+                if !amount.is_zero() {
+                    total += amount;
+                    settled.push(id);
+                }
+            }
+            if !total.is_zero() {
+                self.meter(ink::selector_id!("refund_all"), total);
+                self.env()
+                    .transfer(self.payout_address(caller), math::to_native(total))
+                    .map_err(|_| Error::TransferFailed)?;
+            }
+            Ok(BatchResult {
+                settled,
+                next: (end < len).then_some(end),
+            })
+        }
+
+        /// What `account` can currently withdraw from wish `id` with
+        /// `withdraw_payout`.
+        #[ink(message)]
+        pub fn get_payout(&self, id: u32, account: H160) -> Amount {
+            let stake = self.stakes.get((id, account)).unwrap_or_default();
+            let per_share = self.payout_per_share.get(id).unwrap_or_default();
+            let withdrawn = self.payout_withdrawn.get((id, account)).unwrap_or_default();
+            math::mul_div(stake, per_share, Amount::from(PAYOUT_PRECISION)) - withdrawn
+        }
+
+        /// Pay back part of the loan taken through claimed wish `id`. The
+        /// repayment is shared out among the contributors pro rata, to
+        /// withdraw through `withdraw_payout`. Installments repaid by their
+        /// due date count towards the owner's reputation, late ones against
+        /// it.
+        #[ink(message, payable)]
+        pub fn repay(&mut self, id: u32) -> Result<()> {
+            let amount = self.transferred()?;
+            self.meter(ink::selector_id!("repay"), amount);
+            let mut loan = self.loans.get(id).ok_or(Error::NoLoan)?;
+            let outstanding = loan.principal - loan.repaid;
+            if amount.is_zero() || amount > outstanding {
+                return Err(Error::InvalidContribution);
+            }
+            let now = self.now();
+            let owner = self
+                .archive
+                .get(id)
+                .map(|record| record.owner)
+                .ok_or(Error::WishNotFound)?;
+            let mut reputation = self.reputations.get(owner).unwrap_or_default();
+            let before = loan.installments_paid(loan.repaid);
+            loan.repaid += amount;
+            for n in before + 1..=loan.installments_paid(loan.repaid) {
+                if now <= loan.due_date(n) {
+                    reputation.installments_on_time += 1;
+                } else {
+                    reputation.installments_late += 1;
+                }
+            }
+            self.reputations.insert(owner, &reputation);
+            self.distribute(id, amount, loan.principal);
+            if loan.repaid == loan.principal {
+                self.loans.remove(id);
+            } else {
+                self.loans.insert(id, &loan);
+            }
+            self.env().emit_event(LoanRepaid {
+                id,
+                payer: self.get_caller(),
+                amount,
+                outstanding: outstanding - amount,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_loan(&self, id: u32) -> Option<Loan> {
+            self.loans.get(id)
+        }
+
+        /// Ask the contributors to let the owner claim wish `id` before its
+        /// end date. Only possible once the minimum target has been raised.
+        /// Voting weights are the contributions at this point, cast by the
+        /// contributors or whoever they delegated to. A wish has one request
+        /// at a time, so the votes cast on it cannot be reset.
+        #[ink(message)]
+        pub fn request_early_claim(&mut self, id: u32) -> Result<()> {
+            let caller = self.get_caller();
+            let item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if item.owner != caller {
+                return Err(Error::NotOwner);
+            }
+            if self.early_claims.contains(id) {
+                return Err(Error::EarlyClaimPending);
+            }
+            // Sealed contributions only count once revealed after the end date
+            if item.sealed {
+                return Err(Error::SealedWish);
+            }
+            let now = self.now();
+            if now >= item.end_date {
+                return Err(Error::WishEnded);
+            }
+            if Self::counted(&item) < item.minimum() {
+                return Err(Error::TargetNotReached);
+            }
+
+            self.early_claims.insert(
+                id,
+                &EarlyClaim {
+                    requested_at: now,
+                    approvals: math::ZERO,
+                    objections: math::ZERO,
+                    total: item.contributed,
+                },
+            );
+            self.env()
+                .emit_event(EarlyClaimRequested { id, owner: caller });
+            Ok(())
+        }
+
+        /// Approve or object to the pending early claim on wish `id`, weighted
+        /// by the contributions the caller held or was delegated when the
+        /// claim was requested.
+        #[ink(message)]
+        pub fn vote_early_claim(&mut self, id: u32, approve: bool) -> Result<()> {
+            let caller = self.get_caller();
+            if !self.wish_exists(id) {
+                return Err(Error::WishNotFound);
+            }
+            let mut claim = self
+                .early_claims
+                .get(id)
+                .ok_or(Error::EarlyClaimNotRequested)?;
+            let weight = self.get_voting_weight(id, caller);
+            if weight.is_zero() {
+                return Err(Error::NotContributor);
+            }
+            if self.early_claim_votes.contains((id, caller)) {
+                return Err(Error::AlreadyVoted);
+            }
+
+            if approve {
+                claim.approvals += weight;
+            } else {
+                claim.objections += weight;
+            }
+            self.early_claims.insert(id, &claim);
+            self.early_claim_votes
+                .insert((id, caller), &Vote { approve, weight });
+            self.env().emit_event(EarlyClaimVoted {
+                id,
+                contributor: caller,
+                approve,
+                weight,
+            });
+            Ok(())
+        }
+
+        /// Take back the caller's vote on the early claim of wish `id`.
+        #[ink(message)]
+        pub fn revoke_early_claim_vote(&mut self, id: u32) -> Result<()> {
+            let caller = self.get_caller();
+            if !self.retract_early_claim_vote(id, caller) {
+                return Err(Error::NotVoted);
+            }
+            self.env()
+                .emit_event(EarlyClaimVoteRevoked { id, voter: caller });
+            Ok(())
+        }
+
+        /// Whether the votes cast so far let the owner claim wish `id` early.
+        #[ink(message)]
+        pub fn tally_early_claim(&self, id: u32) -> Result<bool> {
+            if !self.wish_exists(id) {
+                return Err(Error::WishNotFound);
+            }
+            if !self.early_claims.contains(id) {
+                return Err(Error::EarlyClaimNotRequested);
+            }
+            Ok(self.early_claim_approved(id))
+        }
+
+        #[ink(message)]
+        pub fn get_early_claim(&self, id: u32) -> Option<EarlyClaim> {
+            self.early_claims.get(id)
+        }
+
+        /// Weight `account` votes with on the early claim of wish `id`.
+        #[ink(message)]
+        pub fn get_voting_weight(&self, id: u32, account: H160) -> Amount {
+            self.early_claim_weights
+                .get((id, account))
+                .unwrap_or_default()
+        }
+
+        /// Let `to` cast the caller's voting weight on the wishes the caller
+        /// contributes to from now on, or take it back with `None`. Weight
+        /// already held on a wish follows on the caller's next contribution
+        /// to it, unless an early claim on it is pending.
+        #[ink(message)]
+        pub fn delegate(&mut self, to: Option<H160>) -> Result<()> {
+            let caller = self.get_caller();
+            let to = to.filter(|to| *to != caller);
+            match to {
+                Some(to) => {
+                    self.delegates.insert(caller, &to);
+                }
+                None => self.delegates.remove(caller),
+            }
+            self.env().emit_event(VotesDelegated {
+                delegator: caller,
+                delegate: to,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_delegate(&self, account: H160) -> Option<H160> {
+            self.delegates.get(account)
+        }
+
+        /// Fund a wish and raise its boost score by the transferred value,
+        /// moving it up the `BoostScore` listing.
+        #[ink(message, payable)]
+        pub fn boost_wish(&mut self, id: u32) -> Result<()> {
+            let caller = self.get_caller();
+            let value = self.transferred()?;
+            if value.is_zero() {
+                return Err(Error::InvalidContribution);
+            }
+            self.meter(ink::selector_id!("boost_wish"), value);
+
+            self.record_contribution(id, caller, value)?;
+            let mut item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            item.boost += value;
+            let boost = item.boost;
+            self.items.insert(id, &item);
+
+            self.index_upsert(ListingIndex::Boost, id, boost);
+            Ok(())
+        }
+
+        /// List active wishes in the order given by `key`.
+        #[ink(message)]
+        pub fn get_wishes_sorted(
+            &self,
+            key: SortKey,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<WishListItem> {
+            match key {
+                SortKey::Newest => self.page_of(
+                    self.index_ids(ListingIndex::Newest, math::ZERO).rev(),
+                    offset,
+                    limit,
+                ),
+                SortKey::EndingSoonest => self.page_of(
+                    self.index_ids(ListingIndex::Deadline, Amount::from(self.now())),
+                    offset,
+                    limit,
+                ),
+                SortKey::PercentFunded => self.page_of(
+                    self.index_ids(ListingIndex::Funded, math::ZERO).rev(),
+                    offset,
+                    limit,
+                ),
+                SortKey::BoostScore => self.page_of(
+                    self.index_ids(ListingIndex::Boost, math::ZERO).rev(),
+                    offset,
+                    limit,
+                ),
+            }
+        }
+
+        /// Active wishes whose deadline falls within the next `window_ms`,
+        /// soonest first.
+        #[ink(message)]
+        pub fn get_expiring_soon(
+            &self,
+            window_ms: u64,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<WishListItem> {
+            let now = self.now();
+            let cutoff = Amount::from(now.saturating_add(window_ms));
+            let ids = self
+                .index_entries(ListingIndex::Deadline, (Amount::from(now), 0))
+                .take_while(|entry| entry.0 <= cutoff)
+                .map(|entry| entry.1);
+            self.page_of(ids, offset, limit)
+        }
+
+        /// Daily totals raised by wish `id` (owner and contributors combined),
+        /// oldest day first. Days without funding are omitted.
+        #[ink(message)]
+        pub fn raise_history(&self, id: u32) -> Vec<RaiseBucket> {
+            self.raise_history.get(id).unwrap_or_default()
+        }
+
+        /// Individual fundings of wish `id` in the order they happened,
+        /// skipping `offset` records and returning at most `limit`.
+        #[ink(message)]
+        pub fn get_contributions(
+            &self,
+            id: u32,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<ContributionRecord> {
+            let count = self.contribution_counts.get(id).unwrap_or_default();
+            let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(count);
+            (offset..end)
+                .filter_map(|position| self.contribution_log.get((id, position)))
+                .collect()
+        }
+
+        /// Accounts with a contribution in open wish `id` and their amounts,
+        /// in order of first contribution, skipping `offset` and returning at
+        /// most `limit` (capped at `MAX_PAGE_SIZE`).
+        #[ink(message)]
+        pub fn get_contributors(&self, id: u32, offset: u32, limit: u32) -> Vec<(H160, Amount)> {
+            let Some(item) = self.items.get(id) else {
+                return Vec::new();
+            };
+            self.contributors_of(id, &item)
+                .into_iter()
+                .skip(offset as usize)
+                .take(limit.min(MAX_PAGE_SIZE) as usize)
+                .collect()
+        }
+
+        #[ink(message)]
+        pub fn get_contribution_count(&self, id: u32) -> u32 {
+            self.contribution_counts.get(id).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        pub fn get_contributor_activity(
+            &self,
+            id: u32,
+            account: H160,
+        ) -> Option<ContributorActivity> {
+            self.contributor_activity.get((id, account))
+        }
+
+        /// How far wish `id` is towards its minimum and its ideal target.
+        #[ink(message)]
+        pub fn get_progress(&self, id: u32) -> Result<Progress> {
+            let item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            Ok(Progress {
+                minimum_bps: math::to_bps(Self::counted(&item), item.minimum()),
+                target_bps: Self::funded_bps(&item),
+            })
+        }
+
+        /// Counts and approximate sizes of what the contract stores. Reads
+        /// every active wish, so it is meant for off-chain queries.
+        #[ink(message)]
+        pub fn storage_stats(&self) -> StorageStats {
+            use ink::scale::Encode;
+
+            let mut contributor_entries = 0u32;
+            let mut wish_bytes = 0usize;
+            for id in self.index_ids(ListingIndex::Newest, math::ZERO) {
+                if let Some(item) = self.items.get(id) {
+                    contributor_entries += item.contributor_count;
+                    wish_bytes += item.encoded_size();
+                }
+            }
+            let mut index_bytes = 0usize;
+            let mut lens = [0u32; 4];
+            for (index, len) in [
+                ListingIndex::Newest,
+                ListingIndex::Deadline,
+                ListingIndex::Funded,
+                ListingIndex::Boost,
+            ]
+            .into_iter()
+            .zip(lens.iter_mut())
+            {
+                let directory = self.index_directory.get(index).unwrap_or_default();
+                index_bytes += directory.encoded_size();
+                for page in directory.iter() {
+                    *len += page.len;
+                    index_bytes += self
+                        .index_pages
+                        .get(page.page)
+                        .unwrap_or_default()
+                        .encoded_size();
+                }
+            }
+            let [newest, deadlines, funded, boosts] = lens;
+
+            StorageStats {
+                wishes_created: self.next_item_id - 1,
+                active_wishes: newest,
+                archived_wishes: self.archived_count,
+                contributor_entries,
+                newest_index_len: newest,
+                deadline_index_len: deadlines,
+                funded_index_len: funded,
+                boost_index_len: boosts,
+                index_bytes: index_bytes as u32,
+                wish_bytes: wish_bytes as u32,
+            }
+        }
+
+        /// Add up everything the contract owes - active pots, deposits,
+        /// ledger credits, undistributed payouts, escrows, pools, commitments,
+        /// appeal bonds, burned fees, the treasury and claim fees - and
+        /// compare it with the balance.
+        /// Reads every active wish, so it is meant for off-chain queries.
+        #[ink(message)]
+        pub fn audit(&self) -> AuditReport {
+            let pots = self
+                .index_ids(ListingIndex::Newest, math::ZERO)
+                .filter_map(|id| self.items.get(id))
+                .fold(math::ZERO, |acc, item| {
+                    acc + Self::pot(&item) + item.match_remaining
+                });
+            let held = self.held.clone();
+            let liabilities = pots
+                + held.deposits
+                + held.credits
+                + held.payouts
+                + held.escrowed
+                + held.pooled
+                + held.committed
+                + held.bonds
+                + held.burned
+                + self.treasury
+                + self.accumulated_fees;
+            // Nothing owed can exceed `Amount`, so a balance beyond it is all
+            // surplus and is reported as the most `Amount` holds.
+            let balance = math::from_native(self.env().balance()).unwrap_or(Amount::MAX);
+
+            AuditReport {
+                pots,
+                held,
+                treasury: self.treasury,
+                accumulated_fees: self.accumulated_fees,
+                liabilities,
+                balance,
+                surplus: balance.saturating_sub(liabilities),
+                shortfall: liabilities.saturating_sub(balance),
+            }
+        }
+
+        /// Preview what settling wish `id` would pay out right now: the
+        /// owner's payout if `claim_wish` would succeed, otherwise each
+        /// contributor's share from `split_raised_wish`.
+        #[ink(message)]
+        pub fn simulate_settlement(&self, id: u32) -> Result<SettlementPreview> {
+            let item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            let now = self.now();
+            if item.sealed && now < item.end_date.saturating_add(REVEAL_WINDOW) {
+                return Err(Error::NotRevealPhase);
+            }
+            let contributed = item.contributed;
+
+            if self.can_claim_now(id, &item) {
+                let pot = Self::pot(&item);
+                let fee = self.claim_fee_of(pot);
+                let escrowed = Self::escrow_of(&item, pot - fee);
+                let beneficiary = item.charity.unwrap_or(item.owner);
+                return Ok(SettlementPreview {
+                    outcome: SettlementOutcome::Claimed,
+                    pot,
+                    payouts: self.payout_amounts(id, beneficiary, pot - fee - escrowed),
+                    escrowed,
+                    refunds: Vec::new(),
+                    fees: fee,
+                    dust: math::ZERO,
+                });
+            }
+            if item.funding_model == FundingModel::KeepWhatYouRaise {
+                return Err(Error::NotRefundable);
+            }
+
+            let (pot, topups) = Self::failed_sides(&item);
+            let refunds: Vec<(H160, Amount)> = self
+                .contributors_of(id, &item)
+                .into_iter()
+                .map(|(contributor, _)| {
+                    (
+                        contributor,
+                        self.split_share(id, contributor, pot, contributed),
+                    )
+                })
+                .collect();
+            let paid = refunds.iter().fold(math::ZERO, |acc, cur| acc + cur.1);
+            let payouts = if topups.is_zero() {
+                Vec::new()
+            } else {
+                vec![(item.owner, topups)]
+            };
+            Ok(SettlementPreview {
+                outcome: SettlementOutcome::Split,
+                pot: pot + topups,
+                payouts,
+                escrowed: math::ZERO,
+                refunds,
+                fees: math::ZERO,
+                dust: pot - paid,
+            })
+        }
+
+        /// Whether the owner of wish `id` can claim it now, and how the pot
+        /// would be divided between fees, escrow and the payout.
+        #[ink(message)]
+        pub fn get_claimable(&self, id: u32) -> Result<Claimable> {
+            let item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            let gross = Self::pot(&item);
+            let fee = self.claim_fee_of(gross);
+            let escrowed = Self::escrow_of(&item, gross - fee);
+            Ok(Claimable {
+                can_claim: self.can_claim_now(id, &item),
+                gross,
+                fee,
+                escrowed,
+                net: gross - fee - escrowed,
+            })
+        }
+
+        /// What `account` would be refunded if wish `id` failed and was split
+        /// today. Zero for the owner, who is not refunded, and for anyone who
+        /// has not contributed.
+        #[ink(message)]
+        pub fn get_refund_amount(&self, id: u32, account: H160) -> Amount {
+            let Some(item) = self.items.get(id) else {
+                return math::ZERO;
+            };
+            let (pot, _) = Self::failed_sides(&item);
+            self.split_share(id, account, pot, item.contributed)
+        }
+
+        /// The archived settlement of wish `id`, once it has been claimed or split.
+        #[ink(message)]
+        pub fn get_settlement(&self, id: u32) -> Option<SettlementRecord> {
+            self.archive.get(id)
+        }
+
+        /// Commit to a receipt for what claimed wish `id` was spent on. Can be
+        /// submitted once, by the owner.
+        #[ink(message)]
+        pub fn submit_receipt(&mut self, id: u32, hash: [u8; 32], uri: String) -> Result<()> {
+            let caller = self.get_caller();
+            let mut record = self.archive.get(id).ok_or(Error::NotClaimed)?;
+            if record.outcome != SettlementOutcome::Claimed {
+                return Err(Error::NotClaimed);
+            }
+            if record.owner != caller {
+                return Err(Error::NotOwner);
+            }
+            if record.receipt.is_some() {
+                return Err(Error::ReceiptExists);
+            }
+            if uri.len() > MAX_RECEIPT_URI_LEN {
+                return Err(Error::UriTooLong);
+            }
+
+            record.receipt = Some(Receipt {
+                hash,
+                uri: uri.clone(),
+                submitted_at: self.now(),
+            });
+            self.archive.insert(id, &record);
+            let mut reputation = self.reputations.get(caller).unwrap_or_default();
+            reputation.receipts += 1;
+            self.reputations.insert(caller, &reputation);
+            self.env().emit_event(ReceiptSubmitted {
+                id,
+                owner: caller,
+                hash,
+                uri,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_reputation(&self, owner: H160) -> Reputation {
+            self.reputations.get(owner).unwrap_or_default()
+        }
+
+        /// Reputation of `owner` in basis points: half for claimed wishes
+        /// with a receipt, half for those reported on in time, less
+        /// `LATE_INSTALLMENT_PENALTY_BPS` per loan installment repaid late.
+        #[ink(message)]
+        pub fn get_reputation_score(&self, owner: H160) -> u16 {
+            let reputation = self.reputations.get(owner).unwrap_or_default();
+            if reputation.claimed == 0 {
+                return 0;
+            }
+            let earned = u64::from(reputation.receipts) + u64::from(reputation.timely_reports);
+            let score = (earned * 5_000 / u64::from(reputation.claimed)).min(10_000) as u16;
+            score.saturating_sub(
+                LATE_INSTALLMENT_PENALTY_BPS.saturating_mul(
+                    u16::try_from(reputation.installments_late).unwrap_or(u16::MAX),
+                ),
+            )
+        }
+
+        /// File a report of `amount` of claimed wish `id`'s pot being spent on
+        /// `category`. Reports cannot add up to more than the pot.
+        #[ink(message)]
+        pub fn file_spend_report(
+            &mut self,
+            id: u32,
+            amount: Amount,
+            category: SpendCategory,
+            memo_hash: [u8; 32],
+        ) -> Result<()> {
+            let caller = self.get_caller();
+            let record = self.archive.get(id).ok_or(Error::NotClaimed)?;
+            if record.outcome != SettlementOutcome::Claimed {
+                return Err(Error::NotClaimed);
+            }
+            if record.owner != caller {
+                return Err(Error::NotOwner);
+            }
+            let mut reports = self.spend_reports.get(id).unwrap_or_default();
+            let reported = reports.iter().fold(math::ZERO, |acc, cur| acc + cur.amount);
+            if reports.len() >= MAX_SPEND_REPORTS || reported + amount > record.pot {
+                return Err(Error::InvalidSpendReport);
+            }
+
+            let now = self.now();
+            if reports.is_empty() && now <= record.settled_at.saturating_add(REPORT_WINDOW) {
+                let mut reputation = self.reputations.get(caller).unwrap_or_default();
+                reputation.timely_reports += 1;
+                self.reputations.insert(caller, &reputation);
+            }
+            reports.push(SpendReport {
+                amount,
+                category,
+                memo_hash,
+                filed_at: now,
+            });
+            self.spend_reports.insert(id, &reports);
+            self.env().emit_event(SpendReported {
+                id,
+                owner: caller,
+                amount,
+                category,
+                memo_hash,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_spend_reports(&self, id: u32) -> Vec<SpendReport> {
+            self.spend_reports.get(id).unwrap_or_default()
+        }
+
+        /// Settlements `account` was involved in, as owner or contributor,
+        /// oldest first. `offset` and `limit` page through every wish the
+        /// account took part in, so a page may hold fewer records than
+        /// `limit` while some of those wishes are still open.
+        #[ink(message)]
+        pub fn settlement_history(
+            &self,
+            account: H160,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<SettlementRecord> {
+            let len = self.account_history_len.get(account).unwrap_or(0);
+            let start = offset.min(len);
+            let end = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(len);
+            (start..end)
+                .filter_map(|n| self.account_history.get((account, n)))
+                .filter_map(|id| self.archive.get(id))
+                .collect()
+        }
+
+        /// Summaries of listed active wishes, oldest first, skipping `offset`
+        /// and returning at most `limit` (capped at `MAX_PAGE_SIZE`).
+        #[ink(message)]
+        pub fn get_wishes_paginated(&self, offset: u32, limit: u32) -> Vec<WishSummary> {
+            self.page_of(
+                self.index_ids(ListingIndex::Newest, math::ZERO),
+                offset,
+                limit,
+            )
+            .iter()
+            .map(Self::summarize)
+            .collect()
+        }
+
+        #[ink(message)]
+        pub fn get_wish_summary(&self, id: u32) -> Result<WishSummary> {
+            self.items
+                .get(id)
+                .map(|item| Self::summarize(&item))
+                .ok_or(Error::WishNotFound)
+        }
+
+        /// Appoint or remove a moderator.
+        #[ink(message)]
+        pub fn set_moderator(&mut self, account: H160, enabled: bool) -> Result<()> {
+            self.ensure_admin()?;
+            if enabled {
+                self.moderators.insert(account, &());
+            } else {
+                self.moderators.remove(account);
+            }
+            self.env().emit_event(ModeratorUpdated { account, enabled });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_moderator(&self, account: H160) -> bool {
+            self.moderators.contains(account)
+        }
+
+        /// Add or remove `account` from the charities that charity wishes may
+        /// pay out to. Delisting does not affect wishes already created.
+        #[ink(message)]
+        pub fn set_verified_charity(&mut self, account: H160, verified: bool) -> Result<()> {
+            self.ensure_admin()?;
+            if verified {
+                self.verified_charities.insert(account, &());
+            } else {
+                self.verified_charities.remove(account);
+            }
+            self.env().emit_event(CharityUpdated { account, verified });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_verified_charity(&self, account: H160) -> bool {
+            self.verified_charities.contains(account)
+        }
+
+        /// Mark wish `id` as vetted (or not) after off-chain checks.
+        #[ink(message)]
+        pub fn set_verified(&mut self, id: u32, verified: bool) -> Result<()> {
+            let caller = self.get_caller();
+            if !self.moderators.contains(caller) {
+                return Err(Error::NotModerator);
+            }
+            let mut item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            item.verified = verified;
+            self.items.insert(id, &item);
+            self.env().emit_event(WishVerified {
+                id,
+                moderator: caller,
+                verified,
+            });
+            Ok(())
+        }
+
+        /// Set how many unsettled wishes an owner may have at once. Owners
+        /// already above a lowered cap keep their wishes but cannot add more.
+        #[ink(message)]
+        pub fn set_max_active_wishes(&mut self, max: u32) -> Result<()> {
+            self.ensure_admin()?;
+            self.max_active_wishes = max;
+            Ok(())
+        }
+
+        /// Set the smallest target a new wish may have and the smallest
+        /// initial contribution it must come with.
+        #[ink(message)]
+        pub fn set_minimums(&mut self, min_target: Amount, min_deposit: Amount) -> Result<()> {
+            self.ensure_admin()?;
+            self.min_target = min_target;
+            self.min_deposit = min_deposit;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_minimums(&self) -> (Amount, Amount) {
+            (self.min_target, self.min_deposit)
+        }
+
+        /// Charge `bps` basis points, at most `MAX_ENTRY_FEE_BPS`, on every
+        /// contribution to someone else's wish, paid to the treasury or, with
+        /// `burn`, burned. Zero turns the fee off.
+        #[ink(message)]
+        pub fn set_entry_fee(&mut self, bps: u16, burn: bool) -> Result<()> {
+            self.ensure_admin()?;
+            if bps > MAX_ENTRY_FEE_BPS {
+                return Err(Error::InvalidBasisPoints);
+            }
+            self.entry_fee = EntryFee { bps, burn };
+            self.env().emit_event(EntryFeeUpdated { bps, burn });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_entry_fee(&self) -> EntryFee {
+            self.entry_fee
+        }
+
+        /// Keep `bps` basis points, at most `MAX_CLAIM_FEE_BPS`, of every pot
+        /// claimed from now on. Zero turns the fee off.
+        #[ink(message)]
+        pub fn set_claim_fee(&mut self, bps: u16) -> Result<()> {
+            self.ensure_admin()?;
+            if bps > MAX_CLAIM_FEE_BPS {
+                return Err(Error::InvalidBasisPoints);
+            }
+            self.claim_fee_bps = bps;
+            self.env().emit_event(ClaimFeeUpdated { bps });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_claim_fee(&self) -> u16 {
+            self.claim_fee_bps
+        }
+
+        #[ink(message)]
+        pub fn get_accumulated_fees(&self) -> Amount {
+            self.accumulated_fees
+        }
+
+        /// Send every claim fee taken so far to `to`.
+        #[ink(message)]
+        pub fn withdraw_fees(&mut self, to: H160) -> Result<()> {
+            self.ensure_admin()?;
+            let amount = core::mem::replace(&mut self.accumulated_fees, math::ZERO);
+            if amount.is_zero() {
+                return Err(Error::NothingToWithdraw);
+            }
+            self.env()
+                .transfer(to, math::to_native(amount))
+                .map_err(|_| Error::TransferFailed)?;
+            self.env().emit_event(FeesWithdrawn { to, amount });
+            Ok(())
+        }
+
+        /// Exempt `account` from the entry fee, or charge it again.
+        #[ink(message)]
+        pub fn set_fee_exempt(&mut self, account: H160, exempt: bool) -> Result<()> {
+            self.ensure_admin()?;
+            if exempt {
+                self.fee_exempt.insert(account, &());
+            } else {
+                self.fee_exempt.remove(account);
+            }
+            self.env().emit_event(FeeExemptionSet { account, exempt });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_fee_exempt(&self, account: H160) -> bool {
+            self.fee_exempt.contains(account)
+        }
+
+        #[ink(message)]
+        pub fn get_entry_fee_totals(&self) -> EntryFeeTotals {
+            self.entry_fee_totals
+        }
+
+        #[ink(message)]
+        pub fn get_active_count(&self, owner: H160) -> u32 {
+            self.active_counts.get(owner).unwrap_or(0)
+        }
+
+        /// Ids of every wish `account` owns, including settled ones, in
+        /// creation order.
+        #[ink(message)]
+        pub fn get_user_wishes(&self, account: H160) -> Vec<u32> {
+            self.items_by_owner.get(account).unwrap_or_default()
+        }
+
+        /// Register the `AttestationVerifier` contract consulted for `purpose`,
+        /// or `None` to stop gating that flow.
+        #[ink(message)]
+        pub fn set_verifier(
+            &mut self,
+            purpose: AttestationPurpose,
+            verifier: Option<H160>,
+        ) -> Result<()> {
+            self.ensure_admin()?;
+            match verifier {
+                Some(verifier) => self.verifiers.insert(purpose, &verifier),
+                None => {
+                    self.verifiers.remove(purpose);
+                    None
+                }
+            };
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_verifier(&self, purpose: AttestationPurpose) -> Option<H160> {
+            self.verifiers.get(purpose)
+        }
+
+        /// Register a `WishObserver` contract to be notified of contributions
+        /// to and the settlement of wish `id`, or `None` to remove it.
+        #[ink(message)]
+        pub fn set_observer(&mut self, id: u32, observer: Option<H160>) -> Result<()> {
+            let item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if item.owner != self.get_caller() {
+                return Err(Error::NotOwner);
+            }
+            match observer {
+                Some(observer) => self.observers.insert(id, &observer),
+                None => {
+                    self.observers.remove(id);
+                    None
+                }
+            };
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_observer(&self, id: u32) -> Option<H160> {
+            self.observers.get(id)
+        }
+
+        /// Authorize `manager` as a hot key for wish `id`, or `None` to revoke
+        /// it. A manager can post updates and extend the deadline within
+        /// `MAX_DEADLINE_EXTENSION`; everything else stays with the owner.
+        #[ink(message)]
+        pub fn set_manager(&mut self, id: u32, manager: Option<H160>) -> Result<()> {
+            let item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if item.owner != self.get_caller() {
+                return Err(Error::NotOwner);
+            }
+            match manager {
+                Some(manager) => self.managers.insert(id, &manager),
+                None => {
+                    self.managers.remove(id);
+                    None
+                }
+            };
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_manager(&self, id: u32) -> Option<H160> {
+            self.managers.get(id)
+        }
+
+        /// Post an update on active wish `id`, committing to its content by
+        /// `hash`. Only the owner or manager may post.
+        #[ink(message)]
+        pub fn post_update(&mut self, id: u32, hash: [u8; 32], uri: String) -> Result<()> {
+            let caller = self.get_caller();
+            let item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if !self.can_manage(id, &item, caller) {
+                return Err(Error::NotManager);
+            }
+            if uri.len() > MAX_RECEIPT_URI_LEN {
+                return Err(Error::UriTooLong);
+            }
+            self.env().emit_event(UpdatePosted {
+                id,
+                author: caller,
+                hash,
+                uri,
+            });
+            Ok(())
+        }
+
+        /// Move the end date of running wish `id` back by `by`. Only the
+        /// owner or manager may extend, and by at most
+        /// `MAX_DEADLINE_EXTENSION` over the wish's life.
+        #[ink(message)]
+        pub fn extend_deadline(&mut self, id: u32, by: u64) -> Result<()> {
+            let caller = self.get_caller();
+            let mut item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if !self.can_manage(id, &item, caller) {
+                return Err(Error::NotManager);
+            }
+            if self.now() >= item.end_date {
+                return Err(Error::WishEnded);
+            }
+            let extended = item.extended.saturating_add(by);
+            if extended > MAX_DEADLINE_EXTENSION {
+                return Err(Error::ExtensionLimitExceeded);
+            }
+            item.extended = extended;
+            item.end_date = item.end_date.saturating_add(by);
+            self.index_upsert(ListingIndex::Deadline, id, Amount::from(item.end_date));
+            let (owner, end_date) = (item.owner, item.end_date);
+            self.items.insert(id, &item);
+            self.env().emit_event(DeadlineExtended {
+                id,
+                owner,
+                end_date,
+            });
+            Ok(())
+        }
+
+        /// Approve `extension` to handle wishes of `wish_type`, or `None` to
+        /// withdraw the approval. Existing wishes of a withdrawn type fall
+        /// back to the default rules.
+        #[ink(message)]
+        pub fn register_extension(
+            &mut self,
+            wish_type: u32,
+            extension: Option<H160>,
+        ) -> Result<()> {
+            self.ensure_admin()?;
+            match extension {
+                Some(extension) => self.extensions.insert(wish_type, &extension),
+                None => {
+                    self.extensions.remove(wish_type);
+                    None
+                }
+            };
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_extension(&self, wish_type: u32) -> Option<H160> {
+            self.extensions.get(wish_type)
+        }
+
+        /// Split the payout of wish `id` between `shares` when it is claimed,
+        /// e.g. 70% to a recipient and 30% to a charity. Shares must add up to
+        /// 10 000 basis points; an empty list pays everything to the owner.
+        #[ink(message)]
+        pub fn set_payout_split(&mut self, id: u32, shares: Vec<PayoutShare>) -> Result<()> {
+            let item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if item.owner != self.get_caller() {
+                return Err(Error::NotOwner);
+            }
+            if item.charity.is_some() {
+                return Err(Error::CharityPayoutFixed);
+            }
+            if shares.is_empty() {
+                self.payout_splits.remove(id);
+                return Ok(());
+            }
+            let total = shares
+                .iter()
+                .fold(0u32, |acc, cur| acc + u32::from(cur.bps));
+            if shares.len() > MAX_PAYOUT_RECIPIENTS || total != 10_000 {
+                return Err(Error::InvalidPayoutSplit);
+            }
+            self.payout_splits.insert(id, &shares);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_payout_split(&self, id: u32) -> Vec<PayoutShare> {
+            self.payout_splits.get(id).unwrap_or_default()
+        }
+
+        /// Have wish `id` buy `purchase` from its marketplace when claimed,
+        /// delivering the asset to the beneficiary and refunding what is left
+        /// of the pot pro rata. `None` goes back to paying out the pot.
+        #[ink(message)]
+        pub fn set_purchase(&mut self, id: u32, purchase: Option<Purchase>) -> Result<()> {
+            let item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if item.owner != self.get_caller() {
+                return Err(Error::NotOwner);
+            }
+            match purchase {
+                None => self.purchases.remove(id),
+                Some(purchase) if purchase.max_price.is_zero() => {
+                    return Err(Error::InvalidPurchase)
+                }
+                Some(purchase) => {
+                    self.purchases.insert(id, &purchase);
+                }
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_purchase(&self, id: u32) -> Option<Purchase> {
+            self.purchases.get(id)
+        }
+
+        /// Set how the owner's deposit is handled when a wish is force-cancelled.
+        #[ink(message)]
+        pub fn set_deposit_policy(&mut self, policy: DepositPolicy) -> Result<()> {
+            self.ensure_admin()?;
+            self.deposit_policy = policy;
+            Ok(())
+        }
+
+        /// Take wish `id` down. Every contributor is credited their
+        /// contribution on the pull ledger and the owner's side of the pot is
+        /// refunded or forfeited to the treasury according to the deposit
+        /// policy. Should the owner have withdrawn more than their own side,
+        /// contributors share the shortfall pro rata.
+        #[ink(message)]
+        pub fn force_cancel(&mut self, id: u32, reason: String) -> Result<()> {
+            let caller = self.get_caller();
+            if !self.moderators.contains(caller) {
+                return Err(Error::NotModerator);
+            }
+            let item = self.items.get(id).ok_or(Error::WishNotFound)?;
+
+            let owner_share = self.return_contributions(id, &item);
+            match self.deposit_policy {
+                DepositPolicy::Refund => self.credit(item.owner, owner_share),
+                DepositPolicy::Forfeit => {
+                    self.treasury += owner_share;
+                    self.moderation_forfeits.insert(id, &owner_share);
+                }
+            }
+
+            let owner = item.owner;
+            self.retire_wish(id, item, SettlementOutcome::Moderated);
+            self.env().emit_event(WishModerated {
+                id,
+                moderator: caller,
+                owner,
+                reason,
+            });
+            Ok(())
+        }
+
+        /// Appeal the force-cancel of wish `id` within `APPEAL_WINDOW`. The
+        /// transferred value is the bond, which must be at least `appeal_bond`.
+        #[ink(message, payable)]
+        pub fn appeal_moderation(&mut self, id: u32) -> Result<()> {
+            let caller = self.get_caller();
+            let bond = self.transferred()?;
+            self.meter(ink::selector_id!("appeal_moderation"), bond);
+            let record = self.archive.get(id).ok_or(Error::NotModerated)?;
+            if record.outcome != SettlementOutcome::Moderated {
+                return Err(Error::NotModerated);
+            }
+            if record.owner != caller {
+                return Err(Error::NotOwner);
+            }
+            if self.appeals.contains(id) {
+                return Err(Error::AppealExists);
+            }
+            let now = self.now();
+            if now > record.settled_at.saturating_add(APPEAL_WINDOW) {
+                return Err(Error::AppealWindowClosed);
+            }
+            if bond.is_zero() || bond < self.appeal_bond {
+                return Err(Error::InsufficientBond);
+            }
+
+            self.appeals.insert(
+                id,
+                &Appeal {
+                    owner: caller,
+                    bond,
+                    lodged_at: now,
+                    status: AppealStatus::Pending,
+                },
+            );
+            self.held.bonds += bond;
+            self.env().emit_event(AppealLodged {
+                id,
+                owner: caller,
+                bond,
+            });
+            Ok(())
+        }
+
+        /// Decide the pending appeal on wish `id`. Upholding sends the bond to
+        /// the treasury. Reversing returns the bond and any forfeited deposit
+        /// to the owner and marks the tombstone as reversed; contributor
+        /// refunds already queued are left untouched.
+        #[ink(message)]
+        pub fn resolve_appeal(&mut self, id: u32, uphold: bool) -> Result<()> {
+            let caller = self.get_caller();
+            if caller != self.admin && Some(caller) != self.arbiter {
+                return Err(Error::NotArbiter);
+            }
+            let mut appeal = self.appeals.get(id).ok_or(Error::NoPendingAppeal)?;
+            if appeal.status != AppealStatus::Pending {
+                return Err(Error::NoPendingAppeal);
+            }
+
+            self.held.bonds -= appeal.bond;
+            if uphold {
+                appeal.status = AppealStatus::Upheld;
+                self.treasury += appeal.bond;
+            } else {
+                let forfeited = self.moderation_forfeits.take(id).unwrap_or_default();
+                if forfeited > self.treasury {
+                    return Err(Error::InsufficientTreasury);
+                }
+                self.treasury -= forfeited;
+                self.credit(appeal.owner, appeal.bond + forfeited);
+
+                appeal.status = AppealStatus::Reversed;
+                if let Some(mut record) = self.archive.get(id) {
+                    record.outcome = SettlementOutcome::ModerationReversed;
+                    self.archive.insert(id, &record);
+                }
+            }
+            self.appeals.insert(id, &appeal);
+            self.env().emit_event(AppealResolved {
+                id,
+                arbiter: caller,
+                owner: appeal.owner,
+                upheld: uphold,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_appeal(&self, id: u32) -> Option<Appeal> {
+            self.appeals.get(id)
+        }
+
+        #[ink(message)]
+        pub fn set_appeal_bond(&mut self, bond: Amount) -> Result<()> {
+            self.ensure_admin()?;
+            self.appeal_bond = bond;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_arbiter(&mut self, arbiter: Option<H160>) -> Result<()> {
+            self.ensure_admin()?;
+            self.arbiter = arbiter;
+            Ok(())
+        }
+
+        /// Withdraw everything owed to the caller on the pull ledger.
+        #[ink(message)]
+        pub fn withdraw_credits(&mut self) -> Result<()> {
+            let caller = self.get_caller();
+            let amount = self.credits.take(caller).ok_or(Error::NothingToWithdraw)?;
+            self.held.credits -= amount;
+            self.meter(ink::selector_id!("withdraw_credits"), amount);
+            self.env()
+                .transfer(self.payout_address(caller), math::to_native(amount))
+                .map_err(|_| Error::TransferFailed)
+        }
+
+        #[ink(message)]
+        pub fn get_credits(&self, account: H160) -> Amount {
+            self.credits.get(account).unwrap_or_default()
+        }
+
+        /// Have the caller's refunds and withdrawals sent to `payout`, e.g. a
+        /// cold wallet, instead of to the caller. `None` sends them to the
+        /// caller again.
+        #[ink(message)]
+        pub fn set_payout_address(&mut self, payout: Option<H160>) -> Result<()> {
+            let caller = self.get_caller();
+            let payout = payout.filter(|payout| *payout != caller);
+            match payout {
+                Some(payout) => {
+                    self.payout_addresses.insert(caller, &payout);
+                }
+                None => self.payout_addresses.remove(caller),
+            }
+            self.env().emit_event(PayoutAddressSet {
+                account: caller,
+                payout,
+            });
+            Ok(())
+        }
+
+        /// Where refunds and withdrawals of `account` are sent.
+        #[ink(message)]
+        pub fn payout_address(&self, account: H160) -> H160 {
+            self.payout_addresses.get(account).unwrap_or(account)
+        }
+
+        /// Let wishes be funded with PSP22 `token`, or stop accepting it.
+        /// Tokens already held are still paid out.
+        #[ink(message)]
+        pub fn set_accepted_token(&mut self, token: H160, accepted: bool) -> Result<()> {
+            self.ensure_admin()?;
+            if accepted {
+                self.accepted_tokens.insert(token, &());
+            } else {
+                self.accepted_tokens.remove(token);
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_accepted_token(&self, token: H160) -> bool {
+            self.accepted_tokens.contains(token)
+        }
+
+        /// PSP22 contributions held for wish `id`, per token.
+        #[ink(message)]
+        pub fn get_token_pots(&self, id: u32) -> Vec<TokenPot> {
+            self.token_pots.get(id).unwrap_or_default()
+        }
+
+        /// Fund wish `id` with `amount` of PSP22 `token`, pulled from the
+        /// caller's allowance to the contract. Paid out or refunded in the
+        /// same token when the wish settles.
+        #[ink(message)]
+        pub fn fund_wish_psp22(&mut self, id: u32, token: H160, amount: Amount) -> Result<()> {
+            let caller = self.get_caller();
+            self.record_token_contribution(id, token, caller, amount)?;
+            let mut psp22: ink::contract_ref_from_path!(PSP22) = token.into();
+            match psp22
+                .call_mut()
+                .transfer_from(caller, self.env().address(), amount, Vec::new())
+                .try_invoke()
+            {
+                Ok(Ok(Ok(()))) => Ok(()),
+                _ => Err(Error::TransferFailed),
+            }
+        }
+
+        /// Transfer the caller's credited `token` to them: payouts of claimed
+        /// wishes and refunds of contributions to the others.
+        #[ink(message)]
+        pub fn withdraw_token_credits(&mut self, token: H160) -> Result<()> {
+            let caller = self.get_caller();
+            let amount = self
+                .token_credits
+                .take((caller, token))
+                .ok_or(Error::NothingToWithdraw)?;
+            let mut psp22: ink::contract_ref_from_path!(PSP22) = token.into();
+            match psp22
+                .call_mut()
+                .transfer(self.payout_address(caller), amount, Vec::new())
+                .try_invoke()
+            {
+                Ok(Ok(Ok(()))) => Ok(()),
+                _ => Err(Error::TransferFailed),
+            }
+        }
+
+        #[ink(message)]
+        pub fn get_token_credits(&self, account: H160, token: H160) -> Amount {
+            self.token_credits.get((account, token)).unwrap_or_default()
+        }
+
+        /// Count the valuation of NFT `deposit` towards its wish.
+        #[ink(message)]
+        pub fn accept_nft(&mut self, deposit: u32) -> Result<()> {
+            let (mut nft, mut item) = self.review_nft(deposit)?;
+            nft.accepted = true;
+            item.nft_value += nft.valuation;
+            let id = nft.id;
+            self.nft_deposits.insert(deposit, &nft);
+            self.announce_milestones(id, &mut item);
+            self.items.insert(id, &item);
+            self.env().emit_event(NftReviewed {
+                id,
+                contributor: nft.contributor,
+                deposit,
+                accepted: true,
+            });
+            self.auto_claim_at_target(id)
+        }
+
+        /// Turn NFT `deposit` down, so its contributor can withdraw it.
+        #[ink(message)]
+        pub fn reject_nft(&mut self, deposit: u32) -> Result<()> {
+            let (mut nft, _) = self.review_nft(deposit)?;
+            nft.owed_to = Some(nft.contributor);
+            let id = nft.id;
+            self.nft_deposits.insert(deposit, &nft);
+            let mut held = self.wish_nfts.get(id).unwrap_or_default();
+            held.retain(|entry| *entry != deposit);
+            self.wish_nfts.insert(id, &held);
+            self.env().emit_event(NftReviewed {
+                id,
+                contributor: nft.contributor,
+                deposit,
+                accepted: false,
+            });
+            Ok(())
+        }
+
+        /// Transfer NFT `deposit` to the caller: back to its contributor if
+        /// it was rejected or the wish failed, to the beneficiary once the
+        /// wish is claimed. Until the owner reviews it, the contributor may
+        /// also take it back.
+        #[ink(message)]
+        pub fn withdraw_nft(&mut self, deposit: u32) -> Result<()> {
+            let caller = self.get_caller();
+            let nft = self.nft_deposits.get(deposit).ok_or(Error::NftNotFound)?;
+            match nft.owed_to {
+                Some(account) if account == caller => {}
+                None if !nft.accepted && nft.contributor == caller => {
+                    let mut held = self.wish_nfts.get(nft.id).unwrap_or_default();
+                    held.retain(|entry| *entry != deposit);
+                    self.wish_nfts.insert(nft.id, &held);
+                }
+                _ => return Err(Error::NftHeld),
+            }
+            self.nft_deposits.remove(deposit);
+            let mut psp34: ink::contract_ref_from_path!(PSP34) = nft.collection.into();
+            match psp34
+                .call_mut()
+                .transfer(caller, nft.token_id, Vec::new())
+                .try_invoke()
+            {
+                Ok(Ok(Ok(()))) => Ok(()),
+                _ => Err(Error::TransferFailed),
+            }
+        }
+
+        #[ink(message)]
+        pub fn get_nft_deposit(&self, deposit: u32) -> Option<NftDeposit> {
+            self.nft_deposits.get(deposit)
+        }
+
+        /// Ids of the NFT deposits held for wish `id`.
+        #[ink(message)]
+        pub fn get_wish_nfts(&self, id: u32) -> Vec<u32> {
+            self.wish_nfts.get(id).unwrap_or_default()
+        }
+
+        /// Send `amount` from the treasury to `to`.
+        #[ink(message)]
+        pub fn withdraw_treasury(&mut self, to: H160, amount: Amount) -> Result<()> {
+            self.ensure_admin()?;
+            if amount > self.treasury {
+                return Err(Error::InsufficientTreasury);
+            }
+            self.treasury -= amount;
+            self.env()
+                .transfer(to, math::to_native(amount))
+                .map_err(|_| Error::TransferFailed)
+        }
+
+        /// Move the balance `audit` cannot attribute to anything, such as
+        /// rounding dust or value sent to the contract directly, into the
+        /// treasury. Returns the amount swept.
+        #[ink(message)]
+        pub fn sweep_dust(&mut self) -> Result<Amount> {
+            self.ensure_admin()?;
+            let amount = self.audit().surplus;
+            if !amount.is_zero() {
+                self.treasury += amount;
+                self.env().emit_event(DustSwept { amount });
+            }
+            Ok(amount)
+        }
+
+        #[ink(message)]
+        pub fn get_treasury(&self) -> Amount {
+            self.treasury
+        }
+
+        #[ink(message)]
+        pub fn get_admin(&self) -> H160 {
+            self.admin
+        }
+
+        /// Propose `new_admin` to take over the admin role. Nothing changes
+        /// until they call `accept_admin`, so a mistyped address cannot lock
+        /// the contract; proposing again replaces the pending proposal.
+        #[ink(message)]
+        pub fn propose_admin(&mut self, new_admin: H160) -> Result<()> {
+            self.ensure_admin()?;
+            self.pending_admin = Some(new_admin);
+            self.env().emit_event(AdminProposed {
+                admin: self.admin,
+                proposed: new_admin,
+            });
+            Ok(())
+        }
+
+        /// Take over the admin role, as the account last proposed.
+        #[ink(message)]
+        pub fn accept_admin(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            if self.pending_admin != Some(caller) {
+                return Err(Error::NotPendingAdmin);
+            }
+            self.pending_admin = None;
+            let previous = core::mem::replace(&mut self.admin, caller);
+            self.env().emit_event(AdminChanged {
+                previous,
+                admin: caller,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_pending_admin(&self) -> Option<H160> {
+            self.pending_admin
+        }
+
+        /// Stop new wishes and contributions, for when something is wrong
+        /// with the contract. Claims, refunds and withdrawals keep working so
+        /// no one's money is locked in.
+        #[ink(message)]
+        pub fn pause(&mut self) -> Result<()> {
+            self.set_paused(true)
+        }
+
+        /// Take new wishes and contributions again.
+        #[ink(message)]
+        pub fn unpause(&mut self) -> Result<()> {
+            self.set_paused(false)
+        }
+
+        #[ink(message)]
+        pub fn is_paused(&self) -> bool {
+            self.paused
+        }
+
+        /// Move the clock of a contract created with `Clock::Fixed` to
+        /// `time`. Only the admin may move it.
+        #[ink(message)]
+        pub fn set_clock(&mut self, time: u64) -> Result<()> {
+            self.ensure_admin()?;
+            if self.clock == Clock::Block {
+                return Err(Error::ClockNotFixed);
+            }
+            self.clock = Clock::Fixed(time);
+            Ok(())
+        }
+
+        /// Calls and value moved so far for each metered message, keyed by
+        /// its selector: the messages that create, fund, claim, split and
+        /// pay out wishes.
+        #[ink(message)]
+        pub fn metrics(&self) -> Vec<(u32, CallMetrics)> {
+            self.metered_selectors
+                .get_or_default()
+                .into_iter()
+                .map(|selector| {
+                    let metrics = self.call_metrics.get(selector).unwrap_or_default();
+                    (selector, metrics)
+                })
+                .collect()
+        }
+
+        /// Whether wish `id` is active, checked without decoding the wish.
+        #[ink(message)]
+        pub fn wish_exists(&self, id: u32) -> bool {
+            self.items.contains(id)
+        }
+
+        /// Ids of the active wishes from `from` up to, but not including,
+        /// `to`, skipping settled ones. Returns at most `MAX_PAGE_SIZE` ids;
+        /// continue from the last one plus one.
+        #[ink(message)]
+        pub fn get_ids(&self, from: u32, to: u32) -> Vec<u32> {
+            self.index_entries(ListingIndex::Newest, (math::ZERO, from))
+                .map(|entry| entry.1)
+                .take_while(|id| *id < to)
+                .take(MAX_PAGE_SIZE as usize)
+                .collect()
+        }
+
+        pub fn get_caller(&self) -> H160 {
+            self.env().caller()
+        }
+
+        pub fn get_contributors_raised(&self, id: u32) -> Option<Amount> {
+            self.items.get(id).map(|item| item.contributed)
+        }
+
+        /// Credit `value` towards wish `id` on behalf of `contributor`. Funds
+        /// from the owner raise the wish itself, anyone else is tracked as a
+        /// contributor. Every check runs before the first write, so an `Err`
+        /// leaves no state or events behind and callers may carry on past it.
+        fn record_contribution(&mut self, id: u32, contributor: H160, value: Amount) -> Result<()> {
+            let wishlist = self.items.get(id);
+            match wishlist {
+                None => Err(Error::WishNotFound),
+                Some(mut item) => {
+                    self.ensure_fundable(id, &item, contributor)?;
+                    if let Some(extension) = item.wish_type.and_then(|t| self.extension(t)) {
+                        match extension
+                            .call()
+                            .validate_contribution(id, contributor, value)
+                            .try_invoke()
+                        {
+                            Ok(Ok(true)) => {}
+                            _ => return Err(Error::ExtensionRejected),
+                        }
+                    }
+                    let fee = if contributor == item.owner {
+                        math::ZERO
+                    } else {
+                        self.entry_fee_of(contributor, value)
+                    };
+                    let value = value - fee;
+                    // The round is the last check; nothing is written before
+                    // it, so a fee is only charged on contributions that go
+                    // through.
+                    let round = if contributor == item.owner {
+                        None
+                    } else {
+                        self.round_entry(id, value)?
+                    };
+                    self.charge_entry_fee(id, contributor, fee);
+                    if let Some((round, progress)) = round {
+                        self.enter_round(id, item.owner, round, progress);
+                    }
+                    if contributor == item.owner {
+                        // Owner top-ups are kept apart from the initial raise
+                        item.owner_topups += value;
+                        self.announce_milestones(id, &mut item);
+                        self.items.insert(id, &item);
+                    } else {
+                        self.add_stake(id, &mut item, contributor, value);
+
+                        let matched =
+                            math::bps(value, item.match_ratio_bps).min(item.match_remaining);
+                        if !matched.is_zero() {
+                            item.match_remaining -= matched;
+                            item.raised += matched;
+                            self.env().emit_event(ContributionMatched {
+                                id,
+                                contributor,
+                                amount: matched,
+                            });
+                            self.announce_milestones(id, &mut item);
+                        }
+
+                        self.items.insert(id, &item);
+                    }
+                    self.track_inflow(id, contributor, value);
+                    if let Some(observer) = self.observers.get(id) {
+                        let mut observer: ink::contract_ref_from_path!(WishObserver) =
+                            observer.into();
+                        let _ = observer
+                            .call_mut()
+                            .on_contribution(id, contributor, value)
+                            .ref_time_limit(OBSERVER_REF_TIME_LIMIT)
+                            .try_invoke();
+                    }
+                    Ok(())
+                }
+            }
+        }
+
+        /// The entry fee on `value` contributed by `contributor`, zero if they
+        /// are exempt.
+        fn entry_fee_of(&self, contributor: H160, value: Amount) -> Amount {
+            let bps = self.entry_fee.bps;
+            if bps == 0 || self.fee_exempt.contains(contributor) {
+                return math::ZERO;
+            }
+            math::bps(value, bps)
+        }
+
+        /// Charge `fee` on a contribution by `contributor` to wish `id`.
+        /// Burned fees stay in the contract for good; nothing pays them out.
+        fn charge_entry_fee(&mut self, id: u32, contributor: H160, fee: Amount) {
+            if fee.is_zero() {
+                return;
+            }
+            let burn = self.entry_fee.burn;
+            if burn {
+                self.held.burned += fee;
+                self.entry_fee_totals.burned += fee;
+            } else {
+                self.treasury += fee;
+                self.entry_fee_totals.to_treasury += fee;
+            }
+            self.env().emit_event(EntryFeeCharged {
+                id,
+                contributor,
+                fee,
+                burned: burn,
+            });
+        }
+
+        /// Validate and create a wish for `owner`, funded by the transferred
+        /// value.
+        fn add_item(
+            &mut self,
+            owner: H160,
+            description: String,
+            end_date: u64,
+            target: Amount,
+            options: WishOptions,
+        ) -> Result<u32> {
+            if self.paused {
+                return Err(Error::ContractPaused);
+            }
+            let value = self.transferred()?;
+
+            // Ensure target is not 0
+            if target.is_zero() {
+                return Err(Error::InvalidTarget);
+            }
+            if target < self.min_target {
+                return Err(Error::TargetTooSmall);
+            }
+            if let Some(minimum) = options.minimum_target {
+                if minimum.is_zero() || minimum > target {
+                    return Err(Error::InvalidTarget);
+                }
+            }
+
+            if options.exit_penalty_bps > 10_000
+                || options.escrow_bps > 10_000
+                || options.soft_threshold_bps > 10_000
+            {
+                return Err(Error::InvalidBasisPoints);
+            }
+            if options.soft_deadline.is_some_and(|soft| soft >= end_date) {
+                return Err(Error::InvalidSoftDeadline);
+            }
+            if options.start_date.is_some_and(|start| start >= end_date) {
+                return Err(Error::InvalidStartDate);
+            }
+            if options
+                .loan
+                .is_some_and(|terms| terms.installments == 0 || terms.interval == 0)
+            {
+                return Err(Error::InvalidLoanTerms);
+            }
+            self.ensure_attested(AttestationPurpose::Kyc, owner)?;
+
+            // The trasferred_value > 10% of target, on top of any match budget
+            let ten_percent = math::bps(target, 1_000).max(self.min_deposit);
+            if value < options.match_budget || value - options.match_budget < ten_percent {
+                return Err(Error::InvalidContribution);
+            }
+
+            let now = self.now();
+            let mut fingerprint = owner.as_bytes().to_vec();
+            fingerprint.extend_from_slice(normalize(&description).as_bytes());
+            let fingerprint = self.env().hash_bytes::<Keccak256>(&fingerprint);
+            if let Some(created_at) = self.recent_descriptions.get(fingerprint) {
+                if now < created_at.saturating_add(DUPLICATE_WINDOW) {
+                    return Err(Error::DuplicateWish);
+                }
+            }
+
+            let raised = value - options.match_budget;
+            let id = self.create_wish(owner, description, end_date, target, raised, options)?;
+            self.recent_descriptions.insert(fingerprint, &now);
+            Ok(id)
+        }
+
+        /// Store a new wish and emit `WishlistAdded`. Returns the id of the wish.
+        fn create_wish(
+            &mut self,
+            owner: H160,
+            description: String,
+            end_date: u64,
+            target: Amount,
+            raised: Amount,
+            options: WishOptions,
+        ) -> Result<u32> {
+            let active = self.active_counts.get(owner).unwrap_or(0);
+            if active >= self.max_active_wishes {
+                return Err(Error::TooManyActiveWishes);
+            }
+            if let Some(charity) = options.charity {
+                if !self.verified_charities.contains(charity) {
+                    return Err(Error::UnverifiedCharity);
+                }
+                self.ensure_attested(AttestationPurpose::CharityStatus, charity)?;
+            }
+            if let Some(wish_type) = options.wish_type {
+                let extension = self.extension(wish_type).ok_or(Error::UnknownWishType)?;
+                match extension
+                    .call()
+                    .validate_wish(owner, target, end_date)
+                    .try_invoke()
+                {
+                    Ok(Ok(true)) => {}
+                    _ => return Err(Error::ExtensionRejected),
+                }
+            }
+            let id = self.next_item_id;
+            let description_hash = self.env().hash_bytes::<Keccak256>(description.as_bytes());
+
+            let mut wishlist = WishListItem {
+                id,
+                description,
+                owner,
+                target,
+                end_date,
+                raised,
+                contributor_count: 0,
+                contributor_slots: 0,
+                contributed: math::ZERO,
+                recurrence: options.recurrence,
+                boost: math::ZERO,
+                exit_penalty_bps: options.exit_penalty_bps,
+                forfeited: math::ZERO,
+                allow_partial_withdrawal: options.allow_partial_withdrawal,
+                withdrawn: math::ZERO,
+                funding_model: options.funding_model,
+                asset: AssetMetadata::native(),
+                verified: false,
+                withdrawal_lock: options.withdrawal_lock,
+                wish_type: options.wish_type,
+                charity: options.charity,
+                escrow_bps: options.escrow_bps,
+                minimum_target: options.minimum_target,
+                match_ratio_bps: options.match_ratio_bps,
+                match_remaining: options.match_budget,
+                sealed: options.sealed,
+                paused_at: None,
+                unlisted: false,
+                start_date: options.start_date,
+                milestone: 0,
+                extended: 0,
+                owner_topups: math::ZERO,
+                closed: false,
+                terms: None,
+                auto_claim: options.auto_claim,
+                eoa_only: options.eoa_only,
+                loan: options.loan,
+                soft_deadline: options.soft_deadline,
+                soft_threshold_bps: options.soft_threshold_bps,
+                nft_value: math::ZERO,
+                status: WishStatus::Active,
+            };
+            let funded = Self::funded_bps(&wishlist);
+            // Progress the wish starts with is not announced.
+            wishlist.reach(funded);
+
+            self.next_item_id = self
+                .next_item_id
+                .checked_add(1)
+                .ok_or(Error::InvalidContribution)?;
+            self.items.insert(id, &wishlist);
+            self.active_counts.insert(owner, &(active + 1));
+            let mut owned = self.active_by_owner.get(owner).unwrap_or_default();
+            owned.push(id);
+            self.active_by_owner.insert(owner, &owned);
+            self.index_owner(owner, id);
+            self.record_history(owner, id);
+
+            self.index_upsert(ListingIndex::Newest, id, math::ZERO);
+            self.index_upsert(ListingIndex::Deadline, id, Amount::from(end_date));
+            self.update_funded_index(id, funded);
+            self.index_upsert(ListingIndex::Boost, id, math::ZERO);
+
+            self.env().emit_event(WishlistAdded {
+                id,
+                owner,
+                target,
+                end_date,
+                funding_model: options.funding_model,
+                description_hash,
+            });
+            if raised > math::ZERO {
+                self.track_inflow(id, owner, raised);
+            }
+
+            Ok(id)
+        }
+
+        /// Record `id` under `owner` in the per-owner index, keeping it sorted.
+        fn index_owner(&mut self, owner: H160, id: u32) {
+            let mut owned = self.items_by_owner.get(owner).unwrap_or_default();
+            let position = owned.partition_point(|entry| *entry < id);
+            owned.insert(position, id);
+            self.items_by_owner.insert(owner, &owned);
+        }
+
+        /// Append wish `id` to `account`'s history, read back by
+        /// `settlement_history` and `refund_all`.
+        fn record_history(&mut self, account: H160, id: u32) {
+            let len = self.account_history_len.get(account).unwrap_or(0);
+            self.account_history.insert((account, len), &id);
+            self.account_history_len
+                .insert(account, &len.saturating_add(1));
+        }
+
+        fn unindex_owner(&mut self, owner: H160, id: u32) {
+            let mut owned = self.items_by_owner.get(owner).unwrap_or_default();
+            owned.retain(|entry| *entry != id);
+            self.items_by_owner.insert(owner, &owned);
+        }
+
+        /// Remove a settled wish from storage, archiving its final totals and
+        /// keeping its configuration around if it is due to recur. Unused
+        /// match budget is owed back to the owner.
+        fn retire_wish(&mut self, id: u32, item: WishListItem, outcome: SettlementOutcome) {
+            self.credit(item.owner, item.match_remaining);
+            let beneficiary = item.charity.unwrap_or(item.owner);
+            let claimed = outcome == SettlementOutcome::Claimed;
+            self.settle_tokens(id, beneficiary, claimed);
+            self.settle_nfts(id, beneficiary, claimed);
+            let active = self.active_counts.get(item.owner).unwrap_or(0);
+            self.active_counts
+                .insert(item.owner, &active.saturating_sub(1));
+            let mut owned = self.active_by_owner.get(item.owner).unwrap_or_default();
+            owned.retain(|entry| *entry != id);
+            self.active_by_owner.insert(item.owner, &owned);
+            let contributed = item.contributed;
+            let pot = Self::pot(&item);
+            self.archive.insert(
+                id,
+                &SettlementRecord {
+                    id: item.id,
+                    owner: item.owner,
+                    target: item.target,
+                    raised: item.raised,
+                    contributed,
+                    pot,
+                    outcome,
+                    actor: self.env().caller(),
+                    settled_at: self.now(),
+                    receipt: None,
+                },
+            );
+            self.archived_count = self.archived_count.saturating_add(1);
+            if outcome == SettlementOutcome::Claimed {
+                let mut reputation = self.reputations.get(item.owner).unwrap_or_default();
+                reputation.claimed += 1;
+                self.reputations.insert(item.owner, &reputation);
+                if let Some(terms) = item.loan.filter(|_| !contributed.is_zero()) {
+                    self.loans.insert(
+                        id,
+                        &Loan {
+                            principal: contributed,
+                            repaid: math::ZERO,
+                            terms,
+                            claimed_at: self.now(),
+                        },
+                    );
+                }
+            }
+
+            if let Some(interval) = item.recurrence {
+                self.recurring.insert(
+                    id,
+                    &RecurringWish {
+                        owner: item.owner,
+                        options: item.options(),
+                        description: item.description,
+                        target: item.target,
+                        end_date: item.end_date,
+                        interval,
+                    },
+                );
+            }
+            self.items.remove(id);
+            self.early_claims.remove(id);
+
+            for index in [
+                ListingIndex::Newest,
+                ListingIndex::Deadline,
+                ListingIndex::Funded,
+                ListingIndex::Boost,
+            ] {
+                self.index_remove(index, id);
+            }
+
+            self.managers.remove(id);
+            if let Some(observer) = self.observers.take(id) {
+                let mut observer: ink::contract_ref_from_path!(WishObserver) = observer.into();
+                let _ = observer
+                    .call_mut()
+                    .on_settlement(id, pot)
+                    .ref_time_limit(OBSERVER_REF_TIME_LIMIT)
+                    .try_invoke();
+            }
+        }
+
+        /// Book-keeping for every value that flows into wish `id`: updates the
+        /// raise history and appends to the contribution log.
+        fn track_inflow(&mut self, id: u32, contributor: H160, amount: Amount) {
+            let timestamp = self.now();
+
+            let day = timestamp / DAY;
+            let mut history = self.raise_history.get(id).unwrap_or_default();
+            match history.last_mut() {
+                Some(bucket) if bucket.day == day => bucket.raised += amount,
+                _ => history.push(RaiseBucket {
+                    day,
+                    raised: amount,
+                }),
+            }
+            self.raise_history.insert(id, &history);
+
+            let ordinal = self.contribution_counts.get(id).unwrap_or_default();
+            self.contribution_log.insert(
+                (id, ordinal),
+                &ContributionRecord {
+                    ordinal,
+                    contributor,
+                    amount,
+                    timestamp,
+                },
+            );
+            self.contribution_counts
+                .insert(id, &ordinal.saturating_add(1));
+
+            let activity = match self.contributor_activity.get((id, contributor)) {
+                Some(activity) => ContributorActivity {
+                    last_ordinal: ordinal,
+                    last_at: timestamp,
+                    ..activity
+                },
+                None => ContributorActivity {
+                    first_ordinal: ordinal,
+                    first_at: timestamp,
+                    last_ordinal: ordinal,
+                    last_at: timestamp,
+                },
+            };
+            self.contributor_activity
+                .insert((id, contributor), &activity);
+        }
+
+        /// Whether the pending early claim on wish `id` has passed: either a
+        /// majority of contributions approved it, or the objection window
+        /// closed without a majority objecting.
+        fn early_claim_approved(&self, id: u32) -> bool {
+            let Some(claim) = self.early_claims.get(id) else {
+                return false;
+            };
+            let total = claim.total;
+            let window_closed = self.now() >= claim.requested_at.saturating_add(EARLY_CLAIM_WINDOW);
+
+            let two = Amount::from(2u8);
+            claim.approvals * two > total || (window_closed && claim.objections * two <= total)
+        }
+
+        /// Count a call of the message with `selector` that moved `value`.
+        fn meter(&mut self, selector: u32, value: Amount) {
+            let mut metrics = match self.call_metrics.get(selector) {
+                Some(metrics) => metrics,
+                None => {
+                    let mut selectors = self.metered_selectors.get_or_default();
+                    selectors.push(selector);
+                    self.metered_selectors.set(&selectors);
+                    CallMetrics::default()
+                }
+            };
+            metrics.calls = metrics.calls.saturating_add(1);
+            metrics.value += value;
+            self.call_metrics.insert(selector, &metrics);
+        }
+
+        /// Remove `voter`'s vote on the early claim of wish `id`. Returns
+        /// whether they had voted.
+        fn retract_early_claim_vote(&mut self, id: u32, voter: H160) -> bool {
+            let Some(vote) = self.early_claim_votes.take((id, voter)) else {
+                return false;
+            };
+            if let Some(mut claim) = self.early_claims.get(id) {
+                if vote.approve {
+                    claim.approvals -= vote.weight;
+                } else {
+                    claim.objections -= vote.weight;
+                }
+                self.early_claims.insert(id, &claim);
+            }
+            true
+        }
+
+        /// Retire wish `id` with `outcome` and share its pot out among the
+        /// contributors on the payout ledger, crediting the owner's top-ups
+        /// back to them. Returns the amount shared out.
+        fn refund_contributors(
+            &mut self,
+            id: u32,
+            item: WishListItem,
+            outcome: SettlementOutcome,
+        ) -> Amount {
+            let (total_worth, topups) = Self::failed_sides(&item);
+            let contributed = item.contributed;
+            let mut total_refunded = math::ZERO;
+            for (contributor, _) in self.contributors_of(id, &item) {
+                let amount = self.split_share(id, contributor, total_worth, contributed);
+                total_refunded += amount;
+                self.env().emit_event(RefundIssued {
+                    id,
+                    contributor,
+                    amount,
+                });
+            }
+            let owner = item.owner;
+            self.env().emit_event(WishSplit {
+                id,
+                owner,
+                total_refunded,
+            });
+            self.retire_wish(id, item, outcome);
+            self.distribute(id, total_worth, contributed);
+            self.credit(owner, topups);
+            total_worth
+        }
+
+        #[inline]
+        fn stake_of(&self, id: u32, account: H160) -> Amount {
+            self.stakes.get((id, account)).unwrap_or_default()
+        }
+
+        /// Add `amount` to `account`'s contribution to wish `id`, indexing the
+        /// account on its first contribution.
+        fn add_stake(&mut self, id: u32, item: &mut WishListItem, account: H160, amount: Amount) {
+            let stake = match self.stakes.get((id, account)) {
+                Some(stake) => stake,
+                None => {
+                    self.contributor_index
+                        .insert((id, item.contributor_slots), &account);
+                    item.contributor_slots = item.contributor_slots.saturating_add(1);
+                    self.record_history(account, id);
+                    math::ZERO
+                }
+            };
+            if stake.is_zero() {
+                item.contributor_count = item.contributor_count.saturating_add(1);
+            }
+            self.stakes.insert((id, account), &(stake + amount));
+            item.contributed += amount;
+            if !self.early_claims.contains(id) {
+                self.add_early_claim_weight(id, account, amount);
+            }
+        }
+
+        /// Take `amount`, at most their whole contribution, off `account`'s
+        /// contribution to wish `id`.
+        fn reduce_stake(
+            &mut self,
+            id: u32,
+            item: &mut WishListItem,
+            account: H160,
+            amount: Amount,
+        ) {
+            let stake = self.stake_of(id, account);
+            if stake == amount {
+                item.contributor_count = item.contributor_count.saturating_sub(1);
+            }
+            self.stakes.insert((id, account), &(stake - amount));
+            item.contributed -= amount;
+            let funded = Self::funded_bps(item);
+            self.update_funded_index(id, funded);
+            item.reach(funded);
+        }
+
+        /// Every account with a contribution in wish `id`, and its amount, in
+        /// order of first contribution. Visits every index entry, so only
+        /// settlement paths should need it.
+        fn contributors_of(&self, id: u32, item: &WishListItem) -> Vec<(H160, Amount)> {
+            (0..item.contributor_slots)
+                .filter_map(|slot| self.contributor_index.get((id, slot)))
+                .map(|account| (account, self.stake_of(id, account)))
+                .filter(|(_, stake)| !stake.is_zero())
+                .collect()
+        }
+
+        /// Put every contributor's side of the pot of wish `id` on the payout
+        /// ledger, sharing any shortfall from owner withdrawals pro rata.
+        /// Returns the owner's side, which is left to the caller.
+        fn return_contributions(&mut self, id: u32, item: &WishListItem) -> Amount {
+            let owner_share =
+                (Self::owner_funds(item) + item.forfeited).saturating_sub(item.withdrawn);
+            let available = Self::pot(item) - owner_share;
+            self.distribute(id, available, item.contributed);
+            owner_share
+        }
+
+        /// Give `contributor`'s voting weight on wish `id`, raised by
+        /// `amount`, to their current delegate, moving what they already held
+        /// there if they have delegated elsewhere since.
+        fn add_early_claim_weight(&mut self, id: u32, contributor: H160, amount: Amount) {
+            let voter = self.delegates.get(contributor).unwrap_or(contributor);
+            let mut entry = self
+                .early_claim_stakes
+                .get((id, contributor))
+                .unwrap_or(VoteWeight {
+                    voter,
+                    weight: math::ZERO,
+                });
+            let mut moved = amount;
+            if entry.voter != voter {
+                let held = self
+                    .early_claim_weights
+                    .get((id, entry.voter))
+                    .unwrap_or_default();
+                self.early_claim_weights
+                    .insert((id, entry.voter), &(held - entry.weight));
+                moved += entry.weight;
+                entry.voter = voter;
+            }
+            entry.weight += amount;
+            self.early_claim_stakes.insert((id, contributor), &entry);
+            let held = self
+                .early_claim_weights
+                .get((id, voter))
+                .unwrap_or_default();
+            self.early_claim_weights
+                .insert((id, voter), &(held + moved));
+        }
+
+        /// Take `amount` taken out by `contributor` off their voting weight
+        /// on wish `id`, and off any early-claim vote cast with it.
+        fn reduce_early_claim_weight(&mut self, id: u32, contributor: H160, amount: Amount) {
+            let Some(mut entry) = self.early_claim_stakes.get((id, contributor)) else {
+                return;
+            };
+            let cut = amount.min(entry.weight);
+            entry.weight -= cut;
+            let voter = entry.voter;
+            self.early_claim_stakes.insert((id, contributor), &entry);
+            let held = self
+                .early_claim_weights
+                .get((id, voter))
+                .unwrap_or_default();
+            self.early_claim_weights.insert((id, voter), &(held - cut));
+            let Some(mut claim) = self.early_claims.get(id) else {
+                return;
+            };
+            claim.total -= cut;
+            if let Some(mut vote) = self.early_claim_votes.get((id, voter)) {
+                if vote.approve {
+                    claim.approvals -= cut;
+                } else {
+                    claim.objections -= cut;
+                }
+                vote.weight -= cut;
+                self.early_claim_votes.insert((id, voter), &vote);
+            }
+            self.early_claims.insert(id, &claim);
+        }
+
+        fn summarize(item: &WishListItem) -> WishSummary {
+            WishSummary {
+                id: item.id,
+                owner: item.owner,
+                description: item.description.clone(),
+                target: item.target,
+                pot: Self::pot(item),
+                end_date: item.end_date,
+                funding_model: item.funding_model,
+                symbol: item.asset.symbol.clone(),
+                decimals: item.asset.decimals,
+                verified: item.verified,
+                charity: item.charity,
+                minimum_target: item.minimum(),
+                start_date: item.start_date,
+                owner_topups: item.owner_topups,
+                status: item.status,
+            }
+        }
+
+        /// Everything still held for a wish: the owner's raise and top-ups,
+        /// contributions and forfeited exit penalties, less early withdrawals
+        /// by the owner.
+        fn pot(item: &WishListItem) -> Amount {
+            (Self::owner_funds(item) + item.contributed + item.forfeited)
+                .saturating_sub(item.withdrawn)
+        }
+
+        /// What failed wish `item` shares out among its contributors - their
+        /// stakes, the raise and forfeits - and the owner's top-ups credited
+        /// back to them. Progress withdrawals come out of the top-ups first.
+        fn failed_sides(item: &WishListItem) -> (Amount, Amount) {
+            let from_topups = item.withdrawn.min(item.owner_topups);
+            let shared = (item.contributed + item.raised + item.forfeited)
+                .saturating_sub(item.withdrawn - from_topups);
+            (shared, item.owner_topups - from_topups)
+        }
+
+        /// Ask the verifier registered for `purpose`, if any, whether
+        /// `account` holds its attestation. A failing call counts as a no.
+        fn ensure_attested(&self, purpose: AttestationPurpose, account: H160) -> Result<()> {
+            let Some(verifier) = self.verifiers.get(purpose) else {
+                return Ok(());
+            };
+            let verifier: ink::contract_ref_from_path!(AttestationVerifier) = verifier.into();
+            match verifier.call().has_attestation(account).try_invoke() {
+                Ok(Ok(true)) => Ok(()),
+                _ => Err(Error::AttestationRequired),
+            }
+        }
+
+        /// Pay the claimed pot of wish `id` to its payout split, or all of it
+        /// to `owner` if there is none. Shares are rounded down and the
+        /// remainder goes to the first recipient.
+        fn pay_out(&mut self, id: u32, owner: H160, pot: Amount) -> Result<()> {
+            for (recipient, amount) in self.payout_amounts(id, owner, pot) {
+                self.env()
+                    .transfer(recipient, math::to_native(amount))
+                    .map_err(|_| Error::TransferFailed)?;
+                self.env().emit_event(PayoutSent {
+                    id,
+                    recipient,
+                    amount,
+                });
+            }
+            Ok(())
+        }
+
+        /// How `pay_out` divides `pot` between the recipients.
+        fn payout_amounts(&self, id: u32, owner: H160, pot: Amount) -> Vec<(H160, Amount)> {
+            let shares = self.payout_splits.get(id).unwrap_or_else(|| {
+                ink::prelude::vec![PayoutShare {
+                    recipient: owner,
+                    bps: 10_000,
+                }]
+            });
+            let mut amounts: Vec<(H160, Amount)> = shares
+                .iter()
+                .map(|share| (share.recipient, math::bps(pot, share.bps)))
+                .collect();
+            let dust = pot - amounts.iter().fold(math::ZERO, |acc, cur| acc + cur.1);
+            if let Some(first) = amounts.first_mut() {
+                first.1 += dust;
+            }
+            amounts
+        }
+
+        /// The round wish `id` is in and its progress with `value` counted,
+        /// if the wish has rounds. Fails if `value` does not fit the round.
+        /// Nothing is written until `enter_round`.
+        fn round_entry(&self, id: u32, value: Amount) -> Result<Option<(Round, RoundProgress)>> {
+            let Some(rounds) = self.rounds.get(id) else {
+                return Ok(None);
+            };
+            let now = self.now();
+            let index = rounds
+                .iter()
+                .position(|r| now < r.ends_at)
+                .ok_or(Error::NoActiveRound)?;
+            let round = rounds[index];
+            let mut progress = self.round_progress.get(id).unwrap_or_default();
+            if progress.index != index as u32 {
+                progress = RoundProgress {
+                    index: index as u32,
+                    raised: math::ZERO,
+                };
+            }
+            if value < round.min_contribution || progress.raised + value > round.cap {
+                return Err(Error::RoundLimitExceeded);
+            }
+            progress.raised += value;
+            Ok(Some((round, progress)))
+        }
+
+        /// Record `progress` from `round_entry` for wish `id`, announcing the
+        /// round if this is the first contribution to it.
+        fn enter_round(&mut self, id: u32, owner: H160, round: Round, progress: RoundProgress) {
+            let started = self.round_progress.get(id).is_none_or(|previous| {
+                previous.index != progress.index || previous.raised.is_zero()
+            });
+            if started {
+                self.env().emit_event(RoundStarted {
+                    id,
+                    owner,
+                    round: progress.index,
+                    cap: round.cap,
+                    ends_at: round.ends_at,
+                });
+            }
+            self.round_progress.insert(id, &progress);
+        }
+
+        /// Share `amount` out among the contributors of wish `id` in
+        /// proportion to their stake, `shares` being the sum of all stakes.
+        /// Nothing is transferred; each contributor collects their part with
+        /// `withdraw_payout`, so settlement costs the same for any number of
+        /// backers. Rounding dust stays in the contract.
+        fn distribute(&mut self, id: u32, amount: Amount, shares: Amount) {
+            if shares.is_zero() {
+                return;
+            }
+            let per_share = self.payout_per_share.get(id).unwrap_or_default();
+            let added = math::mul_div(amount, Amount::from(PAYOUT_PRECISION), shares);
+            self.payout_per_share.insert(id, &(per_share + added));
+            self.held.payouts += amount;
+        }
+
+        /// Check that `contributor` may fund wish `id` right now.
+        fn ensure_fundable(&self, id: u32, item: &WishListItem, contributor: H160) -> Result<()> {
+            if self.paused {
+                return Err(Error::ContractPaused);
+            }
+            if item.paused_at.is_some() {
+                return Err(Error::WishPaused);
+            }
+            if item.closed {
+                return Err(Error::FundingClosed);
+            }
+            if self.bans.contains((id, contributor)) {
+                return Err(Error::BannedContributor);
+            }
+            if item.start_date.is_some_and(|start| self.now() < start) {
+                return Err(Error::WishUpcoming);
+            }
+            if item.eoa_only && self.env().is_contract(&contributor) {
+                return Err(Error::ContractContributor);
+            }
+            if self.failed_early(item) {
+                return Err(Error::SoftDeadlineMissed);
+            }
+            Ok(())
+        }
+
+        /// Add `amount` of PSP22 `token` from `contributor` to wish `id`.
+        fn record_token_contribution(
+            &mut self,
+            id: u32,
+            token: H160,
+            contributor: H160,
+            amount: Amount,
+        ) -> Result<()> {
+            if !self.accepted_tokens.contains(token) {
+                return Err(Error::TokenNotAccepted);
+            }
+            if amount.is_zero() {
+                return Err(Error::InvalidContribution);
+            }
+            let item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if self.now() >= item.end_date {
+                return Err(Error::WishEnded);
+            }
+            self.ensure_fundable(id, &item, contributor)?;
+
+            let mut pots = self.token_pots.get(id).unwrap_or_default();
+            let index = match pots.iter().position(|pot| pot.token == token) {
+                Some(index) => index,
+                None if pots.len() < MAX_WISH_TOKENS => {
+                    pots.push(TokenPot {
+                        token,
+                        contributions: Vec::new(),
+                    });
+                    pots.len() - 1
+                }
+                None => return Err(Error::TooManyTokens),
+            };
+            let contributions = &mut pots[index].contributions;
+            match contributions.iter_mut().find(|c| c.0 == contributor) {
+                Some(position) => position.1 += amount,
+                None => contributions.push((contributor, amount)),
+            }
+            self.token_pots.insert(id, &pots);
+            self.env().emit_event(TokenContributed {
+                id,
+                token,
+                contributor,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Move the token contributions to wish `id` onto the token ledger:
+        /// all of them to `beneficiary` if the wish was claimed, otherwise
+        /// back to each contributor.
+        fn settle_tokens(&mut self, id: u32, beneficiary: H160, claimed: bool) {
+            for pot in self.token_pots.take(id).unwrap_or_default() {
+                for (contributor, amount) in pot.contributions {
+                    let account = if claimed { beneficiary } else { contributor };
+                    let balance = self
+                        .token_credits
+                        .get((account, pot.token))
+                        .unwrap_or_default();
+                    self.token_credits
+                        .insert((account, pot.token), &(balance + amount));
+                }
+            }
+        }
+
+        /// Record NFT `token_id` of `collection`, sent by `contributor`
+        /// towards wish `id` and awaiting the owner's review.
+        fn record_nft_deposit(
+            &mut self,
+            id: u32,
+            collection: H160,
+            token_id: Id,
+            contributor: H160,
+            valuation: Amount,
+        ) -> Result<()> {
+            if valuation.is_zero() {
+                return Err(Error::InvalidContribution);
+            }
+            let item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if self.now() >= item.end_date {
+                return Err(Error::WishEnded);
+            }
+            self.ensure_fundable(id, &item, contributor)?;
+            let mut held = self.wish_nfts.get(id).unwrap_or_default();
+            if held.len() >= MAX_WISH_NFTS {
+                return Err(Error::TooManyNfts);
+            }
+            let deposit = self.next_nft_deposit;
+            self.next_nft_deposit = deposit.checked_add(1).ok_or(Error::InvalidContribution)?;
+            held.push(deposit);
+            self.wish_nfts.insert(id, &held);
+            self.nft_deposits.insert(
+                deposit,
+                &NftDeposit {
+                    id,
+                    collection,
+                    token_id: token_id.clone(),
+                    contributor,
+                    valuation,
+                    accepted: false,
+                    owed_to: None,
+                },
+            );
+            self.env().emit_event(NftDeposited {
+                id,
+                deposit,
+                contributor,
+                collection,
+                token_id,
+                valuation,
+            });
+            Ok(())
+        }
+
+        /// NFT `deposit` and its open wish, if the caller may review it.
+        fn review_nft(&self, deposit: u32) -> Result<(NftDeposit, WishListItem)> {
+            let nft = self.nft_deposits.get(deposit).ok_or(Error::NftNotFound)?;
+            let item = self.items.get(nft.id).ok_or(Error::WishNotFound)?;
+            if !self.can_manage(nft.id, &item, self.get_caller()) {
+                return Err(Error::NotOwner);
+            }
+            if nft.accepted || nft.owed_to.is_some() {
+                return Err(Error::NftReviewed);
+            }
+            Ok((nft, item))
+        }
+
+        /// Release the NFTs held for wish `id`: accepted ones to
+        /// `beneficiary` if the wish was claimed, all others back to their
+        /// contributors.
+        fn settle_nfts(&mut self, id: u32, beneficiary: H160, claimed: bool) {
+            for deposit in self.wish_nfts.take(id).unwrap_or_default() {
+                if let Some(mut nft) = self.nft_deposits.get(deposit) {
+                    nft.owed_to = Some(if claimed && nft.accepted {
+                        beneficiary
+                    } else {
+                        nft.contributor
+                    });
+                    self.nft_deposits.insert(deposit, &nft);
+                }
+            }
+        }
+
+        /// Settle wish `id` by the auto-claim rules if it has met its target.
+        fn auto_claim_at_target(&mut self, id: u32) -> Result<()> {
+            match self.items.get(id) {
+                Some(item) if item.auto_claim && Self::counted(&item) >= item.target => {
+                    self.auto_claim(id, item)
+                }
+                _ => Ok(()),
+            }
+        }
+
+        fn auto_claim(&mut self, id: u32, item: WishListItem) -> Result<()> {
+            let (owner, pot) = (item.owner, Self::pot(&item));
+            self.settle_claim(id, item, false)?;
+            self.env().emit_event(AutoClaimed { id, owner, pot });
+            Ok(())
+        }
+
+        /// Pay out the pot of wish `id` to its owner, or its charity, holding
+        /// back any escrow. With `push` the payout is transferred, otherwise
+        /// it is credited to the pull ledger.
+        fn settle_claim(&mut self, id: u32, item: WishListItem, push: bool) -> Result<()> {
+            if let Some(purchase) = self.purchases.get(id) {
+                return self.settle_purchase(id, item, purchase);
+            }
+            let beneficiary = item.charity.unwrap_or(item.owner);
+            let pot = Self::pot(&item);
+            let fee = self.claim_fee_of(pot);
+            let held = Self::escrow_of(&item, pot - fee);
+            self.env().emit_event(WishClaimed {
+                id,
+                owner: item.owner,
+                amount: pot,
+                timestamp: self.now(),
+            });
+            if !fee.is_zero() {
+                self.accumulated_fees += fee;
+                self.env().emit_event(ClaimFeeCharged {
+                    id,
+                    owner: item.owner,
+                    fee,
+                });
+            }
+            if push {
+                self.pay_out(id, beneficiary, pot - fee - held)?;
+            } else {
+                for (recipient, amount) in self.payout_amounts(id, beneficiary, pot - fee - held) {
+                    self.credit(recipient, amount);
+                }
+            }
+            if held.is_zero() {
+                self.payout_splits.remove(id);
+            } else {
+                self.held.escrowed += held;
+                self.escrows.insert(
+                    id,
+                    &Escrow {
+                        beneficiary,
+                        amount: held,
+                        contributed: item.contributed,
+                        confirmed: math::ZERO,
+                        deadline: self.now().saturating_add(FULFILLMENT_WINDOW),
+                    },
+                );
+            }
+            self.retire_wish(id, item, SettlementOutcome::Claimed);
+            Ok(())
+        }
+
+        /// Buy `purchase` for the beneficiary of wish `id` out of its pot,
+        /// refund the rest to the owner and contributors in proportion to
+        /// what they put in, and retire the wish.
+        fn settle_purchase(
+            &mut self,
+            id: u32,
+            item: WishListItem,
+            purchase: Purchase,
+        ) -> Result<()> {
+            let beneficiary = item.charity.unwrap_or(item.owner);
+            let pot = Self::pot(&item);
+            let mut marketplace: ink::contract_ref_from_path!(Marketplace) =
+                purchase.marketplace.into();
+            let price = match marketplace.call().price(purchase.listing).try_invoke() {
+                Ok(Ok(Some(price))) if price <= purchase.max_price && price <= pot => price,
+                _ => return Err(Error::PurchaseFailed),
+            };
+            match marketplace
+                .call_mut()
+                .buy(purchase.listing, beneficiary)
+                .transferred_value(math::to_native(price))
+                .try_invoke()
+            {
+                Ok(Ok(Ok(()))) => {}
+                _ => return Err(Error::PurchaseFailed),
+            }
+
+            let leftover = pot - price;
+            let owner_funds = Self::owner_funds(&item);
+            let contributed = item.contributed;
+            let owner_share = if contributed.is_zero() {
+                leftover
+            } else {
+                math::mul_div(leftover, owner_funds, owner_funds + contributed)
+            };
+            self.credit(item.owner, owner_share);
+            self.distribute(id, leftover - owner_share, contributed);
+            self.purchases.remove(id);
+            self.payout_splits.remove(id);
+            self.env().emit_event(WishPurchased {
+                id,
+                marketplace: purchase.marketplace,
+                listing: purchase.listing,
+                price,
+                leftover,
+            });
+            self.retire_wish(id, item, SettlementOutcome::Claimed);
+            Ok(())
+        }
+
+        /// Mark everything `account` can withdraw from wish `id` as withdrawn
+        /// and return it, for the caller to transfer.
+        fn take_payout(&mut self, id: u32, account: H160) -> Amount {
+            let amount = self.get_payout(id, account);
+            if amount.is_zero() {
+                return amount;
+            }
+            let withdrawn = self.payout_withdrawn.get((id, account)).unwrap_or_default();
+            self.payout_withdrawn
+                .insert((id, account), &(withdrawn + amount));
+            self.held.payouts -= amount;
+            self.env().emit_event(PayoutWithdrawn {
+                id,
+                account,
+                amount,
+            });
+            amount
+        }
+
+        /// Whether `claim_wish` would succeed for the owner of wish `id` now.
+        fn can_claim_now(&self, id: u32, item: &WishListItem) -> bool {
+            let now = self.now();
+            if item.sealed && now < item.end_date.saturating_add(REVEAL_WINDOW) {
+                return false;
+            }
+            (now >= item.end_date || self.early_claim_approved(id)) && self.claimable(id, item)
+        }
+
+        /// The part of a claimed `pot` held in escrow. Nothing is held when
+        /// there are no contributors to confirm fulfillment.
+        fn escrow_of(item: &WishListItem, pot: Amount) -> Amount {
+            if item.contributor_count == 0 {
+                math::ZERO
+            } else {
+                math::bps(pot, item.escrow_bps)
+            }
+        }
+
+        /// `account`'s share of `pot` were it distributed over `contributed`
+        /// stake on wish `id`, rounded as `distribute` and `get_payout` round.
+        fn split_share(&self, id: u32, account: H160, pot: Amount, contributed: Amount) -> Amount {
+            if contributed.is_zero() {
+                return math::ZERO;
+            }
+            let stake = self.stakes.get((id, account)).unwrap_or_default();
+            let per_share = self.payout_per_share.get(id).unwrap_or_default();
+            let precision = Amount::from(PAYOUT_PRECISION);
+            let added = math::mul_div(pot, precision, contributed);
+            math::mul_div(stake, per_share + added, precision)
+                - math::mul_div(stake, per_share, precision)
+        }
+
+        /// Whether the wish is past its soft deadline short of its soft
+        /// threshold.
+        fn failed_early(&self, item: &WishListItem) -> bool {
+            item.soft_deadline.is_some_and(|soft| self.now() >= soft)
+                && Self::funded_bps(item) < Amount::from(item.soft_threshold_bps)
+        }
+
+        /// Whether wish `id` fell short: it is neither `Funded` nor otherwise
+        /// claimable, so its pot may go back to the contributors.
+        fn fell_short(&self, id: u32, item: &WishListItem) -> bool {
+            item.status != WishStatus::Funded && !self.claimable(id, item)
+        }
+
+        fn extension(&self, wish_type: u32) -> Option<ink::contract_ref_from_path!(WishExtension)> {
+            self.extensions.get(wish_type).map(Into::into)
+        }
+
+        /// Whether the owner may take the pot of wish `id`. A wish type's
+        /// extension decides for its wishes; if it cannot be reached the
+        /// target rule applies. Wishes that failed at their soft deadline
+        /// never are.
+        fn claimable(&self, id: u32, item: &WishListItem) -> bool {
+            if self.failed_early(item) {
+                return false;
+            }
+            if let Some(extension) = item.wish_type.and_then(|t| self.extension(t)) {
+                if let Ok(Ok(claimable)) = extension
+                    .call()
+                    .claimable(id, Self::counted(item), item.minimum())
+                    .try_invoke()
+                {
+                    return claimable;
+                }
+            }
+            Self::counted(item) >= item.minimum()
+                || item.funding_model == FundingModel::KeepWhatYouRaise
+        }
+
+        fn set_standing_order(
+            &mut self,
+            contributor: H160,
+            id: u32,
+            amount: Amount,
+            period: u64,
+            cap: Amount,
+        ) -> Result<()> {
+            if amount.is_zero() || period == 0 || cap < amount {
+                return Err(Error::InvalidContribution);
+            }
+            if !self.items.contains(id) {
+                return Err(Error::WishNotFound);
+            }
+
+            let order = StandingOrder {
+                amount,
+                period,
+                cap,
+                paid: math::ZERO,
+                next_due: self.now(),
+            };
+            self.standing_orders.insert((contributor, id), &order);
+            Ok(())
+        }
+
+        /// The Ethereum address that signed the EIP-712 message with
+        /// `struct_hash` under this contract's domain, if the signature is valid.
+        fn recover_signer(&self, struct_hash: [u8; 32], signature: &[u8; 65]) -> Option<H160> {
+            let mut message = Vec::with_capacity(66);
+            message.extend_from_slice(b"\x19\x01");
+            message.extend_from_slice(&self.domain_separator());
+            message.extend_from_slice(&struct_hash);
+            let digest = self.env().hash_bytes::<Keccak256>(&message);
+
+            let public_key = self.env().ecdsa_recover(signature, &digest).ok()?;
+            let address = self.env().ecdsa_to_eth_address(&public_key).ok()?;
+            Some(H160::from(address))
+        }
+
+        /// The value sent with the call, which must fit in `Amount`.
+        fn transferred(&self) -> Result<Amount> {
+            math::from_native(self.env().transferred_value()).ok_or(Error::AmountOverflow)
+        }
+
+        /// The current time according to the contract's `clock`.
+        fn now(&self) -> u64 {
+            self.clock.now()
+        }
+
+        fn ensure_admin(&self) -> Result<()> {
+            if self.env().caller() != self.admin {
+                return Err(Error::OnlyAdmin);
+            }
+            Ok(())
+        }
+
+        /// The platform's cut of a claimed `pot`.
+        fn claim_fee_of(&self, pot: Amount) -> Amount {
+            math::bps(pot, self.claim_fee_bps)
+        }
+
+        fn set_paused(&mut self, paused: bool) -> Result<()> {
+            self.ensure_admin()?;
+            self.paused = paused;
+            self.env().emit_event(ContractPauseSet {
+                admin: self.admin,
+                paused,
+            });
+            Ok(())
+        }
+
+        /// Owe `amount` to `account` on the pull ledger.
+        fn credit(&mut self, account: H160, amount: Amount) {
+            if amount.is_zero() {
+                return;
+            }
+            let balance = self.credits.get(account).unwrap_or_default();
+            self.credits.insert(account, &(balance + amount));
+            self.held.credits += amount;
+        }
+
+        /// The highest of `MILESTONES` reached at `funded` basis points.
+        fn milestone_of(funded: Amount) -> u8 {
+            MILESTONES
+                .into_iter()
+                .rfind(|percent| funded >= Amount::from(u16::from(*percent) * 100))
+                .unwrap_or(0)
+        }
+
+        /// Emit `MilestoneReached` for each milestone wish `id` crossed since
+        /// the last one announced. Each is announced once, even if the wish
+        /// later drops back below it. Also refiles the wish in the funded
+        /// index.
+        fn announce_milestones(&mut self, id: u32, item: &mut WishListItem) {
+            let funded = Self::funded_bps(item);
+            self.update_funded_index(id, funded);
+            let reached = Self::milestone_of(funded);
+            for percent in MILESTONES {
+                if percent > item.milestone && percent <= reached {
+                    self.env().emit_event(MilestoneReached {
+                        id,
+                        owner: item.owner,
+                        percent,
+                    });
+                }
+            }
+            item.reach(funded);
+        }
+
+        /// Share of the target raised so far, in basis points.
+        fn funded_bps(item: &WishListItem) -> Amount {
+            math::to_bps(Self::counted(item), item.target)
+        }
+
+        /// The raise and the owner's top-ups.
+        fn owner_funds(item: &WishListItem) -> Amount {
+            item.raised + item.owner_topups
+        }
+
+        /// What counts towards the target: the owner's funds and accepted NFTs.
+        fn counted(item: &WishListItem) -> Amount {
+            Self::owner_funds(item) + item.nft_value
+        }
+
+        /// Check that wish `id` is open and owned by `owner`, so it can join
+        /// their campaign.
+        fn ensure_campaign_wish(&self, id: u32, owner: H160) -> Result<()> {
+            let item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if item.owner != owner {
+                return Err(Error::NotOwner);
+            }
+            Ok(())
+        }
+
+        /// Whether `account` is the owner, manager or an operator of the owner
+        /// of wish `id`.
+        fn can_manage(&self, id: u32, item: &WishListItem, account: H160) -> bool {
+            item.owner == account
+                || self.managers.get(id) == Some(account)
+                || self.operators.contains((item.owner, account))
+        }
+
+        fn update_funded_index(&mut self, id: u32, funded: Amount) {
+            self.index_upsert(ListingIndex::Funded, id, funded);
+        }
+
+        /// Where `entry` belongs in `directory`: the last page starting at or
+        /// below it, or the first page if it sorts before all of them.
+        fn index_slot(directory: &[IndexPage], entry: IndexEntry) -> usize {
+            directory
+                .partition_point(|page| page.first <= entry)
+                .saturating_sub(1)
+        }
+
+        /// File wish `id` under `key` in `index`, moving it if it was filed
+        /// under another key. Touches one page, two when it has to split.
+        fn index_upsert(&mut self, index: ListingIndex, id: u32, key: Amount) {
+            if self.index_keys.get((index, id)) == Some(key) {
+                return;
+            }
+            self.index_remove(index, id);
+            let entry = (key, id);
+            let mut directory = self.index_directory.get(index).unwrap_or_default();
+            if directory.is_empty() {
+                directory.push(IndexPage {
+                    page: self.next_index_page,
+                    first: entry,
+                    len: 0,
+                });
+                self.next_index_page += 1;
+            }
+            let slot = Self::index_slot(&directory, entry);
+            let page = directory[slot].page;
+            let mut entries = self.index_pages.get(page).unwrap_or_default();
+            let position = entries.partition_point(|other| *other < entry);
+            entries.insert(position, entry);
+            if entries.len() > INDEX_PAGE_SIZE {
+                let upper = entries.split_off(entries.len() / 2);
+                let split = IndexPage {
+                    page: self.next_index_page,
+                    first: upper[0],
+                    len: upper.len() as u32,
+                };
+                self.next_index_page += 1;
+                self.index_pages.insert(split.page, &upper);
+                directory.insert(slot + 1, split);
+            }
+            directory[slot].first = entries[0];
+            directory[slot].len = entries.len() as u32;
+            self.index_pages.insert(page, &entries);
+            self.index_directory.insert(index, &directory);
+            self.index_keys.insert((index, id), &key);
+        }
+
+        /// Take wish `id` out of `index`, dropping its page once empty.
+        fn index_remove(&mut self, index: ListingIndex, id: u32) {
+            let Some(key) = self.index_keys.take((index, id)) else {
+                return;
+            };
+            let entry = (key, id);
+            let mut directory = self.index_directory.get(index).unwrap_or_default();
+            if directory.is_empty() {
+                return;
+            }
+            let slot = Self::index_slot(&directory, entry);
+            let page = directory[slot].page;
+            let mut entries = self.index_pages.get(page).unwrap_or_default();
+            entries.retain(|other| *other != entry);
+            if entries.is_empty() {
+                self.index_pages.remove(page);
+                directory.remove(slot);
+            } else {
+                directory[slot].first = entries[0];
+                directory[slot].len = entries.len() as u32;
+                self.index_pages.insert(page, &entries);
+            }
+            self.index_directory.insert(index, &directory);
+        }
+
+        /// Entries of `index` from `from` onwards in ascending order, reading
+        /// a page only once the iteration reaches it.
+        fn index_entries(
+            &self,
+            index: ListingIndex,
+            from: IndexEntry,
+        ) -> impl DoubleEndedIterator<Item = IndexEntry> + '_ {
+            let directory = self.index_directory.get(index).unwrap_or_default();
+            let slot = Self::index_slot(&directory, from);
+            directory
+                .into_iter()
+                .skip(slot)
+                .flat_map(move |page| self.index_pages.get(page.page).unwrap_or_default())
+                .filter(move |entry| *entry >= from)
+        }
+
+        /// Ids in `index` filed under `from` or above, in ascending order.
+        fn index_ids(
+            &self,
+            index: ListingIndex,
+            from: Amount,
+        ) -> impl DoubleEndedIterator<Item = u32> + '_ {
+            self.index_entries(index, (from, 0)).map(|entry| entry.1)
+        }
+
+        /// Collect the listed active wishes for `ids`, skipping `offset`
+        /// entries and returning at most `limit` (capped at `MAX_PAGE_SIZE`).
+        fn page_of(
+            &self,
+            ids: impl Iterator<Item = u32>,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<WishListItem> {
+            ids.filter_map(|id| self.items.get(id))
+                .filter(|item| !item.unlisted)
+                .skip(offset as usize)
+                .take(limit.min(MAX_PAGE_SIZE) as usize)
+                .collect()
+        }
+    }
+
+    impl WishlistCore for Wishlist {
+        /// add a wishlist item to the accountId
+        #[ink(message, payable)]
+        fn add_wishlist_item(
+            &mut self,
+            description: String,
+            end_date: u64,
+            target: Amount,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            self.meter(ink::selector_id!("add_wishlist_item"), self.transferred()?);
+            self.add_item(
+                caller,
+                description,
+                end_date,
+                target,
+                WishOptions::default(),
+            )
+            .map(|_| ())
+        }
+
+        #[ink(message, payable)]
+        fn fund_wish(&mut self, id: u32) -> Result<()> {
+            let caller = self.get_caller();
+            let value = self.transferred()?;
+            if value.is_zero() {
+                return Err(Error::InvalidContribution);
+            }
+            self.meter(ink::selector_id!("fund_wish"), value);
+            if let Some(item) = self.items.get(id) {
+                if item.sealed {
+                    return Err(Error::SealedWish);
+                }
+                if item.terms.is_some() {
+                    return Err(Error::TermsMismatch);
+                }
+            }
+            self.ensure_attested(AttestationPurpose::Personhood, caller)?;
+
+            self.record_contribution(id, caller, value)?;
+            self.auto_claim_at_target(id)
+        }
+
+        #[ink(message)]
+        fn claim_wish(&mut self, id: u32) -> Result<()> {
+            let caller = self.get_caller();
+            let item = self.items.get(id).ok_or(Error::WishNotFound)?;
+            if item.owner != caller && !self.operators.contains((item.owner, caller)) {
+                return Err(Error::NotOwner);
+            }
+            let time = self.now();
+            if item.sealed && time < item.end_date.saturating_add(REVEAL_WINDOW) {
+                return Err(Error::NotRevealPhase);
+            }
+            if time < item.end_date && !self.early_claim_approved(id) {
+                return Err(Error::EndDateNotReached);
+            }
+            if !self.claimable(id, &item) {
+                return Err(Error::TargetNotReached);
+            }
+            self.meter(ink::selector_id!("claim_wish"), Self::pot(&item));
+            self.settle_claim(id, item, true)
+        }
+
+        /// Withdraw the caller's share of failed wish `id`. The first call
+        /// after the end date moves the wish into `Refunding`, after which
+        /// every contributor pulls their own share, so no single transfer can
+        /// hold up the others.
+        #[ink(message)]
+        fn claim_refund(&mut self, id: u32) -> Result<()> {
+            let caller = self.get_caller();
+            if let Some(item) = self.items.get(id) {
+                if item.funding_model == FundingModel::KeepWhatYouRaise {
+                    return Err(Error::NotRefundable);
+                }
+                let now = self.now();
+                let reveal_end = item.end_date.saturating_add(REVEAL_WINDOW);
+                if item.sealed && now < reveal_end {
+                    return Err(Error::NotRevealPhase);
+                }
+                let ended = now >= item.end_date || self.failed_early(&item);
+                if !ended || !self.fell_short(id, &item) {
+                    return Err(Error::NotFailed);
+                }
+                self.refund_contributors(id, item, SettlementOutcome::Refunding);
+            }
+            let amount = self.take_payout(id, caller);
+            if amount.is_zero() {
+                return Err(Error::NothingToWithdraw);
+            }
+            self.meter(ink::selector_id!("claim_refund"), amount);
+            self.env()
+                .transfer(self.payout_address(caller), math::to_native(amount))
+                .map_err(|_| Error::TransferFailed)
+        }
+
+        /// Look up wish `id`: the wish itself while it is active, or its
+        /// settlement record once it has been settled.
+        #[ink(message)]
+        fn get_wishlist_item(&self, id: u32) -> Result<WishEntry> {
+            match self.items.get(id) {
+                Some(item) => Ok(WishEntry::Active(item)),
+                None => self
+                    .archive
+                    .get(id)
+                    .map(WishEntry::Settled)
+                    .ok_or(Error::WishNotFound),
+            }
+        }
+
+        /// Status of wish `id`, whether it is still open or settled. `None`
+        /// if it never existed.
+        #[ink(message)]
+        fn get_wish_status(&self, id: u32) -> Option<WishStatus> {
+            match self.items.get(id) {
+                Some(item) => Some(item.status),
+                None => self.archive.get(id).map(|record| record.outcome.status()),
+            }
+        }
+
+        #[ink(message)]
+        fn get_contribution(&self, id: u32, account: H160) -> Amount {
+            self.stake_of(id, account)
+        }
+    }
+
+    impl PSP22Receiver for Wishlist {
+        /// Accept tokens sent by an approved PSP22 token with `data` holding
+        /// the SCALE-encoded `(wish id, contributor)` to credit them to.
+        /// Tokens the contract pulls itself in `fund_wish_psp22` are
+        /// recorded there.
+        #[ink(message)]
+        fn before_received(
+            &mut self,
+            operator: H160,
+            _from: H160,
+            value: Amount,
+            data: Vec<u8>,
+        ) -> core::result::Result<(), PSP22ReceiverError> {
+            if operator == self.env().address() {
+                return Ok(());
+            }
+            let token = self.get_caller();
+            let rejected = |error: Error| {
+                PSP22ReceiverError::TransferRejected(ink::prelude::format!(
+                    "wishlist error {}",
+                    error.error_code()
+                ))
+            };
+            let (id, contributor) = <(u32, H160) as ink::scale::Decode>::decode(&mut &data[..])
+                .map_err(|_| rejected(Error::InvalidContribution))?;
+            self.record_token_contribution(id, token, contributor, value)
+                .map_err(rejected)
+        }
+    }
+
+    impl PSP34Receiver for Wishlist {
+        /// Hold an NFT sent by its collection with `data` holding the
+        /// SCALE-encoded `(wish id, valuation)` it is offered at.
+        #[ink(message)]
+        fn before_received(
+            &mut self,
+            _operator: H160,
+            from: H160,
+            id: Id,
+            data: Vec<u8>,
+        ) -> core::result::Result<(), PSP34ReceiverError> {
+            let collection = self.get_caller();
+            let rejected = |error: Error| {
+                PSP34ReceiverError::TransferRejected(ink::prelude::format!(
+                    "wishlist error {}",
+                    error.error_code()
+                ))
+            };
+            let (wish, valuation) = <(u32, Amount) as ink::scale::Decode>::decode(&mut &data[..])
+                .map_err(|_| rejected(Error::InvalidContribution))?;
+            self.record_nft_deposit(wish, collection, id, from, valuation)
+                .map_err(rejected)
+        }
+    }
+
+    /// Unit tests in Rust are normally defined within such a `#[cfg(test)]`
+    /// module and test functions are marked with a `#[test]` attribute.
+    /// The below code is technically just normal Rust code.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+        use ink::env::test::*;
+
+        /// `value` as an `Amount`, whichever balance type the contract is
+        /// built with.
+        #[cfg_attr(feature = "u128-balance", allow(clippy::useless_conversion))]
+        fn amount(value: u128) -> Amount {
+            Amount::from(value)
+        }
+
+        /// We test if the default constructor does its job.
+        #[ink::test]
+        fn default_works() {
+            let wishlist = Wishlist::default();
+            assert_eq!(wishlist.next_item_id, 1_u32);
+        }
+
+        /// The transferred_value must be greater than 10% of the target amount
+        #[ink::test]
+        fn add_wishlist_value_must_match_target() {
+            let mut wishlist = Wishlist::default();
+            let target = amount(1000);
+            let end_date = 1752797402; // Example timestamp
+            let description = String::from("Test Wishlist");
+
+            // No transfer value should fail
+            let result = wishlist.add_wishlist_item(description, end_date, target);
+            assert!(result.is_err(), "Expected error for no transfer value");
+            assert!(result.err() == Some(Error::InvalidContribution));
+
+            // Pass value less that 10% target
+            let description = String::from("Test < 10% Wishlist");
+            let transfer_value = U256::from(50);
+            set_value_transferred(transfer_value);
+            let result = wishlist.add_wishlist_item(description, end_date, target);
+            assert!(
+                result.is_err(),
+                "Expected error for insufficient transfer value"
+            );
+            assert!(result.err() == Some(Error::InvalidContribution));
+
+            let description = String::from("Test >= 10% Wishlist");
+            let transfer_value = U256::from(100);
+            set_value_transferred(transfer_value);
+            let result = wishlist.add_wishlist_item(description, end_date, target);
+            assert!(
+                result.is_ok(),
+                "Expected successful addition of wishlist item"
+            );
+            assert_eq!(wishlist.next_item_id, 2_u32);
+        }
+
+        #[ink::test]
+        pub fn add_wishlist_should_succeed() {
+            let mut contract = Wishlist::default();
+            let description = String::from("Add Wishlist should succeed");
+            let end_date = 1752798324779;
+            let target = amount(1000);
+
+            set_value_transferred(U256::from(115));
+            let result = contract.add_wishlist_item(description, end_date, target);
+
+            assert!(result.is_ok(), "is should be Ok");
+            assert_eq!(contract.next_item_id, 2_u32);
+            let item = contract.get_wishlist_item(1);
+            assert!(item.is_ok(), "Item should be found");
+            assert_eq!(item.unwrap().active().unwrap().raised, amount(115));
+        }
+
+        #[ink::test]
+        pub fn fund_wish_should_succeed() {
+            let mut wishlist = Wishlist::default();
+
+            set_value_transferred(U256::from(125));
+            set_caller(default_accounts().alice);
+            let _ = wishlist.add_wishlist_item(
+                String::from("Wishlist Item 1"),
+                1752798324779,
+                amount(1000),
+            );
+
+            // value_transferred mjst not be 0
+            set_value_transferred(U256::zero());
+            let result = wishlist.fund_wish(1);
+            assert!(result.is_err(), "Funding will not succeed");
+            assert_eq!(result.err(), Some(Error::InvalidContribution));
+
+            // ID must exist
+            set_value_transferred(U256::from(10));
+            let result = wishlist.fund_wish(2);
+            assert!(result.is_err(), "ID must exist");
+            assert_eq!(result.err(), Some(Error::WishNotFound));
+
+            set_value_transferred(U256::from(10));
+            let result = wishlist.fund_wish(1);
+            assert!(result.is_ok(), "Funding should succeed");
+            assert_eq!(wishlist.next_item_id, 2_u32);
+            let item = wishlist.get_wishlist_item(1).unwrap().active().unwrap();
+            assert_eq!(item.raised, amount(125));
+            assert_eq!(item.owner_topups, amount(10));
+
+            set_caller(default_accounts().bob);
+            let result = wishlist.fund_wish(1);
+            assert!(result.is_ok(), "Funding should succeed");
+            assert_eq!(
+                wishlist
+                    .get_wishlist_item(1)
+                    .unwrap()
+                    .active()
+                    .unwrap()
+                    .raised,
+                amount(125)
+            );
+            assert_eq!(
+                wishlist
+                    .get_wishlist_item(1)
+                    .unwrap()
+                    .active()
+                    .unwrap()
+                    .contributor_count,
+                1
+            );
+            assert_eq!(
+                wishlist.get_contributors(1, 0, MAX_PAGE_SIZE)[0].0,
+                default_accounts().bob
+            );
+        }
+
+        #[ink::test]
+        pub fn claim_wish_fail_for_error_conditions() {
+            let mut wishlist = Wishlist::default();
+
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(120));
+            let _ =
+                wishlist.add_wishlist_item(String::from("Wishlist Item"), 1752800402, amount(1000));
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.fund_wish(1);
+
+            set_caller(default_accounts().alice);
+            advance_block::<ink::env::DefaultEnvironment>();
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1752800500);
+            let result = wishlist.claim_wish(1);
+            assert!(result.is_err(), "Claiming wish should fail");
+            assert_eq!(result.err(), Some(Error::TargetNotReached));
+        }
+
+        #[ink::test]
+        pub fn claim_wish_should_succeed() {
+            let mut wishlist = Wishlist::default();
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(2));
+            let _ =
+                wishlist.add_wishlist_item(String::from("Wishlist Item"), 1752800402, amount(3));
+
+            advance_block::<ink::env::DefaultEnvironment>();
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(2));
+            let _ = wishlist.fund_wish(1);
+
+            advance_block::<ink::env::DefaultEnvironment>();
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1752800500);
+            let result = wishlist.claim_wish(1);
+            assert!(result.is_ok(), "Claiming wish should succeed");
+            assert_eq!(wishlist.next_item_id, 2_u32);
+        }
+
+        #[ink::test]
+        pub fn recurring_wish_spawns_next_occurrence() {
+            set_caller(default_accounts().alice);
+            let mut wishlist = Wishlist::default();
+            set_value_transferred(U256::from(3));
+            let _ = wishlist.add_wishlist_item_with_options(
+                String::from("Birthday"),
+                1752800402,
+                amount(3),
+                WishOptions {
+                    recurrence: Some(Interval::Yearly),
+                    ..Default::default()
+                },
+            );
+
+            // Nothing to spawn while the wish is still active
+            assert_eq!(
+                wishlist.spawn_next_occurrence(1).err(),
+                Some(Error::NoRecurrence)
+            );
+
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1752800500);
+            assert!(wishlist.claim_wish(1).is_ok());
+
+            // Only the owner can spawn the next occurrence, funding it like a
+            // new wish
+            set_caller(default_accounts().bob);
+            assert_eq!(
+                wishlist.spawn_next_occurrence(1).err(),
+                Some(Error::NotOwner)
+            );
+            set_caller(default_accounts().alice);
+            wishlist.pause().unwrap();
+            assert_eq!(
+                wishlist.spawn_next_occurrence(1).err(),
+                Some(Error::ContractPaused)
+            );
+            wishlist.unpause().unwrap();
+            set_value_transferred(U256::from(1));
+            let next_id = wishlist.spawn_next_occurrence(1).unwrap();
+            assert_eq!(next_id, 2);
+            let next = wishlist
+                .get_wishlist_item(next_id)
+                .unwrap()
+                .active()
+                .unwrap();
+            assert_eq!(next.owner, default_accounts().alice);
+            assert_eq!(next.end_date, 1752800402 + Interval::Yearly.duration());
+            assert_eq!(next.raised, amount(1));
+            assert_eq!(next.recurrence, Some(Interval::Yearly));
+
+            // The template is consumed
+            assert_eq!(
+                wishlist.spawn_next_occurrence(1).err(),
+                Some(Error::NoRecurrence)
+            );
+        }
+
+        #[ink::test]
+        pub fn stop_recurrence_only_by_owner() {
+            let mut wishlist = Wishlist::default();
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(3));
+            let _ = wishlist.add_wishlist_item_with_options(
+                String::from("Birthday"),
+                1752800402,
+                amount(3),
+                WishOptions {
+                    recurrence: Some(Interval::Weekly),
+                    ..Default::default()
+                },
+            );
+
+            set_caller(default_accounts().bob);
+            assert_eq!(wishlist.stop_recurrence(1).err(), Some(Error::NotOwner));
+
+            set_caller(default_accounts().alice);
+            assert!(wishlist.stop_recurrence(1).is_ok());
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1752800500);
+            assert!(wishlist.claim_wish(1).is_ok());
+            assert_eq!(
+                wishlist.spawn_next_occurrence(1).err(),
+                Some(Error::NoRecurrence)
+            );
+        }
+
+        #[ink::test]
+        pub fn standing_order_executes_until_cap() {
+            let mut wishlist = Wishlist::default();
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(100));
+            let _ =
+                wishlist.add_wishlist_item(String::from("Wishlist Item"), 1752800402, amount(1000));
+
+            set_caller(default_accounts().bob);
+            set_value_transferred(U256::from(25));
+            assert!(wishlist.deposit().is_ok());
+            set_value_transferred(U256::zero());
+            assert!(wishlist
+                .approve_standing_order(1, amount(10), 1000, amount(15))
+                .is_ok());
+
+            // First installment is due straight away, the next one a period later
+            set_caller(default_accounts().charlie);
+            assert!(wishlist
+                .execute_standing_order(default_accounts().bob, 1)
+                .is_ok());
+            assert_eq!(
+                wishlist
+                    .execute_standing_order(default_accounts().bob, 1)
+                    .err(),
+                Some(Error::InstallmentNotDue)
+            );
+
+            // The last installment is capped and removes the order
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert!(wishlist
+                .execute_standing_order(default_accounts().bob, 1)
+                .is_ok());
+            assert_eq!(
+                wishlist.get_contributors(1, 0, MAX_PAGE_SIZE),
+                vec![(default_accounts().bob, amount(15))]
+            );
+            assert_eq!(wishlist.get_deposit(default_accounts().bob), amount(10));
+            assert_eq!(wishlist.get_standing_order(default_accounts().bob, 1), None);
+        }
+
+        #[ink::test]
+        pub fn standing_order_stops_when_deposit_runs_out() {
+            let mut wishlist = Wishlist::default();
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(100));
+            let _ =
+                wishlist.add_wishlist_item(String::from("Wishlist Item"), 1752800402, amount(1000));
+
+            set_caller(default_accounts().bob);
+            set_value_transferred(U256::from(5));
+            assert!(wishlist.deposit().is_ok());
+            set_value_transferred(U256::zero());
+            assert!(wishlist
+                .approve_standing_order(1, amount(10), 1000, amount(100))
+                .is_ok());
+
+            assert!(wishlist
+                .execute_standing_order(default_accounts().bob, 1)
+                .is_ok());
+            assert_eq!(wishlist.get_standing_order(default_accounts().bob, 1), None);
+            assert!(
+                wishlist
+                    .get_wishlist_item(1)
+                    .unwrap()
+                    .active()
+                    .unwrap()
+                    .contributor_count
+                    == 0
+            );
+            assert_eq!(wishlist.get_deposit(default_accounts().bob), amount(5));
+        }
+
+        #[ink::test]
+        pub fn get_wishes_sorted_orders_by_key() {
+            let mut wishlist = Wishlist::default();
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(500));
+            let _ = wishlist.add_wishlist_item(String::from("Half"), 3000, amount(1000));
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Tenth"), 1000, amount(1000));
+            set_value_transferred(U256::from(900));
+            let _ = wishlist.add_wishlist_item(String::from("Most"), 2000, amount(1000));
+
+            set_caller(default_accounts().bob);
+            set_value_transferred(U256::from(7));
+            assert!(wishlist.boost_wish(2).is_ok());
+
+            let ids = |items: Vec<WishListItem>| items.iter().map(|i| i.id).collect::<Vec<_>>();
+            assert_eq!(
+                ids(wishlist.get_wishes_sorted(SortKey::Newest, 0, 10)),
+                vec![3, 2, 1]
+            );
+            assert_eq!(
+                ids(wishlist.get_wishes_sorted(SortKey::EndingSoonest, 0, 10)),
+                vec![2, 3, 1]
+            );
+            assert_eq!(
+                ids(wishlist.get_wishes_sorted(SortKey::PercentFunded, 0, 10)),
+                vec![3, 1, 2]
+            );
+            assert_eq!(
+                ids(wishlist.get_wishes_sorted(SortKey::BoostScore, 0, 1)),
+                vec![2]
+            );
+            assert_eq!(
+                ids(wishlist.get_wishes_sorted(SortKey::Newest, 1, 1)),
+                vec![2]
+            );
+
+            // Passed deadlines drop out of the ending-soon listing
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1500);
+            assert_eq!(
+                ids(wishlist.get_wishes_sorted(SortKey::EndingSoonest, 0, 10)),
+                vec![3, 1]
+            );
+
+            // Settled wishes drop out of every index
+            set_caller(default_accounts().alice);
+            set_block_timestamp::<ink::env::DefaultEnvironment>(2500);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.fund_wish(3);
+            assert!(wishlist.claim_wish(3).is_ok());
+            assert_eq!(
+                ids(wishlist.get_wishes_sorted(SortKey::PercentFunded, 0, 10)),
+                vec![1, 2]
+            );
+        }
+
+        #[ink::test]
+        pub fn listing_indexes_span_pages() {
+            set_caller(default_accounts().alice);
+            let mut wishlist = Wishlist::default();
+            assert_eq!(wishlist.set_max_active_wishes(200), Ok(()));
+            let count = 3 * INDEX_PAGE_SIZE as u32;
+            let end_date = |id: u32| 1000 + u64::from(id * 37 % count) * 10;
+            for id in 1..=count {
+                set_value_transferred(U256::from(100));
+                assert_eq!(
+                    wishlist.add_wishlist_item(
+                        ink::prelude::format!("Wish {id}"),
+                        end_date(id),
+                        amount(1000)
+                    ),
+                    Ok(())
+                );
+            }
+            set_value_transferred(U256::zero());
+            // Settle every third wish, so entries leave every page
+            for id in (1..=count).step_by(3) {
+                assert_eq!(wishlist.cancel_wish(id), Ok(()));
+            }
+            let active: Vec<u32> = (1..=count).filter(|id| id % 3 != 1).collect();
+
+            let stats = wishlist.storage_stats();
+            assert_eq!(stats.active_wishes, active.len() as u32);
+            assert_eq!(stats.deadline_index_len, active.len() as u32);
+            assert_eq!(
+                wishlist.get_ids(1, count + 1),
+                active[..MAX_PAGE_SIZE as usize].to_vec()
+            );
+            assert_eq!(
+                wishlist.get_ids(101, 111),
+                vec![101, 102, 104, 105, 107, 108, 110]
+            );
+
+            let ids = |items: Vec<WishListItem>| items.iter().map(|i| i.id).collect::<Vec<_>>();
+            let newest: Vec<u32> = active.iter().rev().take(50).copied().collect();
+            assert_eq!(
+                ids(wishlist.get_wishes_sorted(SortKey::Newest, 0, 50)),
+                newest
+            );
+            let mut soonest = active.clone();
+            soonest.sort_by_key(|id| end_date(*id));
+            assert_eq!(
+                ids(wishlist.get_wishes_sorted(SortKey::EndingSoonest, 40, 60)),
+                soonest[40..100].to_vec()
+            );
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(
+                ids(wishlist.get_expiring_soon(95, 0, 100)),
+                soonest
+                    .iter()
+                    .copied()
+                    .filter(|id| end_date(*id) <= 1095)
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        #[ink::test]
+        pub fn raise_history_buckets_by_day() {
+            let mut wishlist = Wishlist::default();
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(100));
+            let _ =
+                wishlist.add_wishlist_item(String::from("Wishlist Item"), 10 * DAY, amount(1000));
+
+            set_caller(default_accounts().bob);
+            set_value_transferred(U256::from(20));
+            let _ = wishlist.fund_wish(1);
+
+            set_block_timestamp::<ink::env::DefaultEnvironment>(2 * DAY + 5);
+            let _ = wishlist.fund_wish(1);
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(30));
+            let _ = wishlist.fund_wish(1);
+
+            assert_eq!(
+                wishlist.raise_history(1),
+                vec![
+                    RaiseBucket {
+                        day: 0,
+                        raised: amount(120)
+                    },
+                    RaiseBucket {
+                        day: 2,
+                        raised: amount(50)
+                    },
+                ]
+            );
+            assert!(wishlist.raise_history(2).is_empty());
+        }
+
+        #[ink::test]
+        pub fn contribution_log_is_paginated() {
+            let mut wishlist = Wishlist::default();
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(100));
+            let _ =
+                wishlist.add_wishlist_item(String::from("Wishlist Item"), 1752800402, amount(1000));
+
+            set_caller(default_accounts().bob);
+            set_block_timestamp::<ink::env::DefaultEnvironment>(10);
+            set_value_transferred(U256::from(5));
+            let _ = wishlist.fund_wish(1);
+            set_block_timestamp::<ink::env::DefaultEnvironment>(20);
+            set_value_transferred(U256::from(6));
+            let _ = wishlist.fund_wish(1);
+
+            assert_eq!(wishlist.get_contribution_count(1), 3);
+            assert_eq!(
+                wishlist.get_contributions(1, 1, 5),
+                vec![
+                    ContributionRecord {
+                        ordinal: 1,
+                        contributor: default_accounts().bob,
+                        amount: amount(5),
+                        timestamp: 10,
+                    },
+                    ContributionRecord {
+                        ordinal: 2,
+                        contributor: default_accounts().bob,
+                        amount: amount(6),
+                        timestamp: 20,
+                    },
+                ]
+            );
+            assert_eq!(wishlist.get_contributions(1, 0, 1)[0].amount, amount(100));
+            assert!(wishlist.get_contributions(1, 3, 5).is_empty());
+            assert_eq!(
+                wishlist.get_contributor_activity(1, default_accounts().bob),
+                Some(ContributorActivity {
+                    first_ordinal: 1,
+                    first_at: 10,
+                    last_ordinal: 2,
+                    last_at: 20,
+                })
+            );
+            assert_eq!(
+                wishlist.get_contributor_activity(1, default_accounts().charlie),
+                None
+            );
+        }
+
+        #[ink::test]
+        pub fn exit_contribution_keeps_penalty_in_pot() {
+            let mut wishlist = Wishlist::default();
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item_with_options(
+                String::from("Wishlist Item"),
+                1000,
+                amount(100),
+                WishOptions {
+                    exit_penalty_bps: 1_000,
+                    ..Default::default()
+                },
+            );
+
+            set_caller(default_accounts().bob);
+            assert_eq!(
+                wishlist.exit_contribution(1).err(),
+                Some(Error::NotContributor)
+            );
+            set_value_transferred(U256::from(50));
+            let _ = wishlist.fund_wish(1);
+            assert!(wishlist.exit_contribution(1).is_ok());
+
+            let item = wishlist.get_wishlist_item(1).unwrap().active().unwrap();
+            assert_eq!(item.contributor_count, 0);
+            assert_eq!(item.forfeited, amount(5));
+            assert_eq!(Wishlist::pot(&item), amount(105));
+
+            // No exits once the wish has ended
+            let _ = wishlist.fund_wish(1);
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(wishlist.exit_contribution(1).err(), Some(Error::WishEnded));
+        }
+
+        #[ink::test]
+        pub fn withdraw_progress_is_bounded_by_funding() {
+            let mut wishlist = Wishlist::default();
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(500));
+            let _ = wishlist.add_wishlist_item_with_options(
+                String::from("Wishlist Item"),
+                1000,
+                amount(1000),
+                WishOptions {
+                    allow_partial_withdrawal: true,
+                    ..Default::default()
+                },
+            );
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Plain"), 1000, amount(1000));
+
+            set_value_transferred(U256::zero());
+            assert_eq!(
+                wishlist.withdraw_progress(2, amount(1)).err(),
+                Some(Error::PartialWithdrawalDisabled)
+            );
+
+            // 50% funded: up to 25% of the 500 pot
+            assert!(wishlist.withdraw_progress(1, amount(100)).is_ok());
+            assert_eq!(
+                wishlist.withdraw_progress(1, amount(26)).err(),
+                Some(Error::WithdrawalLimitExceeded)
+            );
+            assert!(wishlist.withdraw_progress(1, amount(25)).is_ok());
+
+            let item = wishlist.get_wishlist_item(1).unwrap().active().unwrap();
+            assert_eq!(item.withdrawn, amount(125));
+            assert_eq!(Wishlist::pot(&item), amount(375));
+
+            set_caller(default_accounts().bob);
+            assert_eq!(
+                wishlist.withdraw_progress(1, amount(1)).err(),
+                Some(Error::NotOwner)
+            );
+        }
+
+        #[ink::test]
+        pub fn withdraw_progress_leaves_contributions_untouched() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(1000));
+            let _ = wishlist.add_wishlist_item_with_options(
+                String::from("Wishlist Item"),
+                1000,
+                amount(1000),
+                WishOptions {
+                    allow_partial_withdrawal: true,
+                    ..Default::default()
+                },
+            );
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(9000));
+            assert_eq!(wishlist.fund_wish(1), Ok(()));
+            set_value_transferred(U256::zero());
+            set_contract_balance(ink::env::address(), U256::from(10_000));
+
+            // Bob's 9000 does not raise the allowance past half the seed
+            set_caller(accounts.alice);
+            assert_eq!(
+                wishlist.withdraw_progress(1, amount(5000)),
+                Err(Error::WithdrawalLimitExceeded)
+            );
+            assert_eq!(wishlist.withdraw_progress(1, amount(500)), Ok(()));
+            set_caller(accounts.bob);
+            assert_eq!(wishlist.withdraw_contribution(1, amount(9000)), Ok(()));
+
+            let item = wishlist.get_wishlist_item(1).unwrap().active().unwrap();
+            assert_eq!(Wishlist::pot(&item), amount(500));
+            let report = wishlist.audit();
+            assert_eq!(report.shortfall, math::ZERO);
+            set_caller(accounts.alice);
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(wishlist.claim_wish(1), Ok(()));
+            assert_eq!(wishlist.audit().shortfall, math::ZERO);
+        }
+
+        #[ink::test]
+        pub fn keep_what_you_raise_claims_below_target() {
+            let mut wishlist = Wishlist::default();
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item_with_options(
+                String::from("Wishlist Item"),
+                1000,
+                amount(1000),
+                WishOptions {
+                    funding_model: FundingModel::KeepWhatYouRaise,
+                    ..Default::default()
+                },
+            );
+
+            set_caller(default_accounts().bob);
+            set_value_transferred(U256::from(50));
+            let _ = wishlist.fund_wish(1);
+            assert_eq!(
+                wishlist.split_raised_wish(1).err(),
+                Some(Error::NotRefundable)
+            );
+
+            set_caller(default_accounts().alice);
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert!(wishlist.claim_wish(1).is_ok());
+            assert!(matches!(
+                wishlist.get_wishlist_item(1),
+                Ok(WishEntry::Settled(_))
+            ));
+        }
+
+        #[ink::test]
+        pub fn early_claim_needs_majority_approval() {
+            let mut wishlist = Wishlist::default();
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Gift"), 10 * DAY, amount(200));
+
+            set_caller(default_accounts().bob);
+            set_value_transferred(U256::from(60));
+            let _ = wishlist.fund_wish(1);
+            set_caller(default_accounts().charlie);
+            set_value_transferred(U256::from(40));
+            let _ = wishlist.fund_wish(1);
+
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::zero());
+            assert_eq!(
+                wishlist.request_early_claim(1).err(),
+                Some(Error::TargetNotReached)
+            );
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.fund_wish(1);
+            assert!(wishlist.request_early_claim(1).is_ok());
+
+            set_caller(default_accounts().charlie);
+            assert!(wishlist.vote_early_claim(1, true).is_ok());
+            assert_eq!(
+                wishlist.vote_early_claim(1, true).err(),
+                Some(Error::AlreadyVoted)
+            );
+            set_caller(default_accounts().alice);
+            // 40% approval is not enough
+            assert_eq!(wishlist.claim_wish(1), Err(Error::EndDateNotReached));
+            // and asking again does not wipe the votes cast so far.
+            assert_eq!(
+                wishlist.request_early_claim(1),
+                Err(Error::EarlyClaimPending)
+            );
+
+            set_caller(default_accounts().bob);
+            assert!(wishlist.vote_early_claim(1, true).is_ok());
+            set_caller(default_accounts().alice);
+            assert!(wishlist.claim_wish(1).is_ok());
+        }
+
+        #[ink::test]
+        pub fn early_claim_passes_after_objection_window() {
+            let mut wishlist = Wishlist::default();
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(200));
+            let _ = wishlist.add_wishlist_item(String::from("Gift"), 10 * DAY, amount(200));
+            set_caller(default_accounts().bob);
+            set_value_transferred(U256::from(60));
+            let _ = wishlist.fund_wish(1);
+
+            set_caller(default_accounts().alice);
+            assert!(wishlist.request_early_claim(1).is_ok());
+            set_block_timestamp::<ink::env::DefaultEnvironment>(EARLY_CLAIM_WINDOW);
+            assert!(wishlist.claim_wish(1).is_ok());
+        }
+
+        #[ink::test]
+        pub fn wish_summary_includes_asset_metadata() {
+            let mut wishlist = Wishlist::default();
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(1000));
+            set_caller(default_accounts().bob);
+            set_value_transferred(U256::from(25));
+            let _ = wishlist.fund_wish(1);
+
+            let summary = wishlist.get_wish_summary(1).unwrap();
+            assert_eq!(summary.owner, default_accounts().alice);
+            assert_eq!(summary.pot, amount(125));
+            assert_eq!(summary.symbol, NATIVE_SYMBOL);
+            assert_eq!(summary.decimals, NATIVE_DECIMALS);
+            assert_eq!(
+                wishlist.get_wish_summary(2).err(),
+                Some(Error::WishNotFound)
+            );
+        }
+
+        #[ink::test]
+        pub fn get_expiring_soon_uses_window() {
+            let mut wishlist = Wishlist::default();
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Later"), 5 * DAY, amount(1000));
+            let _ = wishlist.add_wishlist_item(String::from("Soon"), DAY, amount(1000));
+            let _ = wishlist.add_wishlist_item(String::from("Past"), 10, amount(1000));
+            let _ = wishlist.add_wishlist_item(String::from("Next"), 2 * DAY, amount(1000));
+
+            set_block_timestamp::<ink::env::DefaultEnvironment>(100);
+            let ids = |items: Vec<WishListItem>| items.iter().map(|i| i.id).collect::<Vec<_>>();
+            assert_eq!(ids(wishlist.get_expiring_soon(2 * DAY, 0, 10)), vec![2, 4]);
+            assert_eq!(ids(wishlist.get_expiring_soon(2 * DAY, 1, 10)), vec![4]);
+            assert_eq!(
+                ids(wishlist.get_expiring_soon(10 * DAY, 0, 10)),
+                vec![2, 4, 1]
+            );
+        }
+
+        #[ink::test]
+        pub fn settled_wishes_are_archived() {
+            let mut wishlist = Wishlist::default();
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(100));
+            set_caller(default_accounts().bob);
+            set_value_transferred(U256::from(30));
+            let _ = wishlist.fund_wish(1);
+            assert_eq!(wishlist.get_settlement(1), None);
+
+            set_caller(default_accounts().alice);
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1200);
+            assert!(wishlist.claim_wish(1).is_ok());
+            assert_eq!(
+                wishlist.get_settlement(1),
+                Some(SettlementRecord {
+                    id: 1,
+                    owner: default_accounts().alice,
+                    target: amount(100),
+                    raised: amount(100),
+                    contributed: amount(30),
+                    pot: amount(130),
+                    outcome: SettlementOutcome::Claimed,
+                    actor: default_accounts().alice,
+                    settled_at: 1200,
+                    receipt: None,
+                })
+            );
+        }
+
+        #[ink::test]
+        pub fn settlement_history_covers_owners_and_contributors() {
+            let mut wishlist = Wishlist::default();
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("First"), 1000, amount(100));
+            let _ = wishlist.add_wishlist_item(String::from("Second"), 1000, amount(100));
+            set_caller(default_accounts().bob);
+            set_value_transferred(U256::from(30));
+            let _ = wishlist.fund_wish(2);
+
+            set_caller(default_accounts().alice);
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1200);
+            assert!(wishlist.claim_wish(1).is_ok());
+            assert!(wishlist.claim_wish(2).is_ok());
+
+            let ids =
+                |records: Vec<SettlementRecord>| records.iter().map(|r| r.id).collect::<Vec<_>>();
+            assert_eq!(
+                ids(wishlist.settlement_history(default_accounts().alice, 0, 10)),
+                vec![1, 2]
+            );
+            assert_eq!(
+                ids(wishlist.settlement_history(default_accounts().alice, 1, 10)),
+                vec![2]
+            );
+            assert_eq!(
+                ids(wishlist.settlement_history(default_accounts().bob, 0, 10)),
+                vec![2]
+            );
+            assert!(wishlist
+                .settlement_history(default_accounts().charlie, 0, 10)
+                .is_empty());
+        }
+
+        #[ink::test]
+        pub fn events_index_wish_and_accounts() {
+            let mut wishlist = Wishlist::default();
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(100));
+            set_caller(default_accounts().bob);
+            set_value_transferred(U256::from(30));
+            let _ = wishlist.fund_wish(1);
+            set_value_transferred(U256::zero());
+            let _ = wishlist.exit_contribution(1);
+
+            let events = recorded_events();
+            // signature, id and owner
+            assert_eq!(events[0].topics.len(), 3);
+            // signature, id and contributor
+            assert_eq!(events[1].topics.len(), 3);
+        }
+
+        #[ink::test]
+        pub fn wishlist_added_carries_wish_details() {
+            let mut wishlist = Wishlist::default();
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(100));
+
+            let events = recorded_events();
+            let event = <WishlistAdded as ink::scale::Decode>::decode(&mut &events[0].data[..])
+                .expect("WishlistAdded decodes");
+            // The event carries the id every message takes.
+            assert_eq!(event.id, 1);
+            assert_eq!(
+                wishlist
+                    .get_wishlist_item(event.id)
+                    .unwrap()
+                    .active()
+                    .unwrap()
+                    .id,
+                1
+            );
+            assert_eq!(event.target, amount(100));
+            assert_eq!(event.end_date, 1000);
+            assert_eq!(event.funding_model, FundingModel::AllOrNothing);
+            let mut expected = [0u8; 32];
+            ink::env::hash_bytes::<Keccak256>(b"Bike", &mut expected);
+            assert_eq!(event.description_hash, expected);
+        }
+
+        #[ink::test]
+        pub fn get_wishlist_item_distinguishes_settled_from_missing() {
+            let mut wishlist = Wishlist::default();
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(100));
+            assert!(matches!(
+                wishlist.get_wishlist_item(1),
+                Ok(WishEntry::Active(_))
+            ));
+
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert!(wishlist.claim_wish(1).is_ok());
+            match wishlist.get_wishlist_item(1) {
+                Ok(WishEntry::Settled(record)) => {
+                    assert_eq!(record.outcome, SettlementOutcome::Claimed)
+                }
+                other => panic!("expected a settled wish, got {:?}", other),
+            }
+            assert_eq!(
+                wishlist.get_wishlist_item(2).err(),
+                Some(Error::WishNotFound)
+            );
+        }
+
+        #[ink::test]
+        pub fn split_wish_leaves_tombstone_with_actor() {
+            let mut wishlist = Wishlist::default();
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(1000));
+            set_caller(default_accounts().bob);
+            set_value_transferred(U256::from(30));
+            let _ = wishlist.fund_wish(1);
+
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1500);
+            assert!(wishlist.split_raised_wish(1).is_ok());
+            let record = wishlist.get_settlement(1).unwrap();
+            assert_eq!(record.outcome, SettlementOutcome::Split);
+            assert_eq!(record.actor, default_accounts().bob);
+            assert_eq!(record.settled_at, 1500);
+        }
+
+        #[ink::test]
+        pub fn force_cancel_queues_refunds() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            assert!(wishlist.set_moderator(accounts.eve, true).is_ok());
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                wishlist.set_moderator(accounts.bob, true).err(),
+                Some(Error::OnlyAdmin)
+            );
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Scam"), 1000, amount(1000));
+            set_caller(accounts.charlie);
+            set_value_transferred(U256::from(40));
+            let _ = wishlist.fund_wish(1);
+
+            set_value_transferred(U256::zero());
+            assert_eq!(
+                wishlist.force_cancel(1, String::from("spam")).err(),
+                Some(Error::NotModerator)
+            );
+            set_caller(accounts.eve);
+            assert!(wishlist.force_cancel(1, String::from("spam")).is_ok());
+
+            assert_eq!(wishlist.get_payout(1, accounts.charlie), amount(40));
+            assert_eq!(wishlist.get_credits(accounts.bob), amount(100));
+            let record = wishlist.get_settlement(1).unwrap();
+            assert_eq!(record.outcome, SettlementOutcome::Moderated);
+            assert_eq!(record.actor, accounts.eve);
+
+            set_caller(accounts.charlie);
+            assert!(wishlist.withdraw_payout(1).is_ok());
+            assert_eq!(wishlist.get_payout(1, accounts.charlie), math::ZERO);
+            assert_eq!(
+                wishlist.withdraw_payout(1).err(),
+                Some(Error::NothingToWithdraw)
+            );
+            set_caller(accounts.bob);
+            assert!(wishlist.withdraw_credits().is_ok());
+            assert_eq!(
+                wishlist.withdraw_credits().err(),
+                Some(Error::NothingToWithdraw)
+            );
+        }
+
+        #[ink::test]
+        pub fn force_cancel_can_forfeit_deposit() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            assert!(wishlist.set_moderator(accounts.eve, true).is_ok());
+            assert!(wishlist.set_deposit_policy(DepositPolicy::Forfeit).is_ok());
+
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Scam"), 1000, amount(1000));
+
+            set_caller(accounts.eve);
+            set_value_transferred(U256::zero());
+            assert!(wishlist.force_cancel(1, String::from("fraud")).is_ok());
+            assert_eq!(wishlist.get_credits(accounts.bob), math::ZERO);
+            assert_eq!(wishlist.get_treasury(), amount(100));
+
+            set_caller(accounts.alice);
+            assert_eq!(
+                wishlist
+                    .withdraw_treasury(accounts.alice, amount(101))
+                    .err(),
+                Some(Error::InsufficientTreasury)
+            );
+            assert!(wishlist
+                .withdraw_treasury(accounts.alice, amount(100))
+                .is_ok());
+        }
+
+        #[ink::test]
+        pub fn reversed_appeal_restores_forfeited_deposit() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            assert!(wishlist.set_moderator(accounts.eve, true).is_ok());
+            assert!(wishlist.set_deposit_policy(DepositPolicy::Forfeit).is_ok());
+            assert!(wishlist.set_appeal_bond(amount(10)).is_ok());
+
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Legit"), 1000, amount(1000));
+            set_value_transferred(U256::from(10));
+            assert_eq!(
+                wishlist.appeal_moderation(1).err(),
+                Some(Error::NotModerated)
+            );
+
+            set_caller(accounts.eve);
+            set_value_transferred(U256::zero());
+            let _ = wishlist.force_cancel(1, String::from("mistake"));
+
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(9));
+            assert_eq!(
+                wishlist.appeal_moderation(1).err(),
+                Some(Error::InsufficientBond)
+            );
+            set_value_transferred(U256::from(10));
+            assert!(wishlist.appeal_moderation(1).is_ok());
+            assert_eq!(
+                wishlist.appeal_moderation(1).err(),
+                Some(Error::AppealExists)
+            );
+            assert_eq!(
+                wishlist.resolve_appeal(1, false).err(),
+                Some(Error::NotArbiter)
+            );
+
+            set_caller(accounts.alice);
+            set_value_transferred(U256::zero());
+            assert!(wishlist.resolve_appeal(1, false).is_ok());
+            assert_eq!(wishlist.get_credits(accounts.bob), amount(110));
+            assert_eq!(wishlist.get_treasury(), math::ZERO);
+            assert_eq!(
+                wishlist.get_settlement(1).unwrap().outcome,
+                SettlementOutcome::ModerationReversed
+            );
+            assert_eq!(
+                wishlist.resolve_appeal(1, true).err(),
+                Some(Error::NoPendingAppeal)
+            );
+        }
+
+        #[ink::test]
+        pub fn appeal_window_closes() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            assert!(wishlist.set_moderator(accounts.eve, true).is_ok());
+            assert!(wishlist.set_arbiter(Some(accounts.frank)).is_ok());
+
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(1000));
+            let _ = wishlist.add_wishlist_item(String::from("Car"), 1000, amount(1000));
+            set_caller(accounts.eve);
+            set_value_transferred(U256::zero());
+            let _ = wishlist.force_cancel(1, String::from("spam"));
+            let _ = wishlist.force_cancel(2, String::from("spam"));
+
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(5));
+            assert!(wishlist.appeal_moderation(1).is_ok());
+            set_block_timestamp::<ink::env::DefaultEnvironment>(APPEAL_WINDOW + 1);
+            assert_eq!(
+                wishlist.appeal_moderation(2).err(),
+                Some(Error::AppealWindowClosed)
+            );
+
+            set_caller(accounts.frank);
+            set_value_transferred(U256::zero());
+            assert!(wishlist.resolve_appeal(1, true).is_ok());
+            assert_eq!(wishlist.get_treasury(), amount(5));
+            assert_eq!(wishlist.get_appeal(1).unwrap().status, AppealStatus::Upheld);
+        }
+
+        #[ink::test]
+        pub fn moderators_verify_wishes() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            assert!(wishlist.set_moderator(accounts.eve, true).is_ok());
+
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(1000));
+            assert!(!wishlist.get_wish_summary(1).unwrap().verified);
+            assert_eq!(
+                wishlist.set_verified(1, true).err(),
+                Some(Error::NotModerator)
+            );
+
+            set_caller(accounts.eve);
+            set_value_transferred(U256::zero());
+            assert!(wishlist.set_verified(1, true).is_ok());
+            assert!(wishlist.get_wish_summary(1).unwrap().verified);
+        }
+
+        #[ink::test]
+        pub fn active_wishes_are_capped_per_owner() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            assert!(wishlist.set_max_active_wishes(2).is_ok());
+
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(1000));
+            let _ = wishlist.add_wishlist_item(String::from("Car"), 1000, amount(1000));
+            assert_eq!(
+                wishlist
+                    .add_wishlist_item(String::from("Boat"), 1000, amount(1000))
+                    .err(),
+                Some(Error::TooManyActiveWishes)
+            );
+            assert_eq!(wishlist.get_active_count(accounts.bob), 2);
+
+            set_value_transferred(U256::from(900));
+            let _ = wishlist.fund_wish(1);
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1001);
+            set_value_transferred(U256::zero());
+            let _ = wishlist.claim_wish(1);
+            assert_eq!(wishlist.get_active_count(accounts.bob), 1);
+
+            set_value_transferred(U256::from(100));
+            assert!(wishlist
+                .add_wishlist_item(String::from("Boat"), 5000, amount(1000))
+                .is_ok());
+        }
+
+        #[ink::test]
+        pub fn withdraw_contribution_reduces_balance() {
+            let mut wishlist = Wishlist::default();
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item_with_options(
+                String::from("Wishlist Item"),
+                1000,
+                amount(1000),
+                WishOptions {
+                    exit_penalty_bps: 1_000,
+                    ..Default::default()
+                },
+            );
+
+            set_caller(default_accounts().bob);
+            set_value_transferred(U256::from(200));
+            let _ = wishlist.fund_wish(1);
+            set_value_transferred(U256::zero());
+            assert_eq!(
+                wishlist.withdraw_contribution(1, amount(201)).err(),
+                Some(Error::InvalidContribution)
+            );
+            assert!(wishlist.withdraw_contribution(1, amount(50)).is_ok());
+
+            let item = wishlist.get_wishlist_item(1).unwrap().active().unwrap();
+            assert_eq!(
+                wishlist.get_contributors(1, 0, MAX_PAGE_SIZE),
+                vec![(default_accounts().bob, amount(150))]
+            );
+            assert_eq!(item.forfeited, amount(5));
+            assert_eq!(Wishlist::pot(&item), amount(255));
+
+            assert!(wishlist.withdraw_contribution(1, amount(150)).is_ok());
+            let item = wishlist.get_wishlist_item(1).unwrap().active().unwrap();
+            assert_eq!(item.contributor_count, 0);
+            assert_eq!(item.forfeited, amount(20));
+        }
+
+        #[ink::test]
+        pub fn contributions_lock_in_final_stretch() {
+            let mut wishlist = Wishlist::default();
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item_with_options(
+                String::from("Wishlist Item"),
+                3 * DAY,
+                amount(1000),
+                WishOptions {
+                    withdrawal_lock: 2 * DAY,
+                    ..Default::default()
+                },
+            );
+
+            set_caller(default_accounts().bob);
+            set_value_transferred(U256::from(200));
+            let _ = wishlist.fund_wish(1);
+            set_value_transferred(U256::zero());
+            assert!(wishlist.withdraw_contribution(1, amount(50)).is_ok());
+
+            set_block_timestamp::<ink::env::DefaultEnvironment>(DAY);
+            assert_eq!(
+                wishlist.withdraw_contribution(1, amount(50)).err(),
+                Some(Error::ContributionsLocked)
+            );
+            assert_eq!(
+                wishlist.exit_contribution(1).err(),
+                Some(Error::ContributionsLocked)
+            );
+        }
+
+        #[ink::test]
+        pub fn only_admin_registers_verifiers() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            assert_eq!(wishlist.get_verifier(AttestationPurpose::Personhood), None);
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                wishlist
+                    .set_verifier(AttestationPurpose::Personhood, Some(accounts.bob))
+                    .err(),
+                Some(Error::OnlyAdmin)
+            );
+
+            set_caller(accounts.alice);
+            assert!(wishlist
+                .set_verifier(AttestationPurpose::Kyc, Some(accounts.django))
+                .is_ok());
+            assert_eq!(
+                wishlist.get_verifier(AttestationPurpose::Kyc),
+                Some(accounts.django)
+            );
+            assert_eq!(wishlist.get_verifier(AttestationPurpose::Personhood), None);
+            assert!(wishlist.set_verifier(AttestationPurpose::Kyc, None).is_ok());
+            assert_eq!(wishlist.get_verifier(AttestationPurpose::Kyc), None);
+        }
+
+        #[ink::test]
+        pub fn only_owner_registers_observer() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(1000));
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                wishlist.set_observer(1, Some(accounts.bob)).err(),
+                Some(Error::NotOwner)
+            );
+
+            set_caller(accounts.alice);
+            assert!(wishlist.set_observer(1, Some(accounts.django)).is_ok());
+            assert_eq!(wishlist.get_observer(1), Some(accounts.django));
+            assert!(wishlist.set_observer(1, None).is_ok());
+            assert_eq!(wishlist.get_observer(1), None);
+        }
+
+        #[ink::test]
+        pub fn wish_types_need_a_registered_extension() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+
+            set_value_transferred(U256::from(100));
+            assert_eq!(
+                wishlist
+                    .add_wishlist_item_with_options(
+                        String::from("Custom"),
+                        1000,
+                        amount(1000),
+                        WishOptions {
+                            wish_type: Some(7),
+                            ..Default::default()
+                        },
+                    )
+                    .err(),
+                Some(Error::UnknownWishType)
+            );
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                wishlist.register_extension(7, Some(accounts.django)).err(),
+                Some(Error::OnlyAdmin)
+            );
+            set_caller(accounts.alice);
+            assert!(wishlist
+                .register_extension(7, Some(accounts.django))
+                .is_ok());
+            assert_eq!(wishlist.get_extension(7), Some(accounts.django));
+        }
+
+        #[ink::test]
+        pub fn claim_splits_payout_by_weight() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(1001));
+            let _ = wishlist.add_wishlist_item(String::from("Gift"), 1000, amount(1000));
+
+            assert_eq!(
+                wishlist
+                    .set_payout_split(
+                        1,
+                        vec![PayoutShare {
+                            recipient: accounts.charlie,
+                            bps: 7_000,
+                        }],
+                    )
+                    .err(),
+                Some(Error::InvalidPayoutSplit)
+            );
+            assert!(wishlist
+                .set_payout_split(
+                    1,
+                    vec![
+                        PayoutShare {
+                            recipient: accounts.charlie,
+                            bps: 7_000,
+                        },
+                        PayoutShare {
+                            recipient: accounts.django,
+                            bps: 3_000,
+                        },
+                    ],
+                )
+                .is_ok());
+
+            let charlie = get_contract_balance::<ink::env::DefaultEnvironment>(accounts.charlie)
+                .unwrap_or_default();
+            let django = get_contract_balance::<ink::env::DefaultEnvironment>(accounts.django)
+                .unwrap_or_default();
+            set_value_transferred(U256::zero());
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert!(wishlist.claim_wish(1).is_ok());
+
+            assert_eq!(
+                get_contract_balance::<ink::env::DefaultEnvironment>(accounts.charlie).unwrap(),
+                charlie + U256::from(701)
+            );
+            assert_eq!(
+                get_contract_balance::<ink::env::DefaultEnvironment>(accounts.django).unwrap(),
+                django + U256::from(300)
+            );
+            let events = recorded_events();
+            let last =
+                <PayoutSent as ink::scale::Decode>::decode(&mut &events[events.len() - 1].data[..])
+                    .expect("PayoutSent decodes");
+            assert_eq!(last.recipient, accounts.django);
+            assert_eq!(last.amount, amount(300));
+        }
+
+        #[ink::test]
+        pub fn charity_wishes_pay_verified_charity() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            let charity = WishOptions {
+                charity: Some(accounts.frank),
+                ..Default::default()
+            };
+
+            set_value_transferred(U256::from(1000));
+            assert_eq!(
+                wishlist
+                    .add_wishlist_item_with_options(
+                        String::from("Shelter"),
+                        1000,
+                        amount(1000),
+                        charity.clone(),
+                    )
+                    .err(),
+                Some(Error::UnverifiedCharity)
+            );
+            assert!(wishlist.set_verified_charity(accounts.frank, true).is_ok());
+            assert!(wishlist
+                .add_wishlist_item_with_options(
+                    String::from("Shelter"),
+                    1000,
+                    amount(1000),
+                    charity,
+                )
+                .is_ok());
+            assert_eq!(
+                wishlist.get_wish_summary(1).unwrap().charity,
+                Some(accounts.frank)
+            );
+            assert_eq!(
+                wishlist.set_payout_split(1, Vec::new()).err(),
+                Some(Error::CharityPayoutFixed)
+            );
+
+            let before = get_contract_balance::<ink::env::DefaultEnvironment>(accounts.frank)
+                .unwrap_or_default();
+            set_value_transferred(U256::zero());
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert!(wishlist.claim_wish(1).is_ok());
+            assert_eq!(
+                get_contract_balance::<ink::env::DefaultEnvironment>(accounts.frank).unwrap(),
+                before + U256::from(1000)
+            );
+        }
+
+        #[ink::test]
+        pub fn escrow_releases_on_confirmation_quorum() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(1000));
+            let _ = wishlist.add_wishlist_item_with_options(
+                String::from("Gift"),
+                1000,
+                amount(1000),
+                WishOptions {
+                    escrow_bps: 5_000,
+                    ..Default::default()
+                },
+            );
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(600));
+            let _ = wishlist.fund_wish(1);
+            set_caller(accounts.charlie);
+            set_value_transferred(U256::from(400));
+            let _ = wishlist.fund_wish(1);
+
+            set_caller(accounts.alice);
+            set_value_transferred(U256::zero());
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert!(wishlist.claim_wish(1).is_ok());
+            assert_eq!(wishlist.get_escrow(1).unwrap().amount, amount(1000));
+            assert_eq!(
+                wishlist.confirm_fulfillment(1).err(),
+                Some(Error::NotContributor)
+            );
+
+            set_caller(accounts.charlie);
+            assert!(wishlist.confirm_fulfillment(1).is_ok());
+            assert_eq!(
+                wishlist.confirm_fulfillment(1).err(),
+                Some(Error::AlreadyConfirmed)
+            );
+            assert!(wishlist.get_escrow(1).is_some());
+
+            set_caller(accounts.bob);
+            assert!(wishlist.confirm_fulfillment(1).is_ok());
+            assert_eq!(wishlist.get_escrow(1), None);
+        }
+
+        #[ink::test]
+        pub fn unconfirmed_escrow_refunds_contributors() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(1000));
+            let _ = wishlist.add_wishlist_item_with_options(
+                String::from("Gift"),
+                1000,
+                amount(1000),
+                WishOptions {
+                    escrow_bps: 1_000,
+                    ..Default::default()
+                },
+            );
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(200));
+            let _ = wishlist.fund_wish(1);
+            set_caller(accounts.charlie);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.fund_wish(1);
+
+            set_caller(accounts.alice);
+            set_value_transferred(U256::zero());
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert!(wishlist.claim_wish(1).is_ok());
+            assert_eq!(wishlist.refund_escrow(1).err(), Some(Error::EscrowOpen));
+
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1001 + FULFILLMENT_WINDOW);
+            assert!(wishlist.refund_escrow(1).is_ok());
+            // 10% of the 1300 pot, split 2:1
+            assert_eq!(wishlist.get_payout(1, accounts.bob), amount(86));
+            assert_eq!(wishlist.get_payout(1, accounts.charlie), amount(43));
+            assert_eq!(wishlist.refund_escrow(1).err(), Some(Error::NoEscrow));
+        }
+
+        #[ink::test]
+        pub fn receipts_build_reputation() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(1000));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(1000));
+            assert_eq!(
+                wishlist
+                    .submit_receipt(1, [1; 32], String::from("ipfs://receipt"))
+                    .err(),
+                Some(Error::NotClaimed)
+            );
+
+            set_value_transferred(U256::zero());
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert!(wishlist.claim_wish(1).is_ok());
+            assert_eq!(
+                wishlist.get_reputation(accounts.alice),
+                Reputation {
+                    claimed: 1,
+                    ..Default::default()
+                }
+            );
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                wishlist
+                    .submit_receipt(1, [1; 32], String::from("ipfs://receipt"))
+                    .err(),
+                Some(Error::NotOwner)
+            );
+            set_caller(accounts.alice);
+            assert!(wishlist
+                .submit_receipt(1, [1; 32], String::from("ipfs://receipt"))
+                .is_ok());
+            assert_eq!(
+                wishlist
+                    .submit_receipt(1, [2; 32], String::from("ipfs://other"))
+                    .err(),
+                Some(Error::ReceiptExists)
+            );
+            assert_eq!(
+                wishlist.get_settlement(1).unwrap().receipt.unwrap().hash,
+                [1; 32]
+            );
+            assert_eq!(wishlist.get_reputation(accounts.alice).receipts, 1);
+        }
+
+        #[ink::test]
+        pub fn spend_reports_count_towards_reputation() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(1000));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(1000));
+            let _ = wishlist.add_wishlist_item(String::from("Car"), 1000, amount(1000));
+            set_value_transferred(U256::zero());
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert!(wishlist.claim_wish(1).is_ok());
+            assert!(wishlist.claim_wish(2).is_ok());
+
+            assert!(wishlist
+                .file_spend_report(1, amount(600), SpendCategory::Goods, [1; 32])
+                .is_ok());
+            assert!(wishlist
+                .file_spend_report(1, amount(400), SpendCategory::Fees, [2; 32])
+                .is_ok());
+            assert_eq!(
+                wishlist
+                    .file_spend_report(1, amount(1), SpendCategory::Other, [3; 32])
+                    .err(),
+                Some(Error::InvalidSpendReport)
+            );
+            assert_eq!(wishlist.get_spend_reports(1).len(), 2);
+
+            // Late report on the second wish does not count as timely
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1001 + REPORT_WINDOW);
+            assert!(wishlist
+                .file_spend_report(2, amount(1000), SpendCategory::Goods, [4; 32])
+                .is_ok());
+            assert_eq!(wishlist.get_reputation(accounts.alice).timely_reports, 1);
+            assert_eq!(wishlist.get_reputation_score(accounts.alice), 2_500);
+        }
+
+        #[ink::test]
+        pub fn minimum_target_decides_success() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            assert_eq!(
+                wishlist
+                    .add_wishlist_item_with_options(
+                        String::from("Trip"),
+                        1000,
+                        amount(1000),
+                        WishOptions {
+                            minimum_target: Some(amount(1001)),
+                            ..Default::default()
+                        },
+                    )
+                    .err(),
+                Some(Error::InvalidTarget)
+            );
+            let _ = wishlist.add_wishlist_item_with_options(
+                String::from("Trip"),
+                1000,
+                amount(1000),
+                WishOptions {
+                    minimum_target: Some(amount(400)),
+                    ..Default::default()
+                },
+            );
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.fund_wish(1);
+            assert_eq!(
+                wishlist.get_progress(1),
+                Ok(Progress {
+                    minimum_bps: amount(5_000),
+                    target_bps: amount(2_000),
+                })
+            );
+
+            set_value_transferred(U256::from(200));
+            let _ = wishlist.fund_wish(1);
+            set_value_transferred(U256::zero());
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert!(wishlist.claim_wish(1).is_ok());
+        }
+
+        #[ink::test]
+        pub fn owner_budget_matches_contributions() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(250));
+            let _ = wishlist.add_wishlist_item_with_options(
+                String::from("Laptop"),
+                1000,
+                amount(1000),
+                WishOptions {
+                    match_budget: amount(150),
+                    match_ratio_bps: 5_000,
+                    ..Default::default()
+                },
+            );
+            let item = wishlist.get_wishlist_item(1).unwrap().active().unwrap();
+            assert_eq!(item.raised, amount(100));
+
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(200));
+            let _ = wishlist.fund_wish(1);
+            set_caller(accounts.charlie);
+            set_value_transferred(U256::from(200));
+            let _ = wishlist.fund_wish(1);
+            let item = wishlist.get_wishlist_item(1).unwrap().active().unwrap();
+            // 100 seed, 100 matching bob and the last 50 of the budget for charlie
+            assert_eq!(item.raised, amount(250));
+            assert_eq!(item.match_remaining, math::ZERO);
+
+            // A second wish leaves part of its budget unused
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(300));
+            let _ = wishlist.add_wishlist_item_with_options(
+                String::from("Phone"),
+                1000,
+                amount(1000),
+                WishOptions {
+                    match_budget: amount(200),
+                    match_ratio_bps: 10_000,
+                    ..Default::default()
+                },
+            );
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(50));
+            let _ = wishlist.fund_wish(2);
+
+            set_value_transferred(U256::zero());
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            let _ = wishlist.split_raised_wish(2);
+            assert_eq!(wishlist.get_credits(accounts.alice), amount(150));
+        }
+
+        #[ink::test]
+        pub fn pool_applies_once_target_is_met() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(1000));
+
+            set_caller(accounts.bob);
+            set_value_transferred(U256::zero());
+            let pool_id = wishlist.create_pool(1, amount(300), 500).unwrap();
+            set_value_transferred(U256::from(100));
+            assert!(wishlist.join_pool(pool_id).is_ok());
+            let item = wishlist.get_wishlist_item(1).unwrap().active().unwrap();
+            assert_eq!(item.contributor_count, 0);
+
+            set_caller(accounts.charlie);
+            set_value_transferred(U256::from(200));
+            assert!(wishlist.join_pool(pool_id).is_ok());
+            assert_eq!(
+                wishlist.get_contributors(1, 0, MAX_PAGE_SIZE),
+                vec![(accounts.bob, amount(100)), (accounts.charlie, amount(200))]
+            );
+            assert_eq!(
+                wishlist.get_pool(pool_id).unwrap().status,
+                PoolStatus::Applied
+            );
+            assert_eq!(wishlist.join_pool(pool_id).err(), Some(Error::PoolClosed));
+        }
+
+        #[ink::test]
+        pub fn unfilled_pool_is_refunded() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(1000));
+
+            set_caller(accounts.bob);
+            set_value_transferred(U256::zero());
+            let pool_id = wishlist.create_pool(1, amount(300), 500).unwrap();
+            set_value_transferred(U256::from(100));
+            assert!(wishlist.join_pool(pool_id).is_ok());
+            set_value_transferred(U256::zero());
+            assert_eq!(wishlist.refund_pool(pool_id).err(), Some(Error::PoolOpen));
+
+            set_block_timestamp::<ink::env::DefaultEnvironment>(501);
+            set_value_transferred(U256::from(100));
+            assert_eq!(wishlist.join_pool(pool_id).err(), Some(Error::PoolClosed));
+            set_value_transferred(U256::zero());
+            assert!(wishlist.refund_pool(pool_id).is_ok());
+            assert_eq!(wishlist.get_credits(accounts.bob), amount(100));
+        }
+
+        #[ink::test]
+        pub fn sealed_contributions_are_revealed_after_end() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item_with_options(
+                String::from("Surprise"),
+                1000,
+                amount(1000),
+                WishOptions {
+                    sealed: true,
+                    ..Default::default()
+                },
+            );
+
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(50));
+            assert_eq!(wishlist.fund_wish(1).err(), Some(Error::SealedWish));
+            let salt = [7u8; 32];
+            let mut hash = [0u8; 32];
+            ink::env::hash_encoded::<Keccak256, _>(
+                &(1u32, accounts.bob, amount(300), salt),
+                &mut hash,
+            );
+            set_value_transferred(U256::from(500));
+            assert!(wishlist.commit_contribution(1, hash).is_ok());
+            assert!(
+                wishlist
+                    .get_wishlist_item(1)
+                    .unwrap()
+                    .active()
+                    .unwrap()
+                    .contributor_count
+                    == 0
+            );
+
+            set_value_transferred(U256::zero());
+            assert_eq!(
+                wishlist.reveal_contribution(1, amount(300), salt).err(),
+                Some(Error::NotRevealPhase)
+            );
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(
+                wishlist.reveal_contribution(1, amount(299), salt).err(),
+                Some(Error::InvalidReveal)
+            );
+            assert!(wishlist.reveal_contribution(1, amount(300), salt).is_ok());
+            assert_eq!(
+                wishlist.get_contributors(1, 0, MAX_PAGE_SIZE),
+                vec![(accounts.bob, amount(300))]
+            );
+
+            set_caller(accounts.alice);
+            assert_eq!(wishlist.claim_wish(1).err(), Some(Error::NotRevealPhase));
+        }
+
+        #[ink::test]
+        pub fn rounds_enforce_caps_and_boundaries() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Album"), 1000, amount(1000));
+            let early = Round {
+                ends_at: 500,
+                cap: amount(200),
+                min_contribution: amount(50),
+            };
+            let late = Round {
+                ends_at: 1000,
+                cap: amount(800),
+                min_contribution: amount(10),
+            };
+            assert_eq!(
+                wishlist.set_rounds(1, vec![late, early]).err(),
+                Some(Error::InvalidRounds)
+            );
+            assert!(wishlist.set_rounds(1, vec![early, late]).is_ok());
+
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(20));
+            assert_eq!(wishlist.fund_wish(1).err(), Some(Error::RoundLimitExceeded));
+            set_value_transferred(U256::from(150));
+            assert!(wishlist.fund_wish(1).is_ok());
+            set_value_transferred(U256::from(60));
+            assert_eq!(wishlist.fund_wish(1).err(), Some(Error::RoundLimitExceeded));
+
+            set_block_timestamp::<ink::env::DefaultEnvironment>(500);
+            assert_eq!(wishlist.get_current_round(1), Some((1, late)));
+            set_value_transferred(U256::from(20));
+            assert!(wishlist.fund_wish(1).is_ok());
+            assert_eq!(
+                wishlist.round_progress.get(1),
+                Some(RoundProgress {
+                    index: 1,
+                    raised: amount(20),
+                })
+            );
+        }
+
+        #[cfg(feature = "u128-balance")]
+        #[ink::test]
+        pub fn values_beyond_u128_are_refused() {
+            let mut wishlist = Wishlist::default();
+            set_caller(default_accounts().alice);
+            set_value_transferred(U256::from(u128::MAX) + U256::one());
+            assert_eq!(
+                wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(1000)),
+                Err(Error::AmountOverflow)
+            );
+            assert_eq!(math::from_native(U256::from(u128::MAX)), Some(u128::MAX));
+        }
+
+        #[ink::test]
+        pub fn refused_contributions_pay_no_entry_fee() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            assert_eq!(wishlist.set_entry_fee(100, false), Ok(()));
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Album"), 1000, amount(1000));
+            let round = Round {
+                ends_at: 1000,
+                cap: amount(200),
+                min_contribution: amount(10),
+            };
+            assert!(wishlist.set_rounds(1, vec![round]).is_ok());
+
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(300));
+            assert_eq!(wishlist.fund_wish(1), Err(Error::RoundLimitExceeded));
+            assert_eq!(wishlist.get_treasury(), math::ZERO);
+            assert_eq!(wishlist.round_progress.get(1), None);
+
+            set_value_transferred(U256::from(200));
+            assert_eq!(wishlist.fund_wish(1), Ok(()));
+            assert_eq!(wishlist.get_treasury(), amount(2));
+        }
+
+        #[ink::test]
+        pub fn split_fails_with_typed_errors() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(1000));
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Book"), 1000, amount(100));
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(30));
+            let _ = wishlist.fund_wish(1);
+            let _ = wishlist.fund_wish(2);
+            set_value_transferred(U256::zero());
+
+            assert_eq!(wishlist.split_raised_wish(1), Err(Error::EndDateNotReached));
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            // The owner's seed met the target of the second wish.
+            assert_eq!(wishlist.split_raised_wish(2), Err(Error::NotFailed));
+            assert_eq!(wishlist.split_raised_wish(1), Ok(()));
+        }
+
+        #[ink::test]
+        pub fn split_pays_out_through_accumulator() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(1000));
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(30));
+            let _ = wishlist.fund_wish(1);
+            set_caller(accounts.charlie);
+            set_value_transferred(U256::from(70));
+            let _ = wishlist.fund_wish(1);
+            set_value_transferred(U256::zero());
+            assert_eq!(wishlist.get_payout(1, accounts.charlie), math::ZERO);
+
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            set_caller(accounts.alice);
+            assert_eq!(wishlist.split_raised_wish(1), Err(Error::NotContributor));
+            set_caller(accounts.charlie);
+            assert!(wishlist.split_raised_wish(1).is_ok());
+            // The 200 pot goes back to contributors in proportion to their stake
+            assert_eq!(wishlist.get_payout(1, accounts.bob), amount(60));
+            assert_eq!(wishlist.get_payout(1, accounts.charlie), amount(140));
+            assert_eq!(wishlist.get_payout(1, accounts.alice), math::ZERO);
+
+            assert!(wishlist.withdraw_payout(1).is_ok());
+            assert_eq!(wishlist.get_payout(1, accounts.charlie), math::ZERO);
+            set_caller(accounts.bob);
+            assert!(wishlist.withdraw_payout(1).is_ok());
+            assert_eq!(
+                wishlist.withdraw_payout(1).err(),
+                Some(Error::NothingToWithdraw)
+            );
+        }
+
+        #[ink::test]
+        pub fn storage_stats_track_growth() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(1000));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(1000));
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Car"), 1000, amount(1000));
+            set_caller(accounts.bob);
+            let _ = wishlist.fund_wish(2);
+
+            set_caller(accounts.alice);
+            set_value_transferred(U256::zero());
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert!(wishlist.claim_wish(1).is_ok());
+
+            let stats = wishlist.storage_stats();
+            assert_eq!(stats.wishes_created, 2);
+            assert_eq!(stats.active_wishes, 1);
+            assert_eq!(stats.archived_wishes, 1);
+            assert_eq!(stats.contributor_entries, 1);
+            assert_eq!(stats.deadline_index_len, 1);
+            assert!(stats.wish_bytes > 0);
+        }
+
+        #[ink::test]
+        pub fn audit_reconciles_liabilities_with_balance() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            let contract = ink::env::address();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(1000));
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(50));
+            let _ = wishlist.fund_wish(1);
+            set_value_transferred(U256::from(30));
+            let _ = wishlist.deposit();
+
+            set_contract_balance(contract, U256::from(180));
+            let report = wishlist.audit();
+            assert_eq!(report.pots, amount(150));
+            assert_eq!(report.held.deposits, amount(30));
+            assert_eq!(report.liabilities, amount(180));
+            assert_eq!(report.shortfall, math::ZERO);
+            assert_eq!(report.surplus, math::ZERO);
+
+            set_contract_balance(contract, U256::from(170));
+            assert_eq!(wishlist.audit().shortfall, amount(10));
+        }
+
+        /// Random sequences of create/fund/settle/withdraw operations, checked
+        /// against the settlement invariants after every step.
+        mod properties {
+            use super::*;
+            use proptest::prelude::*;
+
+            const DAY: u64 = 24 * 60 * 60 * 1000;
+            const RESERVE: u64 = 1_000;
+
+            #[derive(Debug, Clone)]
+            enum Op {
+                Create {
+                    owner: usize,
+                    target: u64,
+                    seed_pct: u64,
+                    days: u64,
+                },
+                Fund {
+                    who: usize,
+                    wish: usize,
+                    amount: u64,
+                },
+                Advance {
+                    days: u64,
+                },
+                Claim {
+                    wish: usize,
+                },
+                Split {
+                    who: usize,
+                    wish: usize,
+                },
+                Withdraw {
+                    who: usize,
+                    wish: usize,
+                },
+            }
+
+            fn op() -> impl Strategy<Value = Op> {
+                prop_oneof![
+                    (0..4usize, 10..10_000u64, 10..=100u64, 1..10u64).prop_map(
+                        |(owner, target, seed_pct, days)| Op::Create {
+                            owner,
+                            target,
+                            seed_pct,
+                            days
+                        }
+                    ),
+                    (0..4usize, 0..8usize, 1..5_000u64).prop_map(|(who, wish, amount)| Op::Fund {
+                        who,
+                        wish,
+                        amount
+                    }),
+                    (1..5u64).prop_map(|days| Op::Advance { days }),
+                    (0..8usize).prop_map(|wish| Op::Claim { wish }),
+                    (0..4usize, 0..8usize).prop_map(|(who, wish)| Op::Split { who, wish }),
+                    (0..4usize, 0..8usize).prop_map(|(who, wish)| Op::Withdraw { who, wish }),
+                ]
+            }
+
+            /// Tracks what went in and out of the contract alongside the wishes
+            /// the sequence created. The off-chain engine does not move
+            /// transferred value, so payable calls credit the contract by hand.
+            struct Harness {
+                wishlist: Wishlist,
+                contract: H160,
+                users: [H160; 4],
+                initial: [U256; 4],
+                paid_in: U256,
+                now: u64,
+                wishes: Vec<(u32, u64, H160)>,
+                contributors: Vec<Vec<H160>>,
+                settled: Vec<Option<SettlementOutcome>>,
+            }
+
+            impl Harness {
+                fn new() -> Self {
+                    let accounts = default_accounts();
+                    let users = [
+                        accounts.bob,
+                        accounts.charlie,
+                        accounts.django,
+                        accounts.eve,
+                    ];
+                    set_caller(accounts.alice);
+                    // Keep every account above the engine's existential deposit
+                    // so small transfers do not reap it.
+                    let contract = ink::env::address();
+                    set_contract_balance(contract, U256::from(RESERVE));
+                    for user in users {
+                        set_contract_balance(user, U256::from(RESERVE));
+                    }
+                    Self {
+                        wishlist: Wishlist::default(),
+                        contract,
+                        users,
+                        initial: users.map(Self::balance_of),
+                        paid_in: U256::zero(),
+                        now: 0,
+                        wishes: Vec::new(),
+                        contributors: Vec::new(),
+                        settled: Vec::new(),
+                    }
+                }
+
+                fn balance_of(account: H160) -> U256 {
+                    get_contract_balance::<ink::env::DefaultEnvironment>(account)
+                        .unwrap_or_default()
+                }
+
+                fn balance(&self) -> U256 {
+                    Self::balance_of(self.contract)
+                }
+
+                /// Run `call` as `caller` paying `value`, crediting the contract
+                /// only if the call succeeds.
+                fn pay(
+                    &mut self,
+                    caller: H160,
+                    value: U256,
+                    call: impl FnOnce(&mut Wishlist) -> Result<()>,
+                ) -> Result<()> {
+                    set_caller(caller);
+                    set_value_transferred(value);
+                    let before = self.balance();
+                    set_contract_balance(self.contract, before + value);
+                    let result = call(&mut self.wishlist);
+                    if result.is_ok() {
+                        self.paid_in += value;
+                    } else {
+                        set_contract_balance(self.contract, before);
+                    }
+                    set_value_transferred(U256::zero());
+                    result
+                }
+
+                fn settle(&mut self, index: usize, result: Result<()>) {
+                    if result.is_ok() {
+                        let id = self.wishes[index].0;
+                        let record = self
+                            .wishlist
+                            .get_settlement(id)
+                            .expect("settled wish is archived");
+                        self.settled[index] = Some(record.outcome);
+                    }
+                }
+
+                fn apply(&mut self, op: Op) {
+                    match op {
+                        Op::Create {
+                            owner,
+                            target,
+                            seed_pct,
+                            days,
+                        } => {
+                            let seed = U256::from(target) * U256::from(seed_pct) / U256::from(100);
+                            let target = amount(u128::from(target));
+                            let end_date = self.now + days * DAY;
+                            let id = self.wishlist.next_item_id;
+                            let result = self.pay(self.users[owner], seed, |w| {
+                                w.add_wishlist_item(String::from("wish"), end_date, target)
+                            });
+                            if result.is_ok() {
+                                self.wishes.push((id, end_date, self.users[owner]));
+                                self.contributors.push(Vec::new());
+                                self.settled.push(None);
+                            }
+                        }
+                        Op::Fund { who, wish, amount } => {
+                            if self.wishes.is_empty() {
+                                return;
+                            }
+                            let index = wish % self.wishes.len();
+                            let (id, _, owner) = self.wishes[index];
+                            let who = self.users[who];
+                            let result = self.pay(who, U256::from(amount), |w| w.fund_wish(id));
+                            if self.settled[index].is_some() {
+                                assert!(result.is_err(), "funded a settled wish");
+                            } else if result.is_ok()
+                                && who != owner
+                                && !self.contributors[index].contains(&who)
+                            {
+                                self.contributors[index].push(who);
+                            }
+                        }
+                        Op::Advance { days } => {
+                            self.now += days * DAY;
+                            set_block_timestamp::<ink::env::DefaultEnvironment>(self.now);
+                        }
+                        Op::Claim { wish } => {
+                            if self.wishes.is_empty() {
+                                return;
+                            }
+                            let index = wish % self.wishes.len();
+                            let (id, end_date, owner) = self.wishes[index];
+                            if self.now < end_date || self.settled[index].is_some() {
+                                return;
+                            }
+                            set_caller(owner);
+                            let result = self.wishlist.claim_wish(id);
+                            self.settle(index, result);
+                        }
+                        Op::Split { who, wish } => {
+                            if self.wishes.is_empty() {
+                                return;
+                            }
+                            let index = wish % self.wishes.len();
+                            let contributors = &self.contributors[index];
+                            if contributors.is_empty() || self.settled[index].is_some() {
+                                return;
+                            }
+                            set_caller(contributors[who % contributors.len()]);
+                            let result = self.wishlist.split_raised_wish(self.wishes[index].0);
+                            self.settle(index, result);
+                        }
+                        Op::Withdraw { who, wish } => {
+                            if self.wishes.is_empty() {
+                                return;
+                            }
+                            let id = self.wishes[wish % self.wishes.len()].0;
+                            set_caller(self.users[who]);
+                            if self.wishlist.withdraw_payout(id).is_ok() {
+                                assert_eq!(
+                                    self.wishlist.withdraw_payout(id),
+                                    Err(Error::NothingToWithdraw),
+                                    "paid out twice"
+                                );
+                            }
+                        }
+                    }
+                }
+
+                fn check(&self) {
+                    // Nothing is created or destroyed: whatever was paid in is
+                    // either still held or has reached a user.
+                    let paid_out = self
+                        .users
+                        .iter()
+                        .zip(self.initial.iter())
+                        .fold(U256::zero(), |acc, (user, initial)| {
+                            acc + Self::balance_of(*user) - *initial
+                        });
+                    assert_eq!(
+                        self.balance() + paid_out,
+                        self.paid_in + U256::from(RESERVE)
+                    );
+                    assert_eq!(self.wishlist.audit().shortfall, math::ZERO);
+
+                    // Settled wishes never come back or change outcome.
+                    for (index, outcome) in self.settled.iter().enumerate() {
+                        if let Some(outcome) = outcome {
+                            let id = self.wishes[index].0;
+                            assert!(self.wishlist.items.get(id).is_none());
+                            let record = self.wishlist.get_settlement(id).unwrap();
+                            assert_eq!(&record.outcome, outcome);
+                        }
                     }
+                }
+            }
+
+            proptest! {
+                #![proptest_config(ProptestConfig::with_cases(64))]
+
+                #[test]
+                fn settlement_conserves_value(ops in proptest::collection::vec(op(), 1..40)) {
+                    ink::env::test::run_test::<ink::env::DefaultEnvironment, _>(|_| {
+                        let mut harness = Harness::new();
+                        for op in ops {
+                            harness.apply(op);
+                            harness.check();
+                        }
+
+                        // Settle whatever is left and drain every payout; only
+                        // rounding dust may stay behind.
+                        harness.apply(Op::Advance { days: 20 });
+                        for index in 0..harness.wishes.len() {
+                            harness.apply(Op::Claim { wish: index });
+                            harness.apply(Op::Split { who: 0, wish: index });
+                        }
+                        for index in 0..harness.wishes.len() {
+                            for who in 0..harness.users.len() {
+                                harness.apply(Op::Withdraw { who, wish: index });
+                            }
+                        }
+                        harness.check();
+                        let audit = harness.wishlist.audit();
+                        let settled = harness.settled.iter().flatten().count();
+                        assert!(audit.held.payouts <= amount((settled * harness.users.len()) as u128));
+                        Ok(())
+                    })
+                    .unwrap();
+                }
+            }
+        }
+
+        #[ink::test]
+        pub fn injected_time_drives_claims() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::with_clock(Clock::Fixed(0));
+            set_value_transferred(U256::from(1000));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 5 * DAY, amount(1000));
+            assert_eq!(wishlist.claim_wish(1), Err(Error::EndDateNotReached));
+
+            set_caller(accounts.bob);
+            assert_eq!(wishlist.set_clock(5 * DAY), Err(Error::OnlyAdmin));
+            set_caller(accounts.alice);
+            assert_eq!(wishlist.set_clock(5 * DAY), Ok(()));
+            assert_eq!(wishlist.claim_wish(1), Ok(()));
+            assert!(wishlist.get_settlement(1).is_some());
+
+            let mut on_chain = Wishlist::default();
+            assert_eq!(on_chain.set_clock(5 * DAY), Err(Error::ClockNotFixed));
+        }
+
+        #[ink::test]
+        pub fn error_codes_match_revert_data() {
+            assert_eq!(Error::InvalidContribution.error_code(), 0);
+            assert_eq!(Error::InsufficientTreasury.error_code(), 21);
+            assert_eq!(Error::RoundLimitExceeded.error_code(), 53);
+            let encoded = ink::scale::Encode::encode(&Error::NotArbiter);
+            assert_eq!(encoded, [Error::NotArbiter.error_code() as u8]);
+        }
+
+        fn keccak(input: &[u8]) -> [u8; 32] {
+            let mut output = [0u8; 32];
+            ink::env::hash_bytes::<Keccak256>(input, &mut output);
+            output
+        }
+
+        #[ink::test]
+        pub fn standing_order_permits_are_signed_per_domain() {
+            use secp256k1::{Message, SecretKey, SECP256K1};
+
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            set_value_transferred(U256::from(1000));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(1000));
+            let _ = wishlist.set_chain_id(420_420_417);
+
+            let secret = SecretKey::from_slice(&[7u8; 32]).unwrap();
+            let public = secret.public_key(SECP256K1).serialize_uncompressed();
+            let signer = H160::from_slice(&keccak(&public[1..])[12..]);
+
+            let sign = |wishlist: &Wishlist, nonce: u64| {
+                let mut encoded = Vec::new();
+                encoded.extend_from_slice(&keccak(STANDING_ORDER_PERMIT_TYPE.as_bytes()));
+                encoded.extend_from_slice(&address_word(signer));
+                for value in [
+                    U256::from(1),
+                    U256::from(10),
+                    U256::from(DAY),
+                    U256::from(50),
+                    U256::from(nonce),
+                    U256::from(5_000),
+                ] {
+                    encoded.extend_from_slice(&word(value));
+                }
+                let mut message = b"\x19\x01".to_vec();
+                message.extend_from_slice(&wishlist.domain_separator());
+                message.extend_from_slice(&keccak(&encoded));
+                let digest = Message::from_digest(keccak(&message));
+                let (id, bytes) = SECP256K1
+                    .sign_ecdsa_recoverable(&digest, &secret)
+                    .serialize_compact();
+                let mut signature = [0u8; 65];
+                signature[..64].copy_from_slice(&bytes);
+                signature[64] = i32::from(id) as u8;
+                signature
+            };
+
+            // Anyone can relay the permit, but only once.
+            set_caller(accounts.bob);
+            let signature = sign(&wishlist, 0);
+            let approve = |wishlist: &mut Wishlist, signature| {
+                wishlist.approve_standing_order_by_permit(
+                    signer,
+                    1,
+                    amount(10),
+                    DAY,
+                    amount(50),
+                    5_000,
+                    signature,
+                )
+            };
+            assert_eq!(approve(&mut wishlist, signature), Ok(()));
+            assert!(wishlist.get_standing_order(signer, 1).is_some());
+            assert_eq!(wishlist.get_permit_nonce(signer), 1);
+            assert_eq!(
+                approve(&mut wishlist, signature),
+                Err(Error::InvalidSignature)
+            );
+
+            // A signature for another chain does not verify here.
+            set_caller(accounts.alice);
+            let _ = wishlist.set_chain_id(1);
+            let foreign = sign(&wishlist, 1);
+            let _ = wishlist.set_chain_id(420_420_417);
+            assert_eq!(
+                approve(&mut wishlist, foreign),
+                Err(Error::InvalidSignature)
+            );
+
+            set_block_timestamp::<ink::env::DefaultEnvironment>(5_001);
+            let fresh = sign(&wishlist, 1);
+            assert_eq!(approve(&mut wishlist, fresh), Err(Error::PermitExpired));
+        }
+
+        #[ink::test]
+        pub fn simulate_settlement_matches_outcome() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(1000));
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.fund_wish(1);
+            set_caller(accounts.charlie);
+            set_value_transferred(U256::from(200));
+            let _ = wishlist.fund_wish(1);
+
+            // Below target the pot of 400 is split 1:2 between contributors.
+            let preview = wishlist.simulate_settlement(1).unwrap();
+            assert_eq!(preview.outcome, SettlementOutcome::Split);
+            assert_eq!(
+                preview.refunds,
+                vec![(accounts.bob, amount(133)), (accounts.charlie, amount(266))]
+            );
+            assert_eq!(preview.dust, amount(1));
+
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            let _ = wishlist.split_raised_wish(1);
+            assert_eq!(wishlist.get_payout(1, accounts.bob), amount(133));
+            assert_eq!(wishlist.get_payout(1, accounts.charlie), amount(266));
+
+            // A funded wish past its end date previews the owner's claim.
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(1000));
+            let _ = wishlist.add_wishlist_item(String::from("Car"), 2000, amount(1000));
+            set_block_timestamp::<ink::env::DefaultEnvironment>(2000);
+            let preview = wishlist.simulate_settlement(2).unwrap();
+            assert_eq!(preview.outcome, SettlementOutcome::Claimed);
+            assert_eq!(preview.payouts, vec![(accounts.alice, amount(1000))]);
+        }
+
+        #[ink::test]
+        pub fn refund_amount_tracks_exposure() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(1000));
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(300));
+            let _ = wishlist.fund_wish(1);
+
+            // Rounded down as the payout accumulator rounds.
+            assert_eq!(wishlist.get_refund_amount(1, accounts.bob), amount(399));
+            assert_eq!(wishlist.get_refund_amount(1, accounts.alice), math::ZERO);
+
+            set_caller(accounts.charlie);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.fund_wish(1);
+            assert_eq!(wishlist.get_refund_amount(1, accounts.bob), amount(375));
+            assert_eq!(wishlist.get_refund_amount(1, accounts.charlie), amount(125));
+            assert_eq!(wishlist.get_refund_amount(2, accounts.bob), math::ZERO);
+        }
+
+        #[ink::test]
+        pub fn get_claimable_reports_net_payout() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(1000));
+            let options = WishOptions {
+                escrow_bps: 2_000,
+                ..WishOptions::default()
+            };
+            let _ = wishlist.add_wishlist_item_with_options(
+                String::from("Bike"),
+                1000,
+                amount(1000),
+                options,
+            );
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(500));
+            let _ = wishlist.fund_wish(1);
+
+            let claimable = wishlist.get_claimable(1).unwrap();
+            assert!(!claimable.can_claim);
+            assert_eq!(claimable.gross, amount(1500));
+            assert_eq!(claimable.escrowed, amount(300));
+            assert_eq!(claimable.net, amount(1200));
+
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert!(wishlist.get_claimable(1).unwrap().can_claim);
+            assert_eq!(wishlist.get_claimable(2), Err(Error::WishNotFound));
+        }
+
+        #[ink::test]
+        pub fn claim_all_settles_claimable_wishes_in_batches() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            let _ = wishlist.set_max_active_wishes(50);
+            for i in 0..MAX_BATCH_SIZE + 2 {
+                set_value_transferred(U256::from(10));
+                let _ = wishlist.add_wishlist_item(format!("Gift {i}"), 1000, amount(10));
+            }
+            // Not funded, so it cannot be claimed and stays active.
+            set_value_transferred(U256::from(10));
+            let _ = wishlist.add_wishlist_item(String::from("Car"), 1000, amount(100));
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+
+            let first = wishlist.claim_all(0).unwrap();
+            assert_eq!(first.settled.len(), MAX_BATCH_SIZE);
+            assert_eq!(first.next, Some(MAX_BATCH_SIZE as u32 + 1));
+
+            let second = wishlist.claim_all(first.next.unwrap()).unwrap();
+            assert_eq!(second.settled, vec![21, 22]);
+            assert_eq!(second.next, None);
+            assert_eq!(wishlist.get_active_count(accounts.alice), 1);
+        }
+
+        #[ink::test]
+        pub fn refund_all_collects_every_split_payout() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            for id in 1..4 {
+                set_caller(accounts.alice);
+                set_value_transferred(U256::from(100));
+                let _ = wishlist.add_wishlist_item(format!("Gift {id}"), 1000, amount(1000));
+                set_caller(accounts.bob);
+                set_value_transferred(U256::from(100));
+                let _ = wishlist.fund_wish(id);
+            }
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            for id in 1..3 {
+                let _ = wishlist.split_raised_wish(id);
+            }
+
+            let result = wishlist.refund_all(0).unwrap();
+            assert_eq!(result.settled, vec![1, 2]);
+            assert_eq!(result.next, None);
+            assert_eq!(wishlist.get_payout(1, accounts.bob), math::ZERO);
+            assert_eq!(wishlist.get_payout(2, accounts.bob), math::ZERO);
+            assert_eq!(wishlist.refund_all(0).unwrap().settled, Vec::<u32>::new());
+        }
+
+        #[ink::test]
+        pub fn sweep_dust_moves_surplus_to_treasury() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            let contract = ink::env::address();
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(1000));
+            set_contract_balance(contract, U256::from(107));
+
+            set_caller(accounts.bob);
+            assert_eq!(wishlist.sweep_dust(), Err(Error::OnlyAdmin));
+
+            set_caller(accounts.alice);
+            assert_eq!(wishlist.sweep_dust(), Ok(amount(7)));
+            assert_eq!(wishlist.get_treasury(), amount(7));
+            assert_eq!(wishlist.audit().surplus, math::ZERO);
+            assert_eq!(wishlist.sweep_dust(), Ok(math::ZERO));
+        }
+
+        #[ink::test]
+        pub fn paused_wishes_reject_contributions() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 10 * DAY, amount(1000));
+
+            set_caller(accounts.bob);
+            assert_eq!(wishlist.pause_wish(1), Err(Error::NotOwner));
+            set_caller(accounts.alice);
+            set_block_timestamp::<ink::env::DefaultEnvironment>(DAY);
+            assert_eq!(wishlist.pause_wish(1), Ok(()));
+            assert_eq!(wishlist.pause_wish(1), Err(Error::WishPaused));
+
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(50));
+            assert_eq!(wishlist.fund_wish(1), Err(Error::WishPaused));
+
+            set_caller(accounts.alice);
+            set_block_timestamp::<ink::env::DefaultEnvironment>(3 * DAY);
+            assert_eq!(wishlist.resume_wish(1, true), Ok(()));
+            assert_eq!(wishlist.resume_wish(1, true), Err(Error::NotPaused));
+            let item = wishlist.get_wishlist_item(1).unwrap().active().unwrap();
+            assert_eq!(item.end_date, 12 * DAY);
+
+            set_caller(accounts.bob);
+            assert_eq!(wishlist.fund_wish(1), Ok(()));
+        }
+
+        #[ink::test]
+        pub fn unlisted_wishes_stay_out_of_listings() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            for name in ["Bike", "Car", "Boat"] {
+                set_value_transferred(U256::from(100));
+                let _ = wishlist.add_wishlist_item(String::from(name), 1000, amount(1000));
+            }
+            set_caller(accounts.bob);
+            assert_eq!(wishlist.set_unlisted(2, true), Err(Error::NotOwner));
+            set_caller(accounts.alice);
+            assert_eq!(wishlist.set_unlisted(2, true), Ok(()));
+
+            let names = |items: Vec<WishListItem>| {
+                items.into_iter().map(|i| i.description).collect::<Vec<_>>()
+            };
+            assert_eq!(
+                names(wishlist.get_wishes_sorted(SortKey::Newest, 0, 10)),
+                vec![String::from("Boat"), String::from("Bike")]
+            );
+            assert_eq!(
+                names(wishlist.get_wishes_sorted(SortKey::Newest, 1, 10)),
+                vec![String::from("Bike")]
+            );
+            assert_eq!(names(wishlist.get_expiring_soon(DAY, 0, 10)).len(), 2);
+
+            // Still reachable and fundable by id.
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(50));
+            assert_eq!(wishlist.fund_wish(2), Ok(()));
+            assert!(wishlist.get_wishlist_item(2).is_ok());
+        }
+
+        #[ink::test]
+        pub fn upcoming_wishes_open_at_start_date() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let upcoming = |start_date| WishOptions {
+                start_date: Some(start_date),
+                ..WishOptions::default()
+            };
+            assert_eq!(
+                wishlist.add_wishlist_item_with_options(
+                    String::from("Bike"),
+                    DAY,
+                    amount(1000),
+                    upcoming(DAY),
+                ),
+                Err(Error::InvalidStartDate)
+            );
+            let _ = wishlist.add_wishlist_item_with_options(
+                String::from("Bike"),
+                5 * DAY,
+                amount(1000),
+                upcoming(DAY),
+            );
+            assert_eq!(wishlist.get_wish_summary(1).unwrap().start_date, Some(DAY));
+
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(50));
+            assert_eq!(wishlist.fund_wish(1), Err(Error::WishUpcoming));
+            set_block_timestamp::<ink::env::DefaultEnvironment>(DAY);
+            assert_eq!(wishlist.fund_wish(1), Ok(()));
+        }
+
+        #[ink::test]
+        pub fn pledges_convert_from_deposits_at_launch() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item_with_options(
+                String::from("Bike"),
+                5 * DAY,
+                amount(1000),
+                WishOptions {
+                    start_date: Some(DAY),
+                    ..WishOptions::default()
+                },
+            );
+
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(300));
+            let _ = wishlist.deposit();
+            assert_eq!(wishlist.pledge(1, amount(200)), Ok(()));
+            // Charlie pledges without a deposit to back it.
+            set_caller(accounts.charlie);
+            assert_eq!(wishlist.pledge(1, amount(500)), Ok(()));
+            assert_eq!(wishlist.get_pledges(1).len(), 2);
+            assert_eq!(wishlist.convert_pledges(1), Err(Error::WishUpcoming));
+
+            set_block_timestamp::<ink::env::DefaultEnvironment>(DAY);
+            assert_eq!(wishlist.pledge(1, amount(1)), Err(Error::NotUpcoming));
+            assert_eq!(wishlist.convert_pledges(1), Ok(()));
+            assert_eq!(wishlist.get_contributors_raised(1), Some(amount(200)));
+            assert_eq!(wishlist.get_deposit(accounts.bob), amount(100));
+            assert!(wishlist.get_pledges(1).is_empty());
+        }
+
+        #[ink::test]
+        pub fn refused_pledges_leave_nothing_behind() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            assert_eq!(wishlist.set_entry_fee(100, false), Ok(()));
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item_with_options(
+                String::from("Bike"),
+                5 * DAY,
+                amount(1000),
+                WishOptions {
+                    start_date: Some(DAY),
+                    ..WishOptions::default()
+                },
+            );
+            let round = Round {
+                ends_at: 5 * DAY,
+                cap: amount(200),
+                min_contribution: amount(10),
+            };
+            assert!(wishlist.set_rounds(1, vec![round]).is_ok());
+
+            // Bob's pledge is over the round's cap, Charlie's fits.
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(300));
+            let _ = wishlist.deposit();
+            assert_eq!(wishlist.pledge(1, amount(300)), Ok(()));
+            set_caller(accounts.charlie);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.deposit();
+            assert_eq!(wishlist.pledge(1, amount(100)), Ok(()));
+
+            set_block_timestamp::<ink::env::DefaultEnvironment>(DAY);
+            assert_eq!(wishlist.convert_pledges(1), Ok(()));
+            assert_eq!(wishlist.get_deposit(accounts.bob), amount(300));
+            assert_eq!(wishlist.get_deposit(accounts.charlie), math::ZERO);
+            assert_eq!(
+                wishlist.get_contributors(1, 0, MAX_PAGE_SIZE),
+                vec![(accounts.charlie, amount(99))]
+            );
+            assert_eq!(wishlist.get_treasury(), amount(1));
+            assert_eq!(
+                wishlist.round_progress.get(1),
+                Some(RoundProgress {
+                    index: 0,
+                    raised: amount(99),
+                })
+            );
+        }
+
+        #[ink::test]
+        pub fn milestones_are_announced_once() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(300));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(1000));
+
+            let milestones = || {
+                recorded_events()
+                    .into_iter()
+                    .filter(|e| {
+                        e.topics.first().copied()
+                            == <MilestoneReached as ink::env::Event>::SIGNATURE_TOPIC
+                    })
+                    .map(|e| {
+                        <MilestoneReached as ink::scale::Decode>::decode(&mut &e.data[..])
+                            .unwrap()
+                            .percent
+                    })
+                    .collect::<Vec<_>>()
+            };
+            assert!(milestones().is_empty());
+
+            // 30% -> 80% crosses 50 and 75.
+            set_value_transferred(U256::from(500));
+            let _ = wishlist.fund_wish(1);
+            assert_eq!(milestones(), vec![50, 75]);
+
+            set_value_transferred(U256::from(300));
+            let _ = wishlist.fund_wish(1);
+            assert_eq!(milestones(), vec![50, 75, 100]);
+        }
+
+        #[ink::test]
+        pub fn guardians_recover_ownership_after_delay() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 10 * DAY, amount(1000));
+            assert_eq!(
+                wishlist.set_guardians(vec![accounts.bob, accounts.charlie], 3),
+                Err(Error::InvalidGuardians)
+            );
+            assert_eq!(
+                wishlist.set_guardians(vec![accounts.bob, accounts.charlie, accounts.django], 2),
+                Ok(())
+            );
+
+            set_caller(accounts.eve);
+            assert_eq!(
+                wishlist.propose_recovery(accounts.alice, accounts.frank),
+                Err(Error::NotGuardian)
+            );
+            set_caller(accounts.bob);
+            assert_eq!(
+                wishlist.propose_recovery(accounts.alice, accounts.frank),
+                Ok(())
+            );
+            assert_eq!(
+                wishlist.execute_recovery(accounts.alice),
+                Err(Error::RecoveryPending)
+            );
+
+            // The owner can cancel while the delay runs.
+            set_caller(accounts.charlie);
+            assert_eq!(
+                wishlist.propose_recovery(accounts.alice, accounts.frank),
+                Ok(())
+            );
+            set_caller(accounts.alice);
+            assert_eq!(wishlist.cancel_recovery(), Ok(()));
+            assert_eq!(
+                wishlist.execute_recovery(accounts.alice),
+                Err(Error::NoRecovery)
+            );
+
+            set_caller(accounts.bob);
+            let _ = wishlist.propose_recovery(accounts.alice, accounts.frank);
+            set_caller(accounts.django);
+            let _ = wishlist.propose_recovery(accounts.alice, accounts.frank);
+            set_block_timestamp::<ink::env::DefaultEnvironment>(RECOVERY_DELAY);
+            assert_eq!(wishlist.execute_recovery(accounts.alice), Ok(()));
+
+            let item = wishlist.get_wishlist_item(1).unwrap().active().unwrap();
+            assert_eq!(item.owner, accounts.frank);
+            assert_eq!(wishlist.get_active_count(accounts.frank), 1);
+            assert_eq!(wishlist.get_active_count(accounts.alice), 0);
+            assert!(wishlist.get_guardians(accounts.frank).is_some());
+        }
+
+        #[ink::test]
+        pub fn manager_key_is_limited_to_low_risk_operations() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 10 * DAY, amount(100));
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                wishlist.post_update(1, [1; 32], String::from("ipfs://update")),
+                Err(Error::NotManager)
+            );
+            assert_eq!(
+                wishlist.set_manager(1, Some(accounts.bob)),
+                Err(Error::NotOwner)
+            );
+            set_caller(accounts.alice);
+            assert_eq!(wishlist.set_manager(1, Some(accounts.bob)), Ok(()));
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                wishlist.post_update(1, [1; 32], String::from("ipfs://update")),
+                Ok(())
+            );
+            assert_eq!(wishlist.extend_deadline(1, 20 * DAY), Ok(()));
+            assert_eq!(
+                wishlist.extend_deadline(1, 11 * DAY),
+                Err(Error::ExtensionLimitExceeded)
+            );
+            let item = wishlist.get_wishlist_item(1).unwrap().active().unwrap();
+            assert_eq!(item.end_date, 30 * DAY);
+
+            // Claims stay with the owner key.
+            set_block_timestamp::<ink::env::DefaultEnvironment>(30 * DAY);
+            assert_eq!(wishlist.claim_wish(1), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        pub fn operator_manages_wishes_without_redirecting_payouts() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(100));
+            assert_eq!(
+                wishlist.add_wishlist_item_for(
+                    accounts.alice,
+                    String::from("Bike"),
+                    10 * DAY,
+                    amount(100),
+                    WishOptions::default()
+                ),
+                Err(Error::NotOperator)
+            );
+
+            set_caller(accounts.alice);
+            assert_eq!(wishlist.approve_operator(accounts.bob, true), Ok(()));
+            set_caller(accounts.bob);
+            assert_eq!(
+                wishlist.add_wishlist_item_for(
+                    accounts.alice,
+                    String::from("Bike"),
+                    10 * DAY,
+                    amount(100),
+                    WishOptions::default()
+                ),
+                Ok(())
+            );
+            let item = wishlist.get_wishlist_item(1).unwrap().active().unwrap();
+            assert_eq!(item.owner, accounts.alice);
+            assert_eq!(
+                wishlist.post_update(1, [2; 32], String::from("ipfs://progress")),
+                Ok(())
+            );
+
+            set_block_timestamp::<ink::env::DefaultEnvironment>(10 * DAY);
+            assert_eq!(wishlist.claim_wish(1), Ok(()));
+            assert_eq!(wishlist.get_settlement(1).unwrap().owner, accounts.alice);
+            assert_eq!(wishlist.get_payout(1, accounts.bob), math::ZERO);
+        }
+
+        #[ink::test]
+        pub fn owner_topups_return_to_owner_on_split() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 10 * DAY, amount(1000));
+            set_value_transferred(U256::from(300));
+            let _ = wishlist.fund_wish(1);
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(200));
+            let _ = wishlist.fund_wish(1);
+
+            let item = wishlist.get_wishlist_item(1).unwrap().active().unwrap();
+            assert_eq!(item.raised, amount(100));
+            assert_eq!(item.owner_topups, amount(300));
+            assert_eq!(wishlist.get_progress(1).unwrap().target_bps, amount(4_000));
+
+            set_block_timestamp::<ink::env::DefaultEnvironment>(10 * DAY);
+            assert_eq!(wishlist.split_raised_wish(1), Ok(()));
+            // Bob shares the initial raise; the top-up goes back to Alice.
+            assert_eq!(wishlist.get_payout(1, accounts.bob), amount(300));
+            assert_eq!(wishlist.get_credits(accounts.alice), amount(300));
+            assert_eq!(wishlist.audit().shortfall, math::ZERO);
+        }
+
+        #[ink::test]
+        pub fn tiny_wishes_are_rejected() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            // The 10% of a target of 5 rounds down to nothing.
+            set_value_transferred(U256::zero());
+            assert_eq!(
+                wishlist.add_wishlist_item(String::from("Gum"), DAY, amount(5)),
+                Err(Error::InvalidContribution)
+            );
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                wishlist.set_minimums(amount(1_000), amount(50)),
+                Err(Error::OnlyAdmin)
+            );
+            set_caller(accounts.alice);
+            assert_eq!(wishlist.set_minimums(amount(1_000), amount(50)), Ok(()));
+            set_value_transferred(U256::from(100));
+            assert_eq!(
+                wishlist.add_wishlist_item(String::from("Gum"), DAY, amount(500)),
+                Err(Error::TargetTooSmall)
+            );
+            set_value_transferred(U256::from(20));
+            assert_eq!(
+                wishlist.add_wishlist_item(String::from("Gum"), DAY, amount(1_000)),
+                Err(Error::InvalidContribution)
+            );
+            set_value_transferred(U256::from(100));
+            assert_eq!(
+                wishlist.add_wishlist_item(String::from("Bike"), DAY, amount(1_000)),
+                Ok(())
+            );
+        }
+
+        #[ink::test]
+        pub fn campaign_totals_cover_its_wishes() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Plates"), 10 * DAY, amount(1000));
+            let _ = wishlist.add_wishlist_item(String::from("Glasses"), 10 * DAY, amount(500));
+            let _ = wishlist.add_wishlist_item(String::from("Honeymoon"), 10 * DAY, amount(800));
+            set_caller(accounts.bob);
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 10 * DAY, amount(500));
+
+            set_caller(accounts.alice);
+            assert_eq!(
+                wishlist.create_campaign(String::from("our-wedding"), vec![1, 4]),
+                Err(Error::NotOwner)
+            );
+            assert_eq!(
+                wishlist.create_campaign(String::from("our-wedding"), vec![1, 2]),
+                Ok(0)
+            );
+            assert_eq!(
+                wishlist.create_campaign(String::from("our-wedding"), vec![3]),
+                Err(Error::SlugTaken)
+            );
+            assert_eq!(wishlist.add_to_campaign(0, 3), Ok(()));
+            assert_eq!(
+                wishlist.get_campaign_by_slug(String::from("our-wedding")),
+                Some(0)
+            );
+
+            set_caller(accounts.charlie);
+            set_value_transferred(U256::from(50));
+            let _ = wishlist.fund_wish(2);
+            let totals = wishlist.get_campaign_totals(0).unwrap();
+            assert_eq!(totals.target, amount(2300));
+            assert_eq!(totals.raised, amount(350));
+        }
+
+        #[ink::test]
+        pub fn duplicate_descriptions_are_rejected_within_window() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            assert_eq!(
+                wishlist.add_wishlist_item(String::from("New bike"), 10 * DAY, amount(1000)),
+                Ok(())
+            );
+            assert_eq!(
+                wishlist.add_wishlist_item(String::from("  new   BIKE "), 10 * DAY, amount(1000)),
+                Err(Error::DuplicateWish)
+            );
+            // Other owners may use the same words.
+            set_caller(accounts.bob);
+            assert_eq!(
+                wishlist.add_wishlist_item(String::from("New bike"), 10 * DAY, amount(1000)),
+                Ok(())
+            );
+
+            set_caller(accounts.alice);
+            set_block_timestamp::<ink::env::DefaultEnvironment>(DUPLICATE_WINDOW);
+            assert_eq!(
+                wishlist.add_wishlist_item(String::from("New bike"), 10 * DAY, amount(1000)),
+                Ok(())
+            );
+        }
+
+        #[ink::test]
+        pub fn close_funding_stops_contributions() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 10 * DAY, amount(1000));
+            let _ = wishlist.add_wishlist_item(String::from("Lamp"), 10 * DAY, amount(100));
+
+            set_caller(accounts.bob);
+            assert_eq!(wishlist.close_funding(1), Err(Error::NotOwner));
+            set_caller(accounts.alice);
+            set_block_timestamp::<ink::env::DefaultEnvironment>(DAY);
+            assert_eq!(wishlist.close_funding(1), Ok(()));
+            assert_eq!(wishlist.close_funding(1), Err(Error::FundingClosed));
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(50));
+            assert_eq!(wishlist.fund_wish(1), Err(Error::FundingClosed));
+            // Short of its target, the wish still runs to its end date.
+            let item = wishlist.get_wishlist_item(1).unwrap().active().unwrap();
+            assert_eq!(item.end_date, 10 * DAY);
+
+            // A funded wish can be claimed straight away.
+            set_caller(accounts.alice);
+            assert_eq!(wishlist.close_funding(2), Ok(()));
+            assert_eq!(wishlist.claim_wish(2), Ok(()));
+        }
+
+        #[ink::test]
+        pub fn owner_can_reject_a_contribution() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 10 * DAY, amount(1000));
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(200));
+            let _ = wishlist.fund_wish(1);
+            set_caller(accounts.charlie);
+            set_value_transferred(U256::from(50));
+            let _ = wishlist.fund_wish(1);
+
+            assert_eq!(
+                wishlist.reject_contribution(1, accounts.bob),
+                Err(Error::NotOwner)
+            );
+            set_caller(accounts.alice);
+            assert_eq!(
+                wishlist.reject_contribution(1, accounts.django),
+                Err(Error::NotContributor)
+            );
+            assert_eq!(wishlist.reject_contribution(1, accounts.bob), Ok(()));
+
+            assert_eq!(
+                wishlist.get_contributors(1, 0, MAX_PAGE_SIZE),
+                vec![(accounts.charlie, amount(50))]
+            );
+            assert_eq!(wishlist.get_credits(accounts.bob), amount(200));
+            assert_eq!(wishlist.audit().shortfall, math::ZERO);
+
+            assert!(recorded_events().into_iter().any(|e| {
+                e.topics.first().copied()
+                    == <ContributionRejected as ink::env::Event>::SIGNATURE_TOPIC
+            }));
+        }
+
+        #[ink::test]
+        pub fn banned_accounts_cannot_contribute() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 10 * DAY, amount(1000));
+            let _ = wishlist.add_wishlist_item(String::from("Lamp"), 10 * DAY, amount(1000));
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                wishlist.set_banned(1, accounts.charlie, true),
+                Err(Error::NotOwner)
+            );
+            set_caller(accounts.alice);
+            assert_eq!(wishlist.set_banned(1, accounts.charlie, true), Ok(()));
+
+            set_caller(accounts.charlie);
+            set_value_transferred(U256::from(50));
+            assert_eq!(wishlist.fund_wish(1), Err(Error::BannedContributor));
+            // The ban only covers that wish.
+            assert_eq!(wishlist.fund_wish(2), Ok(()));
+
+            set_caller(accounts.alice);
+            let _ = wishlist.set_banned(1, accounts.charlie, false);
+            set_caller(accounts.charlie);
+            assert_eq!(wishlist.fund_wish(1), Ok(()));
+        }
+
+        #[ink::test]
+        pub fn contributions_must_accept_current_terms() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 10 * DAY, amount(1000));
+            assert_eq!(wishlist.set_terms(1, Some([7; 32])), Ok(()));
+
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(50));
+            assert_eq!(wishlist.fund_wish(1), Err(Error::TermsMismatch));
+            assert_eq!(
+                wishlist.fund_wish_with_terms(1, [8; 32]),
+                Err(Error::TermsMismatch)
+            );
+            assert_eq!(wishlist.fund_wish_with_terms(1, [7; 32]), Ok(()));
+            assert_eq!(wishlist.get_terms_accepted(1, accounts.bob), Some([7; 32]));
+
+            set_caller(accounts.alice);
+            let _ = wishlist.set_terms(1, None);
+            set_caller(accounts.charlie);
+            assert_eq!(wishlist.fund_wish(1), Ok(()));
+            assert_eq!(wishlist.get_terms_accepted(1, accounts.charlie), None);
+        }
+
+        #[ink::test]
+        pub fn rollover_carries_consenting_contributors() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 10 * DAY, amount(1000));
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(200));
+            let _ = wishlist.fund_wish(1);
+            assert_eq!(wishlist.consent_rollover(1, true), Ok(()));
+            set_caller(accounts.charlie);
+            assert_eq!(wishlist.set_rollover_preapproval(true), Ok(()));
+            set_value_transferred(U256::from(300));
+            let _ = wishlist.fund_wish(1);
+            set_caller(accounts.django);
+            set_value_transferred(U256::from(50));
+            let _ = wishlist.fund_wish(1);
+
+            set_caller(accounts.alice);
+            assert_eq!(wishlist.rollover(1, 20 * DAY), Err(Error::NotFailed));
+            set_block_timestamp::<ink::env::DefaultEnvironment>(10 * DAY);
+            assert_eq!(wishlist.rollover(1, 20 * DAY), Ok(2));
+
+            let next = wishlist.get_wishlist_item(2).unwrap().active().unwrap();
+            assert_eq!(next.end_date, 20 * DAY);
+            assert_eq!(next.raised, amount(100));
+            assert_eq!(
+                wishlist.get_contributors(2, 0, MAX_PAGE_SIZE),
+                vec![(accounts.bob, amount(200)), (accounts.charlie, amount(300))]
+            );
+            assert_eq!(wishlist.get_credits(accounts.django), amount(50));
+            assert_eq!(
+                wishlist.get_settlement(1).unwrap().outcome,
+                SettlementOutcome::RolledOver
+            );
+            assert_eq!(wishlist.audit().shortfall, math::ZERO);
+        }
+
+        #[ink::test]
+        pub fn auto_claim_settles_at_target_and_on_poke() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let auto = WishOptions {
+                auto_claim: true,
+                ..Default::default()
+            };
+            let _ = wishlist.add_wishlist_item_with_options(
+                String::from("Bike"),
+                10 * DAY,
+                amount(500),
+                auto.clone(),
+            );
+            let _ = wishlist.add_wishlist_item_with_options(
+                String::from("Lamp"),
+                10 * DAY,
+                amount(100),
+                auto,
+            );
+            let _ = wishlist.add_wishlist_item(String::from("Desk"), 10 * DAY, amount(100));
+
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(50));
+            let _ = wishlist.fund_wish(1);
+            assert!(wishlist.get_wishlist_item(1).unwrap().active().is_some());
+
+            // Alice's top-up meets the target and settles the wish at once.
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(400));
+            assert_eq!(wishlist.fund_wish(1), Ok(()));
+            assert_eq!(
+                wishlist.get_settlement(1).unwrap().outcome,
+                SettlementOutcome::Claimed
+            );
+            assert_eq!(wishlist.get_credits(accounts.alice), amount(550));
+
+            set_caller(accounts.bob);
+            assert_eq!(wishlist.poke(3), Err(Error::NotAutoClaim));
+            assert_eq!(wishlist.poke(2), Ok(()));
+            assert_eq!(wishlist.get_credits(accounts.alice), amount(650));
+            assert_eq!(wishlist.audit().shortfall, math::ZERO);
+        }
+
+        #[ink::test]
+        pub fn psp22_transfers_are_credited_to_wishes() {
+            let accounts = default_accounts();
+            let token = H160::from([0x22; 20]);
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 10 * DAY, amount(1000));
+            let data = ink::scale::Encode::encode(&(1u32, accounts.bob));
+
+            set_caller(token);
+            assert!(PSP22Receiver::before_received(
+                &mut wishlist,
+                accounts.bob,
+                accounts.bob,
+                amount(70),
+                data.clone()
+            )
+            .is_err());
+            set_caller(accounts.alice);
+            assert_eq!(wishlist.set_accepted_token(token, true), Ok(()));
+            set_caller(token);
+            assert_eq!(
+                PSP22Receiver::before_received(
+                    &mut wishlist,
+                    accounts.bob,
+                    accounts.bob,
+                    amount(70),
+                    data.clone()
+                ),
+                Ok(())
+            );
+            assert_eq!(
+                PSP22Receiver::before_received(
+                    &mut wishlist,
+                    accounts.bob,
+                    accounts.bob,
+                    amount(30),
+                    data
+                ),
+                Ok(())
+            );
+            assert!(PSP22Receiver::before_received(
+                &mut wishlist,
+                accounts.bob,
+                accounts.bob,
+                amount(1),
+                vec![1, 2]
+            )
+            .is_err());
+            assert_eq!(
+                wishlist.get_token_pots(1),
+                vec![TokenPot {
+                    token,
+                    contributions: vec![(accounts.bob, amount(100))],
+                }]
+            );
+
+            // The wish falls short, so Bob's tokens are refunded to him.
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(10));
+            let _ = wishlist.fund_wish(1);
+            set_block_timestamp::<ink::env::DefaultEnvironment>(10 * DAY);
+            assert_eq!(wishlist.split_raised_wish(1), Ok(()));
+            assert_eq!(wishlist.get_token_credits(accounts.bob, token), amount(100));
+            assert!(wishlist.get_token_pots(1).is_empty());
+        }
+
+        #[ink::test]
+        pub fn eoa_only_wishes_reject_contracts() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item_with_options(
+                String::from("Bike"),
+                10 * DAY,
+                amount(1000),
+                WishOptions {
+                    eoa_only: true,
+                    ..Default::default()
+                },
+            );
 
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(50));
+            assert_eq!(wishlist.fund_wish(1), Ok(()));
+            ink::env::test::set_contract(accounts.charlie);
+            set_caller(accounts.charlie);
+            assert_eq!(wishlist.fund_wish(1), Err(Error::ContractContributor));
+        }
+
+        #[ink::test]
+        pub fn accepted_nfts_count_towards_the_target() {
+            let accounts = default_accounts();
+            let collection = H160::from([0x34; 20]);
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 10 * DAY, amount(1000));
+            let _ = wishlist.add_wishlist_item(String::from("Kite"), 10 * DAY, amount(1000));
+
+            set_caller(collection);
+            let offer = |wish: u32, valuation: u64| {
+                ink::scale::Encode::encode(&(wish, amount(u128::from(valuation))))
+            };
+            for (token, wish) in [(1u32, 1u32), (2, 1), (3, 2)] {
+                assert_eq!(
+                    PSP34Receiver::before_received(
+                        &mut wishlist,
+                        accounts.bob,
+                        accounts.bob,
+                        Id::U32(token),
+                        offer(wish, 900),
+                    ),
                     Ok(())
+                );
+            }
+            assert!(PSP34Receiver::before_received(
+                &mut wishlist,
+                accounts.bob,
+                accounts.bob,
+                Id::U32(4),
+                vec![1, 2],
+            )
+            .is_err());
+            assert_eq!(wishlist.get_wish_nfts(1), vec![0, 1]);
+
+            set_caller(accounts.bob);
+            assert_eq!(wishlist.accept_nft(0), Err(Error::NotOwner));
+            set_caller(accounts.alice);
+            assert_eq!(wishlist.accept_nft(0), Ok(()));
+            assert_eq!(wishlist.accept_nft(0), Err(Error::NftReviewed));
+            set_caller(accounts.bob);
+            assert_eq!(wishlist.withdraw_nft(0), Err(Error::NftHeld));
+            set_caller(accounts.alice);
+            assert_eq!(wishlist.reject_nft(1), Ok(()));
+            assert_eq!(wishlist.get_wish_nfts(1), vec![0]);
+            assert_eq!(
+                wishlist.get_nft_deposit(1).unwrap().owed_to,
+                Some(accounts.bob)
+            );
+            assert_eq!(wishlist.get_progress(1).unwrap().target_bps, amount(10_000));
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(10));
+            assert_eq!(wishlist.fund_wish(2), Ok(()));
+            set_caller(accounts.alice);
+
+            set_block_timestamp::<ink::env::DefaultEnvironment>(10 * DAY);
+            assert_eq!(wishlist.claim_wish(1), Ok(()));
+            assert_eq!(
+                wishlist.get_nft_deposit(0).unwrap().owed_to,
+                Some(accounts.alice)
+            );
+            set_caller(accounts.bob);
+            assert_eq!(wishlist.split_raised_wish(2), Ok(()));
+            assert_eq!(
+                wishlist.get_nft_deposit(2).unwrap().owed_to,
+                Some(accounts.bob)
+            );
+            assert_eq!(wishlist.audit().shortfall, math::ZERO);
+        }
+
+        #[ink::test]
+        pub fn only_the_owner_sets_a_purchase() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 10 * DAY, amount(1000));
+            let purchase = Purchase {
+                marketplace: H160::from([0x4d; 20]),
+                listing: 7,
+                max_price: amount(900),
+            };
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                wishlist.set_purchase(1, Some(purchase)),
+                Err(Error::NotOwner)
+            );
+            set_caller(accounts.alice);
+            assert_eq!(
+                wishlist.set_purchase(
+                    1,
+                    Some(Purchase {
+                        max_price: math::ZERO,
+                        ..purchase
+                    })
+                ),
+                Err(Error::InvalidPurchase)
+            );
+            assert_eq!(wishlist.set_purchase(1, Some(purchase)), Ok(()));
+            assert_eq!(wishlist.get_purchase(1), Some(purchase));
+            assert_eq!(wishlist.set_purchase(1, None), Ok(()));
+            assert_eq!(wishlist.get_purchase(1), None);
+        }
+
+        #[ink::test]
+        pub fn loan_repayments_go_to_contributors() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            set_value_transferred(U256::from(100));
+            assert_eq!(
+                wishlist.add_wishlist_item_with_options(
+                    String::from("Van"),
+                    10 * DAY,
+                    amount(1000),
+                    WishOptions {
+                        loan: Some(LoanTerms {
+                            installments: 0,
+                            interval: DAY,
+                        }),
+                        ..Default::default()
+                    },
+                ),
+                Err(Error::InvalidLoanTerms)
+            );
+            let _ = wishlist.add_wishlist_item_with_options(
+                String::from("Van"),
+                10 * DAY,
+                amount(1000),
+                WishOptions {
+                    loan: Some(LoanTerms {
+                        installments: 4,
+                        interval: 10 * DAY,
+                    }),
+                    funding_model: FundingModel::KeepWhatYouRaise,
+                    ..Default::default()
+                },
+            );
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(600));
+            let _ = wishlist.fund_wish(1);
+            set_caller(accounts.charlie);
+            set_value_transferred(U256::from(200));
+            let _ = wishlist.fund_wish(1);
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.fund_wish(1);
+
+            set_value_transferred(U256::from(400));
+            assert_eq!(wishlist.repay(1), Err(Error::NoLoan));
+            set_block_timestamp::<ink::env::DefaultEnvironment>(10 * DAY);
+            assert_eq!(wishlist.claim_wish(1), Ok(()));
+            let loan = wishlist.get_loan(1).unwrap();
+            assert_eq!(loan.principal, amount(800));
+
+            set_block_timestamp::<ink::env::DefaultEnvironment>(15 * DAY);
+            assert_eq!(wishlist.repay(1), Ok(()));
+            set_block_timestamp::<ink::env::DefaultEnvironment>(45 * DAY);
+            set_value_transferred(U256::from(200));
+            assert_eq!(wishlist.repay(1), Ok(()));
+            assert_eq!(wishlist.get_payout(1, accounts.bob), amount(450));
+            assert_eq!(wishlist.get_payout(1, accounts.charlie), amount(150));
+            let reputation = wishlist.get_reputation(accounts.alice);
+            assert_eq!(reputation.installments_on_time, 2);
+            assert_eq!(reputation.installments_late, 1);
+
+            set_value_transferred(U256::from(300));
+            assert_eq!(wishlist.repay(1), Err(Error::InvalidContribution));
+            set_value_transferred(U256::from(200));
+            assert_eq!(wishlist.repay(1), Ok(()));
+            assert_eq!(wishlist.get_loan(1), None);
+            assert_eq!(wishlist.audit().shortfall, math::ZERO);
+        }
+
+        #[ink::test]
+        pub fn wishes_short_at_the_soft_deadline_fail_early() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            let options = WishOptions {
+                soft_deadline: Some(5 * DAY),
+                soft_threshold_bps: 5_000,
+                withdrawal_lock: 10 * DAY,
+                ..Default::default()
+            };
+            set_value_transferred(U256::from(100));
+            assert_eq!(
+                wishlist.add_wishlist_item_with_options(
+                    String::from("Bike"),
+                    5 * DAY,
+                    amount(1000),
+                    options.clone(),
+                ),
+                Err(Error::InvalidSoftDeadline)
+            );
+            let _ = wishlist.add_wishlist_item_with_options(
+                String::from("Bike"),
+                10 * DAY,
+                amount(1000),
+                options.clone(),
+            );
+            set_value_transferred(U256::from(500));
+            let _ = wishlist.add_wishlist_item_with_options(
+                String::from("Kite"),
+                10 * DAY,
+                amount(1000),
+                options,
+            );
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(50));
+            assert_eq!(wishlist.fund_wish(1), Ok(()));
+            assert_eq!(wishlist.fund_wish(2), Ok(()));
+
+            set_block_timestamp::<ink::env::DefaultEnvironment>(5 * DAY);
+            assert_eq!(wishlist.fund_wish(1), Err(Error::SoftDeadlineMissed));
+            assert_eq!(wishlist.fund_wish(2), Ok(()));
+            assert_eq!(
+                wishlist.withdraw_contribution(2, amount(10)),
+                Err(Error::ContributionsLocked)
+            );
+            assert_eq!(wishlist.withdraw_contribution(1, amount(10)), Ok(()));
+
+            set_caller(accounts.alice);
+            assert_eq!(wishlist.rollover(2, 20 * DAY), Err(Error::NotFailed));
+            assert!(wishlist.rollover(1, 20 * DAY).is_ok());
+        }
+
+        #[ink::test]
+        pub fn gifted_wishes_pass_to_the_recipient() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            set_value_transferred(U256::from(100));
+            assert_eq!(
+                wishlist.gift_wish(
+                    accounts.bob,
+                    String::from("Wedding registry"),
+                    10 * DAY,
+                    amount(1000),
+                    WishOptions::default(),
+                ),
+                Ok(1)
+            );
+            assert_eq!(wishlist.get_gift_recipient(1), Some(accounts.bob));
+            assert_eq!(wishlist.get_wish_summary(1).unwrap().owner, accounts.alice);
+
+            set_caller(accounts.charlie);
+            assert_eq!(wishlist.accept_gift(1), Err(Error::NotGiftRecipient));
+            set_caller(accounts.bob);
+            assert_eq!(wishlist.accept_gift(1), Ok(()));
+            assert_eq!(wishlist.accept_gift(1), Err(Error::NotGiftRecipient));
+            assert_eq!(wishlist.get_gift_recipient(1), None);
+            assert_eq!(wishlist.get_wish_summary(1).unwrap().owner, accounts.bob);
+            assert!(wishlist
+                .active_by_owner
+                .get(accounts.alice)
+                .unwrap()
+                .is_empty());
+            assert_eq!(wishlist.set_unlisted(1, true), Ok(()));
+        }
+
+        #[ink::test]
+        pub fn withdrawals_go_to_the_payout_address() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 10 * DAY, amount(1000));
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(60));
+            let _ = wishlist.fund_wish(1);
+            assert_eq!(wishlist.payout_address(accounts.bob), accounts.bob);
+            assert_eq!(wishlist.set_payout_address(Some(accounts.eve)), Ok(()));
+            assert_eq!(wishlist.payout_address(accounts.bob), accounts.eve);
+
+            let before =
+                get_contract_balance::<ink::env::DefaultEnvironment>(accounts.eve).unwrap();
+            set_value_transferred(U256::zero());
+            assert_eq!(wishlist.withdraw_contribution(1, amount(60)), Ok(()));
+            assert_eq!(
+                get_contract_balance::<ink::env::DefaultEnvironment>(accounts.eve).unwrap(),
+                before + U256::from(60)
+            );
+            assert_eq!(wishlist.set_payout_address(None), Ok(()));
+            assert_eq!(wishlist.payout_address(accounts.bob), accounts.bob);
+        }
+
+        #[ink::test]
+        pub fn get_ids_skips_settled_wishes() {
+            set_caller(default_accounts().alice);
+            let mut wishlist = Wishlist::default();
+            for i in 0..4 {
+                set_value_transferred(U256::from(100));
+                let _ = wishlist.add_wishlist_item(format!("Gift {i}"), 1000, amount(100));
+            }
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert!(wishlist.claim_wish(2).is_ok());
+
+            assert!(wishlist.wish_exists(1));
+            assert!(!wishlist.wish_exists(2));
+            assert!(!wishlist.wish_exists(5));
+            assert_eq!(wishlist.get_ids(1, 4), vec![1, 3]);
+            assert_eq!(wishlist.get_ids(2, u32::MAX), vec![3, 4]);
+            assert!(wishlist.get_ids(4, 4).is_empty());
+        }
+
+        #[ink::test]
+        pub fn early_claim_weights_are_snapshotted_and_delegable() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(200));
+            let _ = wishlist.add_wishlist_item(String::from("Gift"), 10 * DAY, amount(200));
+            set_caller(accounts.charlie);
+            assert_eq!(wishlist.delegate(Some(accounts.bob)), Ok(()));
+            assert_eq!(wishlist.get_delegate(accounts.charlie), Some(accounts.bob));
+            for (account, amount) in [
+                (accounts.bob, 40),
+                (accounts.charlie, 30),
+                (accounts.django, 30),
+            ] {
+                set_caller(account);
+                set_value_transferred(U256::from(amount));
+                let _ = wishlist.fund_wish(1);
+            }
+            assert_eq!(wishlist.get_voting_weight(1, accounts.bob), amount(70));
+
+            set_caller(accounts.alice);
+            set_value_transferred(U256::zero());
+            assert!(wishlist.request_early_claim(1).is_ok());
+            assert_eq!(wishlist.tally_early_claim(1), Ok(false));
+            set_caller(accounts.django);
+            set_value_transferred(U256::from(500));
+            let _ = wishlist.fund_wish(1);
+            assert_eq!(wishlist.get_voting_weight(1, accounts.django), amount(30));
+
+            set_caller(accounts.charlie);
+            assert_eq!(
+                wishlist.vote_early_claim(1, true),
+                Err(Error::NotContributor)
+            );
+            set_caller(accounts.bob);
+            assert_eq!(wishlist.revoke_early_claim_vote(1), Err(Error::NotVoted));
+            assert_eq!(wishlist.vote_early_claim(1, true), Ok(()));
+            assert_eq!(wishlist.tally_early_claim(1), Ok(true));
+            assert_eq!(wishlist.revoke_early_claim_vote(1), Ok(()));
+            assert_eq!(wishlist.tally_early_claim(1), Ok(false));
+            assert_eq!(wishlist.vote_early_claim(1, true), Ok(()));
+
+            set_caller(accounts.charlie);
+            set_value_transferred(U256::zero());
+            assert_eq!(wishlist.withdraw_contribution(1, amount(30)), Ok(()));
+            let claim = wishlist.get_early_claim(1).unwrap();
+            assert_eq!(claim.approvals, amount(40));
+            assert_eq!(claim.total, amount(70));
+            assert_eq!(wishlist.tally_early_claim(1), Ok(true));
+        }
+
+        #[ink::test]
+        pub fn early_claim_weight_follows_the_delegate_on_the_next_contribution() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(200));
+            let _ = wishlist.add_wishlist_item(String::from("Gift"), 10 * DAY, amount(200));
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(40));
+            assert_eq!(wishlist.fund_wish(1), Ok(()));
+            assert_eq!(wishlist.delegate(Some(accounts.charlie)), Ok(()));
+            assert_eq!(wishlist.get_voting_weight(1, accounts.bob), amount(40));
+            assert_eq!(wishlist.get_voting_weight(1, accounts.charlie), math::ZERO);
+
+            set_value_transferred(U256::from(10));
+            assert_eq!(wishlist.fund_wish(1), Ok(()));
+            assert_eq!(wishlist.get_voting_weight(1, accounts.bob), math::ZERO);
+            assert_eq!(wishlist.get_voting_weight(1, accounts.charlie), amount(50));
+        }
+
+        #[ink::test]
+        pub fn metrics_count_calls_and_value() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 10 * DAY, amount(1000));
+            set_caller(accounts.bob);
+            for amount in [20, 30] {
+                set_value_transferred(U256::from(amount));
+                assert_eq!(wishlist.fund_wish(1), Ok(()));
+            }
+
+            let metrics = wishlist.metrics();
+            assert_eq!(
+                metrics,
+                vec![
+                    (
+                        ink::selector_id!("add_wishlist_item"),
+                        CallMetrics {
+                            calls: 1,
+                            value: amount(100),
+                        }
+                    ),
+                    (
+                        ink::selector_id!("fund_wish"),
+                        CallMetrics {
+                            calls: 2,
+                            value: amount(50),
+                        }
+                    ),
+                ]
+            );
+        }
+
+        #[ink::test]
+        pub fn entry_fee_goes_to_treasury_or_is_burned() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            assert_eq!(
+                wishlist.set_entry_fee(MAX_ENTRY_FEE_BPS + 1, false),
+                Err(Error::InvalidBasisPoints)
+            );
+            assert_eq!(wishlist.set_entry_fee(100, false), Ok(()));
+            assert_eq!(wishlist.set_fee_exempt(accounts.charlie, true), Ok(()));
+            set_value_transferred(U256::from(1000));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 10 * DAY, amount(10_000));
+
+            set_caller(accounts.bob);
+            assert_eq!(wishlist.fund_wish(1), Ok(()));
+            set_caller(accounts.charlie);
+            set_value_transferred(U256::from(100));
+            assert_eq!(wishlist.fund_wish(1), Ok(()));
+            set_caller(accounts.alice);
+            assert_eq!(wishlist.fund_wish(1), Ok(()));
+            assert_eq!(wishlist.get_treasury(), amount(10));
+
+            assert_eq!(wishlist.set_entry_fee(100, true), Ok(()));
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(500));
+            assert_eq!(wishlist.fund_wish(1), Ok(()));
+
+            let item = wishlist.items.get(1).unwrap();
+            assert_eq!(
+                wishlist.get_contributors(1, 0, MAX_PAGE_SIZE),
+                vec![
+                    (accounts.bob, amount(1485)),
+                    (accounts.charlie, amount(100))
+                ]
+            );
+            assert_eq!(item.owner_topups, amount(100));
+            assert_eq!(
+                wishlist.get_entry_fee_totals(),
+                EntryFeeTotals {
+                    to_treasury: amount(10),
+                    burned: amount(5),
                 }
+            );
+            // Burned fees stay in the contract and count against its balance.
+            assert_eq!(wishlist.audit().held.burned, amount(5));
+        }
+
+        #[ink::test]
+        pub fn contributors_pull_refunds_of_failed_wishes() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 10 * DAY, amount(1000));
+            for (account, amount) in [(accounts.bob, 300), (accounts.charlie, 100)] {
+                set_caller(account);
+                set_value_transferred(U256::from(amount));
+                let _ = wishlist.fund_wish(1);
             }
+
+            set_caller(accounts.bob);
+            assert_eq!(wishlist.claim_refund(1), Err(Error::NotFailed));
+            set_block_timestamp::<ink::env::DefaultEnvironment>(10 * DAY);
+            assert_eq!(wishlist.claim_refund(1), Ok(()));
+            assert_eq!(
+                wishlist.get_settlement(1).unwrap().outcome,
+                SettlementOutcome::Refunding
+            );
+            assert_eq!(wishlist.claim_refund(1), Err(Error::NothingToWithdraw));
+            assert_eq!(wishlist.get_payout(1, accounts.charlie), amount(125));
+            set_caller(accounts.charlie);
+            assert_eq!(wishlist.claim_refund(1), Ok(()));
+            assert_eq!(wishlist.get_payout(1, accounts.charlie), math::ZERO);
+            set_caller(accounts.django);
+            assert_eq!(wishlist.claim_refund(1), Err(Error::NothingToWithdraw));
+            assert_eq!(wishlist.audit().shortfall, math::ZERO);
         }
 
-        #[ink(message)]
-        pub fn claim_wish(&mut self, id: u32) -> Result<()> {
-            // Ensure the caller is the caller
-            let caller = self.get_caller();
+        #[ink::test]
+        pub fn lists_wishes_of_each_owner() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 10 * DAY, amount(1000));
+            set_caller(accounts.bob);
+            let _ = wishlist.add_wishlist_item(String::from("Car"), 10 * DAY, amount(1000));
+            set_caller(accounts.alice);
+            let _ = wishlist.add_wishlist_item(String::from("Boat"), 10 * DAY, amount(100));
 
-            let wishlist = self.items.get(id);
-            match wishlist {
-                None => Err(Error::WishNotFound),
-                Some(item) => {
-                    let item = item.unwrap();
-                    if item.owner != caller {
-                        return Err(Error::WishNotFound);
-                    } else {
-                        let time = self.env().block_timestamp();
-                        assert!(time >= item.end_date, "Cannot claim wish before end date");
-                        assert!(item.owner == caller, "Only owner can claim wish");
-
-                        if item.raised >= item.target {
-                            let contributors_worth: U256 = item
-                                .contributors
-                                .iter()
-                                .fold(U256::zero(), |acc, cur| U256::from(acc) + cur.1);
-
-                            let result = self
-                                .env()
-                                .transfer(item.owner, item.raised + contributors_worth);
-                            match result {
-                                Ok(_) => {
-                                    self.items.set(
-                                        id,
-                                        &None::<WishListItem>,
-                                    );
-                                },
-                                Err(_) => {
-                                    return Err(Error::InvalidContribution);
-                                }
-                            }
-                            self.items.set(id, &None::<WishListItem>);
-                        } else {
-                            return Err(Error::InvalidContribution);
-                        }
+            assert_eq!(wishlist.get_user_wishes(accounts.alice), vec![1, 3]);
+            assert_eq!(wishlist.get_user_wishes(accounts.bob), vec![2]);
+            set_block_timestamp::<ink::env::DefaultEnvironment>(10 * DAY);
+            assert_eq!(wishlist.claim_wish(3), Ok(()));
+            assert_eq!(wishlist.get_user_wishes(accounts.alice), vec![1, 3]);
+            assert!(wishlist.get_user_wishes(accounts.charlie).is_empty());
+        }
 
-                        Ok(())
-                    }
-                }
+        #[ink::test]
+        pub fn pages_through_wishes() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            set_value_transferred(U256::from(100));
+            for description in ["Bike", "Car", "Boat", "Kite"] {
+                let _ =
+                    wishlist.add_wishlist_item(String::from(description), 10 * DAY, amount(1000));
             }
-        }
 
-        #[ink(message)]
-        pub fn get_wishlist_item(&self, id: u32) -> Result<Option<WishListItem>> {
-            self.items.get(id).ok_or(Error::WishNotFound)
+            let page = wishlist.get_wishes_paginated(1, 2);
+            assert_eq!(page.len(), 2);
+            assert_eq!(page[0], wishlist.get_wish_summary(2).unwrap());
+            assert_eq!(page[1], wishlist.get_wish_summary(3).unwrap());
+            assert_eq!(wishlist.get_wishes_paginated(3, 10).len(), 1);
+            assert!(wishlist.get_wishes_paginated(4, 10).is_empty());
+            assert_eq!(wishlist.get_wishes_paginated(0, u32::MAX).len(), 4);
         }
 
-        pub fn get_caller(&self) -> H160 {
-            self.env().caller()
-        }
+        #[ink::test]
+        pub fn psp22_funding_is_checked_before_pulling_tokens() {
+            let accounts = default_accounts();
+            let token = H160::from([0x22; 20]);
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 10 * DAY, amount(1000));
 
-        pub fn get_contributors_raised(&self, id: u32) -> Option<U256> {
-            let wishlist = self.items.get(id);
+            set_caller(accounts.bob);
+            assert_eq!(
+                wishlist.fund_wish_psp22(1, token, amount(50)),
+                Err(Error::TokenNotAccepted)
+            );
+            set_caller(accounts.alice);
+            assert_eq!(wishlist.set_accepted_token(token, true), Ok(()));
+            set_caller(accounts.bob);
+            assert_eq!(
+                wishlist.fund_wish_psp22(1, token, math::ZERO),
+                Err(Error::InvalidContribution)
+            );
+            assert_eq!(
+                wishlist.fund_wish_psp22(2, token, amount(50)),
+                Err(Error::WishNotFound)
+            );
 
-            match wishlist {
-                None => None, // return nothing if there is no wishlist
-                Some(item) => {
-                    if item.is_some() {
-                        let item = item.unwrap();
-                        let contributors = item.contributors;
-                        let total_raised = contributors.iter().fold(U256::zero(), |acc, curr| {
-                            acc + curr.1
-                        });
-                        return Some(total_raised);
-                    } else {
-                        return None;
-                    }
-                }
-            }
+            // The hook fired while the contract pulls tokens records nothing.
+            set_caller(token);
+            let contract = ink::env::address();
+            assert_eq!(
+                PSP22Receiver::before_received(
+                    &mut wishlist,
+                    contract,
+                    accounts.bob,
+                    amount(50),
+                    Vec::new()
+                ),
+                Ok(())
+            );
+            assert!(wishlist.get_token_pots(1).is_empty());
         }
-    }
-
-    /// Unit tests in Rust are normally defined within such a `#[cfg(test)]`
-    /// module and test functions are marked with a `#[test]` attribute.
-    /// The below code is technically just normal Rust code.
-    #[cfg(test)]
-    mod tests {
-        /// Imports all the definitions from the outer scope so we can use them here.
-        use super::*;
-        use ink::env::test::*;
 
-        /// We test if the default constructor does its job.
         #[ink::test]
-        fn default_works() {
-            let wishlist = Wishlist::default();
-            assert_eq!(wishlist.next_item_id, 1_u32);
+        pub fn split_drains_the_pot_without_double_counting() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            let contract = ink::env::address();
+            let contributors = [
+                (accounts.bob, 333),
+                (accounts.charlie, 333),
+                (accounts.django, 334),
+            ];
+            for (account, _) in contributors {
+                set_contract_balance(account, U256::from(1000));
+            }
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(1000));
+            for (account, amount) in contributors {
+                set_caller(account);
+                set_value_transferred(U256::from(amount));
+                assert_eq!(wishlist.fund_wish(1), Ok(()));
+            }
+            set_value_transferred(U256::zero());
+            // The off-chain engine does not move transferred value.
+            set_contract_balance(contract, U256::from(1100));
+
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(wishlist.split_raised_wish(1), Ok(()));
+            let mut paid = math::ZERO;
+            for (account, stake) in contributors {
+                // Each share is the stake scaled by pot / contributed, 1100 / 1000.
+                let share = amount(stake * 1100 / 1000);
+                assert_eq!(wishlist.get_payout(1, account), share);
+                set_caller(account);
+                assert_eq!(wishlist.withdraw_payout(1), Ok(()));
+                assert_eq!(wishlist.withdraw_payout(1), Err(Error::NothingToWithdraw));
+                let balance =
+                    get_contract_balance::<ink::env::DefaultEnvironment>(account).unwrap();
+                assert_eq!(balance, U256::from(1000) + math::to_native(share));
+                paid += share;
+            }
+
+            // Only rounding dust below one unit per contributor stays behind,
+            // and it is still accounted for.
+            let left = get_contract_balance::<ink::env::DefaultEnvironment>(contract).unwrap();
+            assert_eq!(math::to_native(paid) + left, U256::from(1100));
+            assert!(left < U256::from(contributors.len()));
+            let report = wishlist.audit();
+            assert_eq!(report.shortfall, math::ZERO);
+            assert_eq!(report.surplus, math::ZERO);
         }
 
-        /// The transferred_value must be greater than 10% of the target amount
         #[ink::test]
-        fn add_wishlist_value_must_match_target() {
+        pub fn claiming_emits_wish_claimed() {
+            let accounts = default_accounts();
             let mut wishlist = Wishlist::default();
-            let target = U256::from(1000);
-            let end_date = 1752797402; // Example timestamp
-            let description = String::from("Test Wishlist");
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(100));
+            set_value_transferred(U256::zero());
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(wishlist.claim_wish(1), Ok(()));
 
-            // No transfer value should fail
-            let result = wishlist.add_wishlist_item(description, end_date, target);
-            assert!(result.is_err(), "Expected error for no transfer value");
-            assert!(result.err() == Some(Error::InvalidContribution));
+            let claimed = recorded_events()
+                .into_iter()
+                .find(|e| {
+                    e.topics.first().copied() == <WishClaimed as ink::env::Event>::SIGNATURE_TOPIC
+                })
+                .expect("WishClaimed is emitted");
+            let event =
+                <WishClaimed as ink::scale::Decode>::decode(&mut &claimed.data[..]).unwrap();
+            assert_eq!(event.id, 1);
+            assert_eq!(event.owner, accounts.alice);
+            assert_eq!(event.amount, amount(100));
+            assert_eq!(event.timestamp, 1000);
+        }
 
-            // Pass value less that 10% target
-            let description = String::from("Test < 10% Wishlist");
-            let transfer_value = U256::from(50);
-            set_value_transferred(transfer_value);
-            let result = wishlist.add_wishlist_item(description, end_date, target);
-            assert!(
-                result.is_err(),
-                "Expected error for insufficient transfer value"
-            );
-            assert!(result.err() == Some(Error::InvalidContribution));
+        #[ink::test]
+        pub fn split_emits_refund_events() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(1000));
+            for (account, amount) in [(accounts.bob, 30), (accounts.charlie, 70)] {
+                set_caller(account);
+                set_value_transferred(U256::from(amount));
+                let _ = wishlist.fund_wish(1);
+            }
+            set_value_transferred(U256::zero());
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(wishlist.split_raised_wish(1), Ok(()));
 
-            let description = String::from("Test >= 10% Wishlist");
-            let transfer_value = U256::from(100);
-            set_value_transferred(transfer_value);
-            let result = wishlist.add_wishlist_item(description, end_date, target);
-            assert!(
-                result.is_ok(),
-                "Expected successful addition of wishlist item"
+            let events = recorded_events();
+            let refunds = events
+                .iter()
+                .filter(|e| {
+                    e.topics.first().copied() == <RefundIssued as ink::env::Event>::SIGNATURE_TOPIC
+                })
+                .map(|e| <RefundIssued as ink::scale::Decode>::decode(&mut &e.data[..]).unwrap())
+                .map(|event| (event.contributor, event.amount))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                refunds,
+                vec![
+                    (accounts.bob, wishlist.get_payout(1, accounts.bob)),
+                    (accounts.charlie, wishlist.get_payout(1, accounts.charlie)),
+                ]
             );
-            assert_eq!(wishlist.next_item_id, 2_u32);
+            let split = events
+                .iter()
+                .find(|e| {
+                    e.topics.first().copied() == <WishSplit as ink::env::Event>::SIGNATURE_TOPIC
+                })
+                .map(|e| <WishSplit as ink::scale::Decode>::decode(&mut &e.data[..]).unwrap())
+                .expect("WishSplit is emitted");
+            assert_eq!(split.id, 1);
+            assert_eq!(split.total_refunded, amount(200));
         }
 
         #[ink::test]
-        pub fn add_wishlist_should_succeed() {
-            let mut contract = Wishlist::default();
-            let description = String::from("Add Wishlist should succeed");
-            let end_date = 1752798324779;
-            let target = U256::from(1000);
+        pub fn owners_can_cancel_before_the_end_date() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(1000));
+            let _ = wishlist.add_wishlist_item(String::from("Car"), 1000, amount(1000));
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(30));
+            let _ = wishlist.fund_wish(1);
+            set_value_transferred(U256::zero());
 
-            set_value_transferred(U256::from(115));
-            let result = contract.add_wishlist_item(description, end_date, target);
+            assert_eq!(wishlist.cancel_wish(1), Err(Error::NotOwner));
+            set_caller(accounts.alice);
+            assert_eq!(wishlist.cancel_wish(1), Ok(()));
+            assert_eq!(wishlist.get_credits(accounts.alice), amount(100));
+            assert_eq!(
+                wishlist.get_settlement(1).unwrap().outcome,
+                SettlementOutcome::Cancelled
+            );
+            assert_eq!(wishlist.cancel_wish(1), Err(Error::WishNotFound));
 
-            assert!(result.is_ok(), "is should be Ok");
-            assert_eq!(contract.next_item_id, 2_u32);
-            let item = contract.get_wishlist_item(0);
-            assert!(item.is_ok(), "Item should be found");
-            assert_eq!(item.unwrap().unwrap().raised, U256::from(115));
+            set_caller(accounts.bob);
+            assert_eq!(wishlist.get_payout(1, accounts.bob), amount(30));
+            assert_eq!(wishlist.claim_refund(1), Ok(()));
+            assert_eq!(wishlist.audit().shortfall, math::ZERO);
+
+            set_caller(accounts.alice);
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(wishlist.cancel_wish(2), Err(Error::WishEnded));
         }
 
         #[ink::test]
-        pub fn fund_wish_should_succeed() {
+        pub fn wish_status_follows_the_lifecycle() {
+            let accounts = default_accounts();
             let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            for (description, target) in [("Bike", 200), ("Car", 1000), ("Boat", 1000)] {
+                let _ = wishlist.add_wishlist_item(String::from(description), 1000, amount(target));
+            }
+            assert_eq!(wishlist.get_wish_status(1), Some(WishStatus::Active));
+            assert_eq!(wishlist.get_wish_status(4), None);
 
-            set_value_transferred(U256::from(125));
-            set_caller(default_accounts().alice);
-            let _ = wishlist.add_wishlist_item(
-                String::from("Wishlist Item 1"),
-                1752798324779,
-                U256::from(1000),
+            assert_eq!(wishlist.fund_wish(1), Ok(()));
+            assert_eq!(wishlist.get_wish_status(1), Some(WishStatus::Funded));
+            assert_eq!(
+                wishlist.get_wish_summary(1).unwrap().status,
+                WishStatus::Funded
             );
-
-            // value_transferred mjst not be 0
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(30));
+            assert_eq!(wishlist.fund_wish(2), Ok(()));
+            assert_eq!(wishlist.get_wish_status(2), Some(WishStatus::Active));
+            assert_eq!(wishlist.fund_wish(1), Ok(()));
             set_value_transferred(U256::zero());
-            let result = wishlist.fund_wish(0);
-            assert!(result.is_err(), "Funding will not succeed");
-            assert_eq!(result.err(), Some(Error::InvalidContribution));
+            set_caller(accounts.alice);
+            assert_eq!(wishlist.cancel_wish(3), Ok(()));
+            assert_eq!(wishlist.get_wish_status(3), Some(WishStatus::Cancelled));
 
-            // ID must exist
-            set_value_transferred(U256::from(10));
-            let result = wishlist.fund_wish(1);
-            assert!(result.is_err(), "ID must exist");
-            assert_eq!(result.err(), Some(Error::WishNotFound));
+            // A funded wish is the owner's to claim, not the contributors'
+            // to share out.
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            set_caller(accounts.bob);
+            assert_eq!(wishlist.split_raised_wish(1), Err(Error::NotFailed));
+            assert_eq!(wishlist.get_wish_status(1), Some(WishStatus::Funded));
+            set_caller(accounts.alice);
+            assert_eq!(wishlist.claim_wish(1), Ok(()));
+            assert_eq!(wishlist.get_wish_status(1), Some(WishStatus::Claimed));
+            set_caller(accounts.bob);
+            assert_eq!(wishlist.split_raised_wish(2), Ok(()));
+            assert_eq!(wishlist.get_wish_status(2), Some(WishStatus::Refunded));
+        }
+
+        #[ink::test]
+        pub fn contributions_are_kept_per_account() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(1000));
+            for (account, amount) in [
+                (accounts.bob, 30),
+                (accounts.charlie, 70),
+                (accounts.bob, 20),
+            ] {
+                set_caller(account);
+                set_value_transferred(U256::from(amount));
+                assert_eq!(wishlist.fund_wish(1), Ok(()));
+            }
+            set_value_transferred(U256::zero());
+            assert_eq!(wishlist.get_contribution(1, accounts.bob), amount(50));
+            let item = wishlist.items.get(1).unwrap();
+            assert_eq!(item.contributor_count, 2);
+            assert_eq!(item.contributed, amount(120));
 
+            // Leaving and coming back keeps a single entry per account.
+            set_caller(accounts.bob);
+            assert_eq!(wishlist.exit_contribution(1), Ok(()));
+            assert_eq!(wishlist.items.get(1).unwrap().contributor_count, 1);
             set_value_transferred(U256::from(10));
-            let result = wishlist.fund_wish(0);
-            assert!(result.is_ok(), "Funding should succeed");
-            assert_eq!(wishlist.next_item_id, 2_u32);
+            assert_eq!(wishlist.fund_wish(1), Ok(()));
             assert_eq!(
-                wishlist.get_wishlist_item(0).unwrap().unwrap().raised,
-                U256::from(135)
+                wishlist.get_contributors(1, 0, MAX_PAGE_SIZE),
+                vec![(accounts.bob, amount(10)), (accounts.charlie, amount(70))]
             );
+            let item = wishlist.items.get(1).unwrap();
+            assert_eq!(item.contributor_count, 2);
+            assert_eq!(item.contributor_slots, 2);
+            assert_eq!(item.contributed, amount(80));
+        }
 
-            set_caller(default_accounts().bob);
-            let result = wishlist.fund_wish(0);
-            assert!(result.is_ok(), "Funding should succeed");
+        /// Drive a wish from creation to claim through nothing but
+        /// `WishlistCore`, as an integrating contract would.
+        #[ink::test]
+        pub fn wishlist_core_covers_the_lifecycle() {
+            let accounts = default_accounts();
+            let mut wishlist = Wishlist::default();
+            set_caller(accounts.alice);
+            set_value_transferred(U256::from(100));
+            let description = String::from("Bike");
             assert_eq!(
-                wishlist.get_wishlist_item(0).unwrap().unwrap().raised,
-                U256::from(135)
+                WishlistCore::add_wishlist_item(&mut wishlist, description, 1000, amount(100)),
+                Ok(())
             );
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(40));
+            assert_eq!(WishlistCore::fund_wish(&mut wishlist, 1), Ok(()));
             assert_eq!(
-                wishlist
-                    .get_wishlist_item(0)
-                    .unwrap()
-                    .unwrap()
-                    .contributors
-                    .len(),
-                1
+                WishlistCore::get_contribution(&wishlist, 1, accounts.bob),
+                amount(40)
             );
             assert_eq!(
-                wishlist.get_wishlist_item(0).unwrap().unwrap().contributors[0].0,
-                default_accounts().bob
+                WishlistCore::get_wish_status(&wishlist, 1),
+                Some(WishStatus::Funded)
+            );
+
+            set_value_transferred(U256::zero());
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            set_caller(accounts.alice);
+            assert_eq!(WishlistCore::claim_wish(&mut wishlist, 1), Ok(()));
+            assert!(matches!(
+                WishlistCore::get_wishlist_item(&wishlist, 1),
+                Ok(WishEntry::Settled(_))
+            ));
+            assert_eq!(
+                WishlistCore::claim_refund(&mut wishlist, 1),
+                Err(Error::NothingToWithdraw)
             );
         }
 
         #[ink::test]
-        pub fn claim_wish_fail_for_error_conditions() {
+        pub fn admin_is_handed_over_in_two_steps() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
             let mut wishlist = Wishlist::default();
+            set_caller(accounts.bob);
+            assert_eq!(wishlist.propose_admin(accounts.bob), Err(Error::OnlyAdmin));
+            assert_eq!(wishlist.accept_admin(), Err(Error::NotPendingAdmin));
 
-            set_caller(default_accounts().alice);
-            set_value_transferred(U256::from(120));
-            let _ = wishlist.add_wishlist_item(
-                String::from("Wishlist Item"),
-                1752800402,
-                U256::from(1000),
+            set_caller(accounts.alice);
+            assert_eq!(wishlist.propose_admin(accounts.charlie), Ok(()));
+            assert_eq!(wishlist.propose_admin(accounts.bob), Ok(()));
+            assert_eq!(wishlist.get_pending_admin(), Some(accounts.bob));
+            assert_eq!(wishlist.get_admin(), accounts.alice);
+
+            set_caller(accounts.charlie);
+            assert_eq!(wishlist.accept_admin(), Err(Error::NotPendingAdmin));
+            set_caller(accounts.bob);
+            assert_eq!(wishlist.accept_admin(), Ok(()));
+            assert_eq!(wishlist.get_admin(), accounts.bob);
+            assert_eq!(wishlist.get_pending_admin(), None);
+            assert_eq!(wishlist.set_arbiter(None), Ok(()));
+            set_caller(accounts.alice);
+            assert_eq!(wishlist.set_arbiter(None), Err(Error::OnlyAdmin));
+
+            let changed: Vec<AdminChanged> = recorded_events()
+                .into_iter()
+                .filter(|e| {
+                    e.topics[0] == <AdminChanged as ink::env::Event>::SIGNATURE_TOPIC.unwrap()
+                })
+                .map(|e| <AdminChanged as ink::scale::Decode>::decode(&mut &e.data[..]).unwrap())
+                .collect();
+            assert_eq!(changed.len(), 1);
+            assert_eq!(changed[0].previous, accounts.alice);
+            assert_eq!(changed[0].admin, accounts.bob);
+        }
+
+        #[ink::test]
+        pub fn pausing_stops_new_money_but_not_payouts() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut wishlist = Wishlist::default();
+            set_value_transferred(U256::from(100));
+            assert_eq!(
+                wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(100)),
+                Ok(())
             );
             set_value_transferred(U256::from(100));
-            let _ = wishlist.fund_wish(0);
+            assert_eq!(
+                wishlist.add_wishlist_item(String::from("Car"), 1000, amount(1000)),
+                Ok(())
+            );
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(50));
+            assert_eq!(wishlist.fund_wish(2), Ok(()));
+            set_value_transferred(U256::zero());
+            assert_eq!(wishlist.pause(), Err(Error::OnlyAdmin));
 
-            set_caller(default_accounts().alice);
-            advance_block::<ink::env::DefaultEnvironment>();
-            set_block_timestamp::<ink::env::DefaultEnvironment>(1752800500);
-            let result = wishlist.claim_wish(0);
-            assert!(result.is_err(), "Claiming wish should fail");
-            assert_eq!(result.err(), Some(Error::InvalidContribution));
+            set_caller(accounts.alice);
+            assert_eq!(wishlist.pause(), Ok(()));
+            assert!(wishlist.is_paused());
+            set_value_transferred(U256::from(100));
+            assert_eq!(
+                wishlist.add_wishlist_item(String::from("Boat"), 1000, amount(100)),
+                Err(Error::ContractPaused)
+            );
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(10));
+            assert_eq!(wishlist.fund_wish(1), Err(Error::ContractPaused));
+
+            set_value_transferred(U256::zero());
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            set_contract_balance(ink::env::address(), U256::from(250));
+            assert_eq!(wishlist.claim_refund(2), Ok(()));
+            set_caller(accounts.alice);
+            assert_eq!(wishlist.claim_wish(1), Ok(()));
+
+            assert_eq!(wishlist.unpause(), Ok(()));
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(100));
+            assert_eq!(
+                wishlist.add_wishlist_item(String::from("Boat"), 2000, amount(100)),
+                Ok(())
+            );
         }
 
         #[ink::test]
-        pub fn claim_wish_should_succeed() {
-            let mut wishlist = Wishlist::default();
-            set_caller(default_accounts().alice);
-            set_value_transferred(U256::from(2));
-            let _ = wishlist.add_wishlist_item(
-                String::from("Wishlist Item"),
-                1752800402,
-                U256::from(3)
+        pub fn claim_fee_is_kept_until_withdrawn() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            assert!(matches!(
+                Wishlist::with_claim_fee(MAX_CLAIM_FEE_BPS + 1),
+                Err(Error::InvalidBasisPoints)
+            ));
+            let mut wishlist = Wishlist::with_claim_fee(500).unwrap();
+            assert_eq!(wishlist.get_claim_fee(), 500);
+            set_value_transferred(U256::from(100));
+            let _ = wishlist.add_wishlist_item(String::from("Bike"), 1000, amount(100));
+            set_caller(accounts.bob);
+            set_value_transferred(U256::from(100));
+            assert_eq!(wishlist.fund_wish(1), Ok(()));
+            assert_eq!(wishlist.set_claim_fee(100), Err(Error::OnlyAdmin));
+
+            let claimable = wishlist.get_claimable(1).unwrap();
+            assert_eq!(claimable.fee, amount(10));
+            assert_eq!(claimable.net, amount(190));
+            set_value_transferred(U256::zero());
+            set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(wishlist.simulate_settlement(1).unwrap().fees, amount(10));
+
+            let balance_of =
+                |account| get_contract_balance::<ink::env::DefaultEnvironment>(account).unwrap();
+            set_contract_balance(ink::env::address(), U256::from(200));
+            set_caller(accounts.alice);
+            let before = balance_of(accounts.alice);
+            assert_eq!(wishlist.claim_wish(1), Ok(()));
+            assert_eq!(balance_of(accounts.alice) - before, U256::from(190));
+            assert_eq!(wishlist.get_accumulated_fees(), amount(10));
+            assert_eq!(wishlist.audit().shortfall, math::ZERO);
+
+            set_caller(accounts.bob);
+            assert_eq!(wishlist.withdraw_fees(accounts.bob), Err(Error::OnlyAdmin));
+            set_caller(accounts.alice);
+            let before = balance_of(accounts.charlie);
+            assert_eq!(wishlist.withdraw_fees(accounts.charlie), Ok(()));
+            assert_eq!(balance_of(accounts.charlie) - before, U256::from(10));
+            assert_eq!(wishlist.get_accumulated_fees(), math::ZERO);
+            assert_eq!(
+                wishlist.withdraw_fees(accounts.charlie),
+                Err(Error::NothingToWithdraw)
             );
 
-            advance_block::<ink::env::DefaultEnvironment>();
-            set_caller(default_accounts().alice);
-            set_value_transferred(U256::from(2));
-            let _ = wishlist.fund_wish(0);
+            assert_eq!(
+                wishlist.set_claim_fee(MAX_CLAIM_FEE_BPS + 1),
+                Err(Error::InvalidBasisPoints)
+            );
+            assert_eq!(wishlist.set_claim_fee(0), Ok(()));
+            assert_eq!(wishlist.get_claim_fee(), 0);
 
-            advance_block::<ink::env::DefaultEnvironment>();
-            set_block_timestamp::<ink::env::DefaultEnvironment>(1752800500);
-            let result = wishlist.claim_wish(0);
-            assert!(result.is_ok(), "Claiming wish should succeed");
-            assert_eq!(wishlist.next_item_id, 2_u32);
+            let charged: Vec<ClaimFeeCharged> = recorded_events()
+                .into_iter()
+                .filter(|e| {
+                    e.topics[0] == <ClaimFeeCharged as ink::env::Event>::SIGNATURE_TOPIC.unwrap()
+                })
+                .map(|e| <ClaimFeeCharged as ink::scale::Decode>::decode(&mut &e.data[..]).unwrap())
+                .collect();
+            assert_eq!(charged.len(), 1);
+            assert_eq!(charged[0].fee, amount(10));
+        }
+    }
+
+    /// Tests that run the compiled contract in an in-process runtime, so
+    /// cross-contract calls and balance transfers are real without starting a
+    /// node. Enable with `--features sandbox-tests`; they need the `ink_sandbox`
+    /// crate, ink! 6's successor to drink!, as a dev-dependency.
+    #[cfg(all(test, feature = "sandbox-tests"))]
+    mod sandbox_tests {
+        use super::*;
+        use ink_e2e::ContractsBackend;
+
+        type SandboxResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        #[ink_e2e::test(backend(runtime_only(
+            sandbox = ink_sandbox::DefaultSandbox,
+            client = ink_sandbox::SandboxClient
+        )))]
+        async fn split_transfers_real_balances<Client: E2EBackend>(
+            mut client: Client,
+        ) -> SandboxResult<()> {
+            let mut constructor = WishlistRef::with_clock(Clock::Fixed(0));
+            let contract = client
+                .instantiate("wishlist", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("instantiate failed");
+            let mut call_builder = contract.call_builder::<Wishlist>();
+
+            let create =
+                call_builder.add_wishlist_item(String::from("Bike"), DAY, Amount::from(1_000u16));
+            client
+                .call(&ink_e2e::bob(), &create)
+                .value(100)
+                .submit()
+                .await
+                .expect("create failed");
+
+            let fund = call_builder.fund_wish(1);
+            client
+                .call(&ink_e2e::charlie(), &fund)
+                .value(500)
+                .submit()
+                .await
+                .expect("fund failed");
+
+            let past_deadline = call_builder.set_clock(DAY);
+            client
+                .call(&ink_e2e::alice(), &past_deadline)
+                .submit()
+                .await
+                .expect("set_clock failed");
+            let split = call_builder.split_raised_wish(1);
+            client
+                .call(&ink_e2e::charlie(), &split)
+                .submit()
+                .await
+                .expect("split failed");
+
+            let charlie = ink_e2e::account_id(ink_e2e::Sr25519Keyring::Charlie);
+            let before = client.free_balance(charlie).await?;
+            let withdraw = call_builder.withdraw_payout(1);
+            client
+                .call(&ink_e2e::charlie(), &withdraw)
+                .submit()
+                .await
+                .expect("withdraw failed");
+            let after = client.free_balance(charlie).await?;
+            assert!(after > before, "payout did not reach charlie");
+
+            let audit = call_builder.audit();
+            let report = client
+                .call(&ink_e2e::alice(), &audit)
+                .dry_run()
+                .await?
+                .return_value();
+            assert_eq!(report.shortfall, math::ZERO);
+            Ok(())
+        }
+
+        #[ink_e2e::test(backend(runtime_only(
+            sandbox = ink_sandbox::DefaultSandbox,
+            client = ink_sandbox::SandboxClient
+        )))]
+        async fn unreachable_verifier_blocks_creation<Client: E2EBackend>(
+            mut client: Client,
+        ) -> SandboxResult<()> {
+            let mut constructor = WishlistRef::default();
+            let contract = client
+                .instantiate("wishlist", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("instantiate failed");
+            let mut call_builder = contract.call_builder::<Wishlist>();
+
+            // A plain account cannot answer `has_attestation`, so the hook
+            // must fail closed.
+            let verifier =
+                ink_e2e::address::<ink::env::DefaultEnvironment>(ink_e2e::Sr25519Keyring::Dave);
+            let set = call_builder.set_verifier(AttestationPurpose::Kyc, Some(verifier));
+            client
+                .call(&ink_e2e::alice(), &set)
+                .submit()
+                .await
+                .expect("set_verifier failed");
+
+            let create = call_builder.add_wishlist_item(
+                String::from("Bike"),
+                u64::MAX,
+                Amount::from(1_000u16),
+            );
+            let result = client
+                .call(&ink_e2e::bob(), &create)
+                .value(100)
+                .dry_run()
+                .await?
+                .return_value();
+            assert_eq!(result, Err(Error::AttestationRequired));
+            Ok(())
+        }
+    }
+
+    /// End-to-end tests against a running node, so payable calls and
+    /// transfers move real balances. Enable with `--features e2e-tests`; they
+    /// spawn the node named by `CONTRACTS_NODE`, or use the one at
+    /// `CONTRACTS_NODE_URL`.
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink_e2e::{ChainBackend, ContractsBackend, Sr25519Keyring};
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        /// How long the wishes in these tests stay open.
+        const OPEN_FOR_MS: u64 = 3_000;
+
+        /// An end date `OPEN_FOR_MS` from now. The node stamps blocks with the
+        /// wall clock.
+        fn end_date() -> u64 {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .expect("clock is past the epoch");
+            now.as_millis() as u64 + OPEN_FOR_MS
+        }
+
+        async fn wait_for_end_date() {
+            let wait = std::time::Duration::from_millis(2 * OPEN_FOR_MS);
+            ink_e2e::tokio::time::sleep(wait).await;
+        }
+
+        #[ink_e2e::test]
+        async fn claim_pays_the_owner<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+            let mut constructor = WishlistRef::default();
+            let contract = client
+                .instantiate("wishlist", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("instantiate failed");
+            let mut call_builder = contract.call_builder::<Wishlist>();
+
+            // Bob's seed alone meets the target.
+            let create =
+                call_builder.add_wishlist_item(String::from("Bike"), end_date(), math::ONE);
+            client
+                .call(&ink_e2e::bob(), &create)
+                .value(1_000)
+                .submit()
+                .await
+                .expect("create failed");
+
+            let charlie = ink_e2e::account_id(Sr25519Keyring::Charlie);
+            let before = client.free_balance(charlie).await?;
+            let fund = call_builder.fund_wish(1);
+            client
+                .call(&ink_e2e::charlie(), &fund)
+                .value(5_000)
+                .submit()
+                .await
+                .expect("fund failed");
+            let after = client.free_balance(charlie).await?;
+            assert!(
+                before - after >= 5_000,
+                "contribution did not leave charlie"
+            );
+
+            // Alice claims as Bob's operator, so Bob pays no fees and his
+            // balance moves by exactly the payout.
+            let alice = ink_e2e::address::<ink::env::DefaultEnvironment>(Sr25519Keyring::Alice);
+            let approve = call_builder.approve_operator(alice, true);
+            client
+                .call(&ink_e2e::bob(), &approve)
+                .submit()
+                .await
+                .expect("approve failed");
+            wait_for_end_date().await;
+
+            let bob = ink_e2e::account_id(Sr25519Keyring::Bob);
+            let before = client.free_balance(bob).await?;
+            let claim = call_builder.claim_wish(1);
+            client
+                .call(&ink_e2e::alice(), &claim)
+                .submit()
+                .await
+                .expect("claim failed");
+            let after = client.free_balance(bob).await?;
+            assert_eq!(after - before, 6_000);
+
+            let status = call_builder.get_wish_status(1);
+            let status = client
+                .call(&ink_e2e::alice(), &status)
+                .dry_run()
+                .await?
+                .return_value();
+            assert_eq!(status, Some(WishStatus::Claimed));
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn failed_wish_refunds_contributors<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            let mut constructor = WishlistRef::default();
+            let contract = client
+                .instantiate("wishlist", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("instantiate failed");
+            let mut call_builder = contract.call_builder::<Wishlist>();
+
+            // Learn how a transferred value reads inside the contract, so the
+            // target can be set just above Bob's seed.
+            let deposit = call_builder.deposit();
+            client
+                .call(&ink_e2e::dave(), &deposit)
+                .value(1_000)
+                .submit()
+                .await
+                .expect("deposit failed");
+            let dave = ink_e2e::address::<ink::env::DefaultEnvironment>(Sr25519Keyring::Dave);
+            let get_deposit = call_builder.get_deposit(dave);
+            let seed = client
+                .call(&ink_e2e::dave(), &get_deposit)
+                .dry_run()
+                .await?
+                .return_value();
+
+            let create = call_builder.add_wishlist_item(String::from("Bike"), end_date(), seed * 2);
+            client
+                .call(&ink_e2e::bob(), &create)
+                .value(1_000)
+                .submit()
+                .await
+                .expect("create failed");
+            let fund = call_builder.fund_wish(1);
+            client
+                .call(&ink_e2e::charlie(), &fund)
+                .value(5_000)
+                .submit()
+                .await
+                .expect("fund failed");
+
+            // Charlie's refund goes to Eve, who pays no fees, so her balance
+            // moves by exactly the refund.
+            let eve = ink_e2e::address::<ink::env::DefaultEnvironment>(Sr25519Keyring::Eve);
+            let route = call_builder.set_payout_address(Some(eve));
+            client
+                .call(&ink_e2e::charlie(), &route)
+                .submit()
+                .await
+                .expect("set_payout_address failed");
+            wait_for_end_date().await;
+
+            let eve = ink_e2e::account_id(Sr25519Keyring::Eve);
+            let before = client.free_balance(eve).await?;
+            let refund = call_builder.claim_refund(1);
+            client
+                .call(&ink_e2e::charlie(), &refund)
+                .submit()
+                .await
+                .expect("claim_refund failed");
+            let after = client.free_balance(eve).await?;
+            // The sole contributor is refunded the owner's forfeited seed too.
+            assert_eq!(after - before, 6_000);
+
+            let audit = call_builder.audit();
+            let report = client
+                .call(&ink_e2e::alice(), &audit)
+                .dry_run()
+                .await?
+                .return_value();
+            assert_eq!(report.shortfall, math::ZERO);
+            Ok(())
         }
     }
 }